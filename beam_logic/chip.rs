@@ -0,0 +1,265 @@
+use anyhow::{Result, bail};
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+
+use common::{direction::Direction, map::Map};
+
+use crate::tile::Tile;
+
+/// A board wrapped up as a black box so it can be placed as a single tile on
+/// another board. Beams entering an input side power one of the inner
+/// emitters and powered inner detectors send a beam out of their output side.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Chip {
+    pub name: String,
+    pub tiles: Map<Tile>,
+    /// Chips used by the inner board, indexed by the `index` of its
+    /// `Tile::Chip`s. This lets chips be nested inside of other chips.
+    pub chips: Vec<Chip>,
+    /// The port on each side of an unrotated chip, indexed by `Direction`.
+    pub ports: [Option<Port>; 4],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Port {
+    pub kind: PortKind,
+    /// Position of the port's emitter or detector on the inner board.
+    pub pos: Vector2<i32>,
+    /// Shown on the side of the chip, like `I0` for the first input. The
+    /// number is the dynamic id of the emitter or detector if it has one.
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PortKind {
+    /// Beams entering this side activate the port's emitter.
+    Input,
+    /// A beam will leave this side while the port's detector is powered.
+    Output,
+}
+
+/// Inputs fill the sides clockwise starting from the left and outputs fill
+/// the sides clockwise starting from the right, so a chip with up to two of
+/// each will have its inputs on the left and top and outputs on the right and
+/// bottom.
+const INPUT_SIDES: [Direction; 4] = [
+    Direction::Left,
+    Direction::Up,
+    Direction::Right,
+    Direction::Down,
+];
+const OUTPUT_SIDES: [Direction; 4] = [
+    Direction::Right,
+    Direction::Down,
+    Direction::Left,
+    Direction::Up,
+];
+
+impl Chip {
+    /// Creates a chip from a board. The ports are taken from the board's
+    /// emitters and detectors, ordered by their dynamic id. If the board has
+    /// no dynamic tiles (like most sandboxes), every emitter and detector is
+    /// used instead, ordered by position, and numbered in that order.
+    pub fn new(name: String, tiles: Map<Tile>, chips: Vec<Chip>) -> Result<Self> {
+        let (mut inputs, mut outputs) = (Vec::new(), Vec::new());
+        let dynamic = tiles.iter().any(|(_, tile)| tile.id().is_some());
+
+        for (pos, tile) in tiles.iter() {
+            if dynamic && tile.id().is_none() {
+                continue;
+            }
+
+            let key = (tile.id(), pos.y, pos.x);
+            match tile {
+                Tile::Emitter { .. } => inputs.push((key, pos)),
+                Tile::Detector { .. } => outputs.push((key, pos)),
+                _ => {}
+            }
+        }
+
+        if inputs.len() + outputs.len() > 4 {
+            bail!("Chips can have at most four ports (one per side)");
+        }
+
+        inputs.sort_by_key(|(key, _)| *key);
+        outputs.sort_by_key(|(key, _)| *key);
+
+        let mut ports = [const { None }; 4];
+        for (kind, tiles, sides) in [
+            (PortKind::Input, inputs, INPUT_SIDES),
+            (PortKind::Output, outputs, OUTPUT_SIDES),
+        ] {
+            let prefix = match kind {
+                PortKind::Input => "I",
+                PortKind::Output => "O",
+            };
+            for (i, ((id, ..), pos)) in tiles.into_iter().enumerate() {
+                let side = sides.iter().find(|x| ports[**x as usize].is_none());
+                ports[*side.unwrap() as usize] = Some(Port {
+                    kind,
+                    pos,
+                    name: format!("{prefix}{}", id.unwrap_or(i as u32)),
+                });
+            }
+        }
+
+        Ok(Self {
+            name,
+            tiles,
+            chips,
+            ports,
+        })
+    }
+
+    /// Returns the port on the given side of a chip with the given rotation.
+    pub fn port(&self, rotation: Direction, side: Direction) -> Option<&Port> {
+        self.ports[unrotated_side(rotation, side) as usize].as_ref()
+    }
+
+    /// The total price of all tiles within the chip, including any nested
+    /// chips.
+    pub fn price(&self) -> u32 {
+        (self.tiles.iter())
+            .map(|(_, tile)| tile.price_with_chips(&self.chips))
+            .sum()
+    }
+}
+
+/// Converts a side of a rotated chip into the matching side of the unrotated
+/// chip, which is how ports are stored.
+pub fn unrotated_side(rotation: Direction, mut side: Direction) -> Direction {
+    for _ in 0..rotation as u8 {
+        side = side.rotate_reverse();
+    }
+
+    side
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{direction::Direction, map::Map};
+    use nalgebra::Vector2;
+
+    use crate::tile::Tile;
+
+    use super::{Chip, Port, PortKind};
+
+    fn emitter(id: Option<u32>) -> Tile {
+        Tile::Emitter {
+            rotation: Direction::Right,
+            active: false,
+            id,
+        }
+    }
+
+    fn board(tiles: &[((i32, i32), Tile)]) -> Map<Tile> {
+        let mut board = Map::default();
+        for &((x, y), tile) in tiles {
+            board.set(Vector2::new(x, y), tile);
+        }
+        board
+    }
+
+    fn port(kind: PortKind, (x, y): (i32, i32), name: &str) -> Option<Port> {
+        Some(Port {
+            kind,
+            pos: Vector2::new(x, y),
+            name: name.into(),
+        })
+    }
+
+    #[test]
+    fn ports_fill_sides_by_position() {
+        let tiles = board(&[
+            ((0, 1), emitter(None)),
+            ((0, 0), emitter(None)),
+            ((3, 0), Tile::Detector { id: None }),
+            ((3, 1), Tile::Detector { id: None }),
+        ]);
+        let chip = Chip::new("Test".into(), tiles, Vec::new()).unwrap();
+
+        assert_eq!(
+            chip.ports,
+            [
+                port(PortKind::Input, (0, 1), "I1"),
+                port(PortKind::Output, (3, 0), "O0"),
+                port(PortKind::Output, (3, 1), "O1"),
+                port(PortKind::Input, (0, 0), "I0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn ports_use_dynamic_ids() {
+        // Tiles without an id are ignored once the board has dynamic tiles
+        let tiles = board(&[
+            ((0, 0), emitter(Some(5))),
+            ((0, 1), emitter(Some(2))),
+            ((1, 0), emitter(None)),
+            ((3, 0), Tile::Detector { id: Some(7) }),
+        ]);
+        let chip = Chip::new("Test".into(), tiles, Vec::new()).unwrap();
+
+        assert_eq!(
+            chip.ports,
+            [
+                port(PortKind::Input, (0, 0), "I5"),
+                port(PortKind::Output, (3, 0), "O7"),
+                None,
+                port(PortKind::Input, (0, 1), "I2"),
+            ]
+        );
+    }
+
+    #[test]
+    fn too_many_ports() {
+        let tiles = board(&[
+            ((0, 0), emitter(None)),
+            ((0, 1), emitter(None)),
+            ((0, 2), emitter(None)),
+            ((3, 0), Tile::Detector { id: None }),
+            ((3, 1), Tile::Detector { id: None }),
+        ]);
+        assert!(Chip::new("Test".into(), tiles, Vec::new()).is_err());
+    }
+
+    #[test]
+    fn rotated_ports() {
+        let tiles = board(&[
+            ((0, 0), emitter(None)),
+            ((3, 0), Tile::Detector { id: None }),
+        ]);
+        let chip = Chip::new("Test".into(), tiles, Vec::new()).unwrap();
+        let kind = |rotation, side| chip.port(rotation, side).map(|x| x.kind);
+
+        assert_eq!(kind(Direction::Up, Direction::Left), Some(PortKind::Input));
+        assert_eq!(kind(Direction::Right, Direction::Up), Some(PortKind::Input));
+        assert_eq!(
+            kind(Direction::Right, Direction::Down),
+            Some(PortKind::Output)
+        );
+        assert_eq!(
+            kind(Direction::Down, Direction::Right),
+            Some(PortKind::Input)
+        );
+        assert_eq!(
+            kind(Direction::Left, Direction::Down),
+            Some(PortKind::Input)
+        );
+        assert_eq!(kind(Direction::Left, Direction::Left), None);
+    }
+
+    #[test]
+    fn price_includes_nested_chips() {
+        let inner = Chip::new("Inner".into(), board(&[((0, 0), Tile::Delay)]), Vec::new());
+        let inner = inner.unwrap();
+        let chip_tile = Tile::Chip {
+            rotation: Direction::Up,
+            index: 0,
+        };
+        let outer = board(&[((0, 0), chip_tile), ((1, 0), Tile::Delay)]);
+        let outer = Chip::new("Outer".into(), outer, vec![inner.clone()]).unwrap();
+
+        assert_eq!(outer.price(), inner.price() * 2);
+    }
+}
@@ -0,0 +1,213 @@
+use ahash::{HashMap, HashMapExt, HashSet};
+use nalgebra::Vector2;
+
+use common::map::Map;
+
+use crate::tile::Tile;
+
+/// The tile changes needed to turn one board into another.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Diff {
+    pub changes: HashMap<Vector2<i32>, Change>,
+    /// Positions that were changed differently on both sides of a merge,
+    /// where the current tile was kept.
+    pub conflicts: HashSet<Vector2<i32>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Added(Tile),
+    Removed(Tile),
+    Changed { old: Tile, new: Tile },
+}
+
+impl Diff {
+    pub fn new(old: &Map<Tile>, new: &Map<Tile>) -> Self {
+        let mut changes = HashMap::new();
+
+        for (pos, tile) in old.iter() {
+            let new = new.get(pos);
+            if new.is_empty() {
+                changes.insert(pos, Change::Removed(tile));
+            } else if new != tile {
+                changes.insert(pos, Change::Changed { old: tile, new });
+            }
+        }
+
+        for (pos, tile) in new.iter() {
+            if old.get(pos).is_empty() {
+                changes.insert(pos, Change::Added(tile));
+            }
+        }
+
+        Self {
+            changes,
+            conflicts: HashSet::default(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Applies the changes to another board. If a tile on the board doesn't
+    /// match the old or new version of a change, it was changed differently
+    /// on both sides. These conflicting tiles are left alone and their
+    /// positions are returned.
+    pub fn apply(&self, tiles: &mut Map<Tile>) -> Vec<Vector2<i32>> {
+        let mut conflicts = Vec::new();
+
+        for (&pos, change) in self.changes.iter() {
+            let current = tiles.get(pos);
+            if current == change.after() {
+                continue;
+            }
+
+            if current == change.before() {
+                tiles.set(pos, change.after());
+            } else {
+                conflicts.push(pos);
+            }
+        }
+
+        conflicts
+    }
+}
+
+impl Change {
+    pub fn before(&self) -> Tile {
+        match self {
+            Change::Added(_) => Tile::Empty,
+            Change::Removed(old) | Change::Changed { old, .. } => *old,
+        }
+    }
+
+    pub fn after(&self) -> Tile {
+        match self {
+            Change::Removed(_) => Tile::Empty,
+            Change::Added(new) | Change::Changed { new, .. } => *new,
+        }
+    }
+}
+
+/// Three way merge of two boards that were both derived from `base`. The
+/// changes made in `theirs` are applied on top of `ours`, returning the merged
+/// tiles along with any conflicting positions, where `ours` is kept.
+pub fn merge(
+    base: &Map<Tile>,
+    ours: &Map<Tile>,
+    theirs: &Map<Tile>,
+) -> (Map<Tile>, Vec<Vector2<i32>>) {
+    let mut tiles = ours.clone();
+    let conflicts = Diff::new(base, theirs).apply(&mut tiles);
+    (tiles, conflicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{direction::Direction, map::Map};
+    use nalgebra::Vector2;
+
+    use crate::tile::Tile;
+
+    use super::{Change, Diff, merge};
+
+    const MIRROR: Tile = Tile::Mirror { rotation: false };
+    const GALVO: Tile = Tile::Galvo {
+        rotation: Direction::Up,
+    };
+
+    fn map(tiles: &[((i32, i32), Tile)]) -> Map<Tile> {
+        let mut map = Map::default();
+        for &((x, y), tile) in tiles {
+            map.set(Vector2::new(x, y), tile);
+        }
+        map
+    }
+
+    #[test]
+    fn new_finds_changes() {
+        let old = map(&[
+            ((0, 0), Tile::Wall),
+            ((1, 0), Tile::Delay),
+            ((2, 0), MIRROR),
+        ]);
+        let new = map(&[((0, 0), Tile::Wall), ((1, 0), GALVO), ((3, 0), MIRROR)]);
+        let diff = Diff::new(&old, &new);
+
+        assert_eq!(diff.changes.len(), 3);
+        assert_eq!(
+            diff.changes[&Vector2::new(1, 0)],
+            Change::Changed {
+                old: Tile::Delay,
+                new: GALVO
+            }
+        );
+        assert_eq!(diff.changes[&Vector2::new(2, 0)], Change::Removed(MIRROR));
+        assert_eq!(diff.changes[&Vector2::new(3, 0)], Change::Added(MIRROR));
+        assert!(Diff::new(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn apply_turns_old_into_new() {
+        let old = map(&[((0, 0), Tile::Wall), ((1, 0), Tile::Delay)]);
+        let new = map(&[((1, 0), GALVO), ((2, 0), MIRROR)]);
+
+        let mut tiles = old.clone();
+        assert!(Diff::new(&old, &new).apply(&mut tiles).is_empty());
+        assert_eq!(tiles, new);
+    }
+
+    #[test]
+    fn apply_reports_conflicts() {
+        let old = map(&[((0, 0), Tile::Delay)]);
+        let new = map(&[((0, 0), GALVO), ((1, 0), MIRROR)]);
+
+        // The delay was replaced and a tile was placed where the diff adds
+        // one, but a tile changed the same way on both sides is fine
+        let mut tiles = map(&[((0, 0), Tile::Wall), ((1, 0), MIRROR)]);
+        let conflicts = Diff::new(&old, &new).apply(&mut tiles);
+        assert_eq!(conflicts, vec![Vector2::new(0, 0)]);
+        assert_eq!(tiles.get(Vector2::new(0, 0)), Tile::Wall);
+        assert_eq!(tiles.get(Vector2::new(1, 0)), MIRROR);
+
+        // Removing a tile the diff changes is also a conflict
+        let mut tiles = map(&[((1, 0), Tile::Delay)]);
+        let mut conflicts = Diff::new(&old, &new).apply(&mut tiles);
+        conflicts.sort_by_key(|pos| pos.x);
+        assert_eq!(conflicts, vec![Vector2::new(0, 0), Vector2::new(1, 0)]);
+        assert_eq!(tiles, map(&[((1, 0), Tile::Delay)]));
+    }
+
+    #[test]
+    fn merge_keeps_both_sides() {
+        let base = map(&[((0, 0), Tile::Delay), ((1, 0), Tile::Delay)]);
+        let ours = map(&[((0, 0), GALVO), ((1, 0), Tile::Delay), ((2, 0), Tile::Wall)]);
+        let theirs = map(&[((0, 0), Tile::Delay), ((3, 0), MIRROR)]);
+
+        let (tiles, conflicts) = merge(&base, &ours, &theirs);
+        assert!(conflicts.is_empty());
+        assert_eq!(
+            tiles,
+            map(&[((0, 0), GALVO), ((2, 0), Tile::Wall), ((3, 0), MIRROR)])
+        );
+    }
+
+    #[test]
+    fn merge_conflicts_keep_ours() {
+        let base = map(&[((0, 0), Tile::Delay)]);
+        let ours = map(&[((0, 0), GALVO)]);
+        let theirs = map(&[((0, 0), MIRROR)]);
+
+        let (tiles, conflicts) = merge(&base, &ours, &theirs);
+        assert_eq!(conflicts, vec![Vector2::new(0, 0)]);
+        assert_eq!(tiles, ours);
+
+        // Against a base without the delay, their unchanged tiles would
+        // wrongly look like edits
+        let (_, conflicts) = merge(&Map::default(), &ours, &base);
+        assert_eq!(conflicts, vec![Vector2::new(0, 0)]);
+        let (_, conflicts) = merge(&base, &ours, &base);
+        assert!(conflicts.is_empty());
+    }
+}
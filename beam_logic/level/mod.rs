@@ -13,6 +13,7 @@ use crate::tile::{Tile, TileType};
 pub mod case;
 pub mod default;
 pub mod tree;
+pub mod validate;
 use case::TestCase;
 
 #[derive(Debug, Clone, Deserialize)]
@@ -0,0 +1,196 @@
+use std::fmt;
+
+use ahash::{HashSet, HashSetExt};
+use common::map::Map;
+use nalgebra::Vector2;
+
+use crate::{
+    level::Level,
+    misc::area,
+    tile::{Tile, TileType},
+};
+
+/// Why a board couldn't have been built in a level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardError {
+    DuplicateDynamicTiles,
+    UndefinedDynamicTiles,
+    ModifiedPermanentTiles,
+    OutOfBounds,
+    ChipsNotAllowed,
+    DisabledTiles,
+    TooLarge,
+}
+
+impl Level {
+    /// Checks that a board could have been built in this level by the game's
+    /// editing rules. All permanent tiles should still be there unchanged,
+    /// every other tile has to pass [`Level::check_placement`], and dynamic
+    /// tiles have to be defined by the level and can't be duplicated. The
+    /// placed tiles also have to be close enough together that their area can
+    /// be stored.
+    ///
+    /// Used by the client before uploading a solution and by the leaderboard
+    /// server before accepting one.
+    pub fn validate(&self, board: &Map<Tile>) -> Result<(), BoardError> {
+        let mut ids = HashSet::new();
+        for (pos, tile) in board.iter() {
+            if let Some(id) = tile.id() {
+                if !self.is_dynamic(id) {
+                    return Err(BoardError::UndefinedDynamicTiles);
+                } else if !ids.insert(id) {
+                    return Err(BoardError::DuplicateDynamicTiles);
+                }
+            }
+
+            if !self.permanent.contains(&pos) {
+                self.check_placement(pos, &tile)?;
+            }
+        }
+
+        // Permanent tiles are checked here, even if they were removed from the
+        // board.
+        for &pos in self.permanent.iter() {
+            if !self.tiles.get(pos).soft_eq(&board.get(pos)) {
+                return Err(BoardError::ModifiedPermanentTiles);
+            }
+        }
+
+        if area(board, self).is_none() {
+            return Err(BoardError::TooLarge);
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the player can change the tile at a position, which has to
+    /// be inside the level and not permanent.
+    pub fn check_position(&self, pos: Vector2<i32>) -> Result<(), BoardError> {
+        if self.permanent.contains(&pos) {
+            Err(BoardError::ModifiedPermanentTiles)
+        } else if self.out_of_bounds(pos) {
+            Err(BoardError::OutOfBounds)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks that the player could place a tile at a position. Chips,
+    /// disabled tiles, and dynamic tiles the level doesn't define can't be
+    /// placed anywhere. Duplicate dynamic tiles are only caught by
+    /// [`Level::validate`], as they depend on the rest of the board.
+    pub fn check_placement(&self, pos: Vector2<i32>, tile: &Tile) -> Result<(), BoardError> {
+        self.check_position(pos)?;
+
+        if let Tile::Chip { .. } = tile {
+            return Err(BoardError::ChipsNotAllowed);
+        }
+
+        // Levels can start with dynamic tiles the player isn't allowed to
+        // place, but they can still be moved.
+        match tile.id() {
+            Some(id) if !self.is_dynamic(id) => Err(BoardError::UndefinedDynamicTiles),
+            None if self.is_disabled(tile.as_type()) => Err(BoardError::DisabledTiles),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn is_disabled(&self, tile: TileType) -> bool {
+        (self.disabled.as_ref()).is_some_and(|disabled| disabled.contains(&tile))
+    }
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BoardError::DuplicateDynamicTiles => "Extra dynamic tiles were added",
+            BoardError::UndefinedDynamicTiles => "Dynamic tiles not in the level were added",
+            BoardError::ModifiedPermanentTiles => "Permanent tiles were modified",
+            BoardError::OutOfBounds => "Tiles placed outside the level area",
+            BoardError::ChipsNotAllowed => "Chips are not allowed in campaign levels",
+            BoardError::DisabledTiles => "Disabled tiles were placed",
+            BoardError::TooLarge => "Tiles are spread out too far",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::direction::Direction;
+    use nalgebra::Vector2;
+
+    use crate::{
+        level::{Level, default::DEFAULT_LEVELS},
+        tile::Tile,
+    };
+
+    use super::BoardError;
+
+    /// An empty position inside the level that the player can build on.
+    fn free_position(level: &Level) -> Option<Vector2<i32>> {
+        let size = level.size?.cast::<i32>();
+        (0..size.x)
+            .flat_map(|x| (0..size.y).map(move |y| Vector2::new(x, y)))
+            .find(|&pos| level.tiles.get(pos).is_empty() && !level.permanent.contains(&pos))
+    }
+
+    fn with_tile(level: &Level, pos: Vector2<i32>, tile: Tile) -> Result<(), BoardError> {
+        let mut board = level.tiles.clone();
+        board.set(pos, tile);
+        level.validate(&board)
+    }
+
+    #[test]
+    fn default_levels_are_valid() {
+        for level in DEFAULT_LEVELS.iter() {
+            assert_eq!(level.validate(&level.tiles), Ok(()), "{}", level.name);
+        }
+    }
+
+    #[test]
+    fn invalid_boards_are_rejected() {
+        let chip = Tile::Chip {
+            rotation: Direction::Up,
+            index: 0,
+        };
+        let undefined = Tile::Detector { id: Some(u32::MAX) };
+
+        for level in DEFAULT_LEVELS.iter() {
+            let Some(pos) = free_position(level) else {
+                continue;
+            };
+            let check =
+                |tile, error| assert_eq!(with_tile(level, pos, tile), Err(error), "{}", level.name);
+
+            check(chip, BoardError::ChipsNotAllowed);
+            check(undefined, BoardError::UndefinedDynamicTiles);
+            assert_eq!(
+                with_tile(level, Vector2::new(-1, -1), Tile::Wall),
+                Err(BoardError::OutOfBounds),
+                "{}",
+                level.name
+            );
+
+            if let Some((_, tile)) = level.tiles.iter().find(|(_, x)| x.id().is_some()) {
+                check(tile, BoardError::DuplicateDynamicTiles);
+            }
+
+            if let Some(tile) = (Tile::DEFAULT.into_iter()).find(|x| level.is_disabled(x.as_type()))
+            {
+                check(tile, BoardError::DisabledTiles);
+            }
+
+            let permanent = (level.permanent.iter()).find(|&&pos| !level.tiles.get(pos).is_empty());
+            if let Some(&pos) = permanent {
+                let mut board = level.tiles.clone();
+                board.remove(pos);
+                assert_eq!(
+                    level.validate(&board),
+                    Err(BoardError::ModifiedPermanentTiles),
+                    "{}",
+                    level.name
+                );
+            }
+        }
+    }
+}
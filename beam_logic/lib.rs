@@ -1,6 +1,9 @@
 #![feature(decl_macro)]
 
+pub mod chip;
+pub mod diff;
 pub mod level;
 pub mod misc;
 pub mod simulation;
+pub mod text;
 pub mod tile;
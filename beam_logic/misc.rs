@@ -1,18 +1,43 @@
 use common::map::Map;
+use nalgebra::Vector2;
 
 use crate::{level::Level, tile::Tile};
 
 pub fn price(board: &Map<Tile>, level: &Level) -> (u32, usize) {
     let (mut price, mut count) = (0, 0);
-    for (pos, tile) in board.iter() {
-        let is_dynamic = tile.id().map(|id| level.is_dynamic(id)).unwrap_or_default();
-        if level.permanent.contains(&pos) || is_dynamic {
-            continue;
-        }
-
+    for (_pos, tile) in placed(board, level) {
         price += tile.price();
         count += 1;
     }
 
     (price, count)
 }
+
+/// The area of the smallest rectangle containing every tile placed by the
+/// player, or `None` if it's too big to fit in a u32. That can only happen on
+/// levels without a size limit.
+pub fn area(board: &Map<Tile>, level: &Level) -> Option<u32> {
+    let mut bounds = None::<(Vector2<i32>, Vector2<i32>)>;
+    for (pos, _tile) in placed(board, level) {
+        bounds = Some(match bounds {
+            Some((min, max)) => (min.inf(&pos), max.sup(&pos)),
+            None => (pos, pos),
+        });
+    }
+
+    let Some((min, max)) = bounds else {
+        return Some(0);
+    };
+
+    let size = (max.map(i64::from) - min.map(i64::from)).map(|x| x as u64 + 1);
+    (size.x.checked_mul(size.y)).and_then(|x| u32::try_from(x).ok())
+}
+
+/// Tiles on the board that were placed by the player, skipping the level's
+/// permanent and dynamic tiles.
+fn placed(board: &Map<Tile>, level: &Level) -> impl Iterator<Item = (Vector2<i32>, Tile)> {
+    board.iter().filter(|(pos, tile)| {
+        let is_dynamic = tile.id().map(|id| level.is_dynamic(id)).unwrap_or_default();
+        !level.permanent.contains(pos) && !is_dynamic
+    })
+}
@@ -1,4 +1,7 @@
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    time::{Duration, Instant},
+};
 
 use crate::{
     level::Level,
@@ -10,6 +13,7 @@ use common::map::Map;
 pub struct TestingSimulationState {
     beam: BeamState,
     max_ticks: u32,
+    ticks: u64,
 }
 
 impl TestingSimulationState {
@@ -17,14 +21,31 @@ impl TestingSimulationState {
         Self {
             beam: BeamState::new(board, Some(level), Some(0)),
             max_ticks,
+            ticks: 0,
         }
     }
 
+    /// Total number of ticks simulated, across every test case.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
     pub fn run(&mut self) -> LevelResult {
+        self.run_until(None).unwrap()
+    }
+
+    /// Runs the simulation like [`Self::run`], but gives up and returns None
+    /// if it takes longer than `timeout` in wall-clock time.
+    pub fn run_for(&mut self, timeout: Duration) -> Option<LevelResult> {
+        self.run_until(Some(Instant::now() + timeout))
+    }
+
+    fn run_until(&mut self, deadline: Option<Instant>) -> Option<LevelResult> {
         let (mut case, mut timer) = (0, 0);
 
         loop {
             self.beam.tick();
+            self.ticks += 1;
 
             let level = self.beam.level.as_ref().unwrap();
 
@@ -36,11 +57,15 @@ impl TestingSimulationState {
             timer += 1;
 
             if timer > self.max_ticks {
-                return LevelResult::OutOfTime;
+                return Some(LevelResult::OutOfTime);
             }
 
             if let Some(result) = level.result {
-                return result;
+                return Some(result);
+            }
+
+            if deadline.is_some_and(|x| Instant::now() >= x) {
+                return None;
             }
         }
     }
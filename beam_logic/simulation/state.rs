@@ -1,13 +1,19 @@
 use std::{
     borrow::Cow,
+    collections::HashMap,
     hash::{DefaultHasher, Hash, Hasher},
 };
 
-use common::{map::Map, misc::in_bounds};
+use common::{
+    direction::{Direction, Directions},
+    map::Map,
+    misc::in_bounds,
+};
 use log::trace;
 use nalgebra::Vector2;
 
 use crate::{
+    chip::{Chip, Port, PortKind, unrotated_side},
     level::{DynamicElementMap, Level},
     tile::Tile,
 };
@@ -18,6 +24,13 @@ pub struct BeamState {
     pub board: Map<BeamTile>,
     pub level: Option<LevelState>,
     pub bounds: (Vector2<i32>, Vector2<i32>),
+    /// The inner simulation of every chip on the board, by position.
+    pub chips: HashMap<Vector2<i32>, ChipState>,
+}
+
+pub struct ChipState {
+    pub ports: [Option<Port>; 4],
+    pub state: BeamState,
 }
 
 impl BeamState {
@@ -40,6 +53,7 @@ impl BeamState {
             board,
             level,
             bounds,
+            chips: HashMap::new(),
         };
 
         if let Some(level) = &mut state.level {
@@ -50,6 +64,26 @@ impl BeamState {
         state
     }
 
+    /// Creates the inner simulations for all chips on the board. The `chips`
+    /// are indexed by the `index` of each chip tile.
+    pub fn with_chips(mut self, chips: &[Chip]) -> Self {
+        for (pos, tile) in self.board.iter() {
+            let BeamTile::Chip { index, .. } = tile else {
+                continue;
+            };
+
+            let Some(chip) = chips.get(index as usize) else {
+                continue;
+            };
+
+            let state = BeamState::new(&chip.tiles, None, None).with_chips(&chip.chips);
+            let ports = chip.ports.clone();
+            self.chips.insert(pos, ChipState { ports, state });
+        }
+
+        self
+    }
+
     pub fn hash(&self) -> u64 {
         let mut tiles = (self.board.iter())
             .filter(|(pos, _)| in_bounds(*pos, self.bounds))
@@ -62,6 +96,176 @@ impl BeamState {
             tile.hash(&mut hasher);
         }
 
+        let mut chips = self.chips.iter().collect::<Vec<_>>();
+        chips.sort_by(|(a, _), (b, _)| a.x.cmp(&b.x).then(a.y.cmp(&b.y)));
+        for (pos, chip) in chips {
+            pos.hash(&mut hasher);
+            chip.state.hash().hash(&mut hasher);
+        }
+
         hasher.finish()
     }
 }
+
+impl ChipState {
+    /// Activates the inner emitters of the input ports that are receiving a
+    /// beam, ticks the inner board, then returns the sides that should be
+    /// emitting a beam because their inner detector is powered.
+    pub fn tick(&mut self, rotation: Direction, powered: Directions) -> Directions {
+        for side in Direction::ALL {
+            let port = &self.ports[unrotated_side(rotation, side) as usize];
+            let Some(Port {
+                kind: PortKind::Input,
+                pos,
+                ..
+            }) = port
+            else {
+                continue;
+            };
+
+            // A beam entering from a side will be traveling away from it
+            if let BeamTile::Emitter { active, .. } = self.state.board.get_mut(*pos) {
+                *active = powered.contains(side.opposite());
+            }
+        }
+
+        self.state.tick();
+
+        let mut output = Directions::empty();
+        for side in Direction::ALL {
+            let port = &self.ports[unrotated_side(rotation, side) as usize];
+            if let Some(Port {
+                kind: PortKind::Output,
+                pos,
+                ..
+            }) = port
+            {
+                output.set(side, self.state.board.get(*pos).is_powered());
+            }
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{direction::Direction, map::Map};
+    use nalgebra::Vector2;
+
+    use crate::{chip::Chip, tile::Tile};
+
+    use super::BeamState;
+
+    const DETECTOR: Tile = Tile::Detector { id: None };
+
+    fn emitter(rotation: Direction, active: bool) -> Tile {
+        Tile::Emitter {
+            rotation,
+            active,
+            id: None,
+        }
+    }
+
+    fn chip(rotation: Direction) -> Tile {
+        Tile::Chip { rotation, index: 0 }
+    }
+
+    fn board(tiles: &[((i32, i32), Tile)]) -> Map<Tile> {
+        let mut board = Map::default();
+        for &((x, y), tile) in tiles {
+            board.set(Vector2::new(x, y), tile);
+        }
+        board
+    }
+
+    /// A chip that passes beams entering its left side out of its right.
+    fn wire(chips: Vec<Chip>) -> Chip {
+        let inner = match chips.is_empty() {
+            true => Tile::Empty,
+            false => chip(Direction::Up),
+        };
+        let tiles = board(&[
+            ((0, 0), emitter(Direction::Right, false)),
+            ((2, 0), inner),
+            ((4, 0), DETECTOR),
+        ]);
+        Chip::new("Wire".into(), tiles, chips).unwrap()
+    }
+
+    /// Ticks the board until the detector at `detector` is powered, returning
+    /// how many ticks it took.
+    fn ticks_until_powered(
+        tiles: &Map<Tile>,
+        chips: &[Chip],
+        detector: (i32, i32),
+    ) -> Option<usize> {
+        let mut state = BeamState::new(tiles, None, None).with_chips(chips);
+        let detector = Vector2::new(detector.0, detector.1);
+        (1..=50).find(|_| {
+            state.tick();
+            state.board.get(detector).is_powered()
+        })
+    }
+
+    // The inner board ticks once at the end of each outer tick and its
+    // detectors power the chip's sides on the next one, so a chip takes one
+    // tick less to pass a beam through than its inner board would.
+
+    #[test]
+    fn chip_passes_beams_through() {
+        let direct = board(&[
+            ((0, 0), emitter(Direction::Right, true)),
+            ((4, 0), DETECTOR),
+        ]);
+        assert_eq!(ticks_until_powered(&direct, &[], (4, 0)), Some(4));
+
+        let tiles = board(&[
+            ((0, 0), emitter(Direction::Right, true)),
+            ((2, 0), chip(Direction::Up)),
+            ((4, 0), DETECTOR),
+        ]);
+        assert_eq!(
+            ticks_until_powered(&tiles, &[wire(Vec::new())], (4, 0)),
+            Some(7)
+        );
+    }
+
+    #[test]
+    fn unpowered_chip_stays_off() {
+        let tiles = board(&[
+            ((0, 0), emitter(Direction::Right, false)),
+            ((2, 0), chip(Direction::Up)),
+            ((4, 0), DETECTOR),
+        ]);
+        assert_eq!(
+            ticks_until_powered(&tiles, &[wire(Vec::new())], (4, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn rotated_chip() {
+        // Turned clockwise, the input is on top and the output on the bottom
+        let tiles = board(&[
+            ((2, 2), emitter(Direction::Down, true)),
+            ((2, 0), chip(Direction::Right)),
+            ((2, -2), DETECTOR),
+            ((4, 0), DETECTOR),
+        ]);
+        let chips = [wire(Vec::new())];
+        assert_eq!(ticks_until_powered(&tiles, &chips, (2, -2)), Some(7));
+        assert_eq!(ticks_until_powered(&tiles, &chips, (4, 0)), None);
+    }
+
+    #[test]
+    fn nested_chips() {
+        let tiles = board(&[
+            ((0, 0), emitter(Direction::Right, true)),
+            ((2, 0), chip(Direction::Up)),
+            ((4, 0), DETECTOR),
+        ]);
+        let chips = [wire(vec![wire(Vec::new())])];
+        assert_eq!(ticks_until_powered(&tiles, &chips, (4, 0)), Some(10));
+    }
+}
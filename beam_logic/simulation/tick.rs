@@ -192,6 +192,15 @@ impl BeamState {
                     let (powered, _) = working.get_mut(pos).delay_mut();
                     self.track_powered(powered, pos);
                 }
+                // Chips send beams out of every side whose inner detector is
+                // powered, the inner board is updated below.
+                BeamTile::Chip { output, .. } => {
+                    for dir in output.iter() {
+                        self.power(&mut working, pos, dir);
+                    }
+
+                    self.track_powered(working.get_mut(pos).directions_mut(), pos);
+                }
                 BeamTile::Wall { .. } | BeamTile::Detector { .. } => {
                     self.track_powered(working.get_mut(pos).directions_mut(), pos)
                 }
@@ -199,6 +208,18 @@ impl BeamState {
             }
         }
 
+        for (&pos, chip) in self.chips.iter_mut() {
+            if let BeamTile::Chip {
+                rotation,
+                powered,
+                output,
+                ..
+            } = working.get_mut(pos)
+            {
+                *output = chip.tick(*rotation, *powered);
+            }
+        }
+
         self.board = working;
     }
 
@@ -253,7 +274,8 @@ impl BeamState {
             } if direction != dir.opposite() => *powered |= direction,
             BeamTile::Wall { powered }
             | BeamTile::Detector { powered }
-            | BeamTile::Delay { powered, .. } => *powered |= direction,
+            | BeamTile::Delay { powered, .. }
+            | BeamTile::Chip { powered, .. } => *powered |= direction,
             _ => {}
         }
     }
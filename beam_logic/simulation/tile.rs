@@ -47,6 +47,14 @@ pub enum BeamTile {
         direction: Direction,
         powered: Directions,
     },
+    Chip {
+        rotation: Direction,
+        index: u32,
+        /// Directions of the beams entering the chip.
+        powered: Directions,
+        /// Sides the chip is currently sending beams out of.
+        output: Directions,
+    },
 }
 
 impl BeamTile {
@@ -61,6 +69,7 @@ impl BeamTile {
             Self::Delay { last_powered, .. } => last_powered.any(),
             Self::Mirror { powered, .. } => powered[0].is_some() || powered[1].is_some(),
             Self::Splitter { powered, .. } => powered.any(),
+            Self::Chip { output, .. } => output.any(),
             _ => false,
         }
     }
@@ -91,6 +100,7 @@ impl BeamTile {
                 .flat_map(|x| [MIRROR_REFLECTIONS[x as usize].opposite_if(!direction), x])
                 .collect::<Directions>(),
             Self::Delay { last_powered, .. } => *last_powered,
+            Self::Chip { output, .. } => *output,
             _ => Directions::empty(),
         }
     }
@@ -126,9 +136,10 @@ impl BeamTile {
 
     pub fn directions_mut(&mut self) -> &mut Directions {
         match self {
-            Self::Galvo { powered, .. } | Self::Wall { powered } | Self::Detector { powered } => {
-                powered
-            }
+            Self::Galvo { powered, .. }
+            | Self::Wall { powered }
+            | Self::Detector { powered }
+            | Self::Chip { powered, .. } => powered,
             _ => panic!(),
         }
     }
@@ -167,6 +178,12 @@ impl From<Tile> for BeamTile {
             Tile::Wall => BeamTile::Wall {
                 powered: Directions::empty(),
             },
+            Tile::Chip { rotation, index } => BeamTile::Chip {
+                rotation,
+                index,
+                powered: Directions::empty(),
+                output: Directions::empty(),
+            },
         }
     }
 }
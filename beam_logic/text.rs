@@ -0,0 +1,298 @@
+//! A plain text format for tile maps, meant to be diffed and edited by hand.
+//!
+//! Tiles are drawn as a grid of two character cells separated by spaces, with
+//! the first row being the top of the board. The `origin` line gives the
+//! position of the top left cell and any tile properties that don't fit in a
+//! cell are listed after the grid. Lines starting with `;` are ignored.
+//!
+//! ```text
+//! origin 0 2
+//! E> .. M\
+//! .. .. ##
+//! .. .. D.
+//! id 0 2 0
+//! id 2 0 1
+//! ```
+//!
+//! | Cell                  | Tile                                  |
+//! | --------------------- | ------------------------------------- |
+//! | `..`                  | Empty                                 |
+//! | `D.`                  | Detector                              |
+//! | `T.`                  | Delay                                 |
+//! | `E^` `E>` `Ev` `E<`   | Emitter, lowercase `e` when inactive  |
+//! | `M/` `M\`             | Mirror                                |
+//! | `S/` `S\`             | Splitter                              |
+//! | `G^` `G>` `Gv` `G<`   | Galvo                                 |
+//! | `##`                  | Wall                                  |
+//! | `C^` `C>` `Cv` `C<`   | Chip                                  |
+//!
+//! Dynamic emitters and detectors have an `id <x> <y> <id>` line and chips
+//! have a `chip <x> <y> <index>` line.
+
+use std::fmt::Write;
+
+use anyhow::{Context, Result, bail};
+use nalgebra::Vector2;
+
+use common::{direction::Direction, map::Map};
+
+use crate::tile::Tile;
+
+const DIRECTIONS: [char; 4] = ['^', '>', 'v', '<'];
+
+/// Encodes a tile map into the text format.
+pub fn encode(tiles: &Map<Tile>) -> String {
+    let mut out = String::new();
+    let (mut min, mut max) = (Vector2::repeat(i32::MAX), Vector2::repeat(i32::MIN));
+    for (pos, _) in tiles.iter() {
+        min = min.inf(&pos);
+        max = max.sup(&pos);
+    }
+
+    if tiles.tiles.is_empty() {
+        (min, max) = (Vector2::zeros(), Vector2::new(-1, -1));
+    }
+
+    writeln!(out, "origin {} {}", min.x, max.y).unwrap();
+    for y in (min.y..=max.y).rev() {
+        let row = (min.x..=max.x)
+            .map(|x| encode_tile(tiles.get(Vector2::new(x, y))))
+            .collect::<Vec<_>>();
+        writeln!(out, "{}", row.join(" ")).unwrap();
+    }
+
+    // Sorted top to bottom, left to right to keep diffs stable
+    let mut tiles = tiles.iter().collect::<Vec<_>>();
+    tiles.sort_by(|(a, _), (b, _)| b.y.cmp(&a.y).then(a.x.cmp(&b.x)));
+    for (pos, tile) in tiles {
+        match tile {
+            Tile::Chip { index, .. } => writeln!(out, "chip {} {} {index}", pos.x, pos.y),
+            _ => match tile.id() {
+                Some(id) => writeln!(out, "id {} {} {id}", pos.x, pos.y),
+                None => Ok(()),
+            },
+        }
+        .unwrap();
+    }
+
+    out
+}
+
+/// Decodes a tile map from the text format.
+pub fn decode(text: &str) -> Result<Map<Tile>> {
+    let mut tiles = Map::default();
+    let (mut origin, mut row) = (None, 0);
+
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+
+        decode_line(&mut tiles, &mut origin, &mut row, line)
+            .with_context(|| format!("Invalid tile text on line {}", i + 1))?;
+    }
+
+    Ok(tiles)
+}
+
+fn decode_line(
+    tiles: &mut Map<Tile>,
+    origin: &mut Option<Vector2<i32>>,
+    row: &mut i32,
+    line: &str,
+) -> Result<()> {
+    let parts = line.split_whitespace().collect::<Vec<_>>();
+    let value = |idx: usize| parts.get(idx).context("Missing value");
+    let position =
+        || -> Result<Vector2<i32>> { Ok(Vector2::new(value(1)?.parse()?, value(2)?.parse()?)) };
+
+    match parts[0] {
+        "origin" => *origin = Some(position()?),
+        keyword @ ("id" | "chip") => {
+            let pos = position()?;
+            let value = value(3)?.parse::<u32>()?;
+
+            let mut tile = tiles.get(pos);
+            match (keyword, &mut tile) {
+                ("id", Tile::Emitter { id, .. } | Tile::Detector { id }) => *id = Some(value),
+                ("chip", Tile::Chip { index, .. }) => *index = value,
+                _ => bail!("No tile to apply `{keyword}` to at ({}, {})", pos.x, pos.y),
+            }
+
+            tiles.set(pos, tile);
+        }
+        _ => {
+            let origin = origin.context("Tile grid must come after the origin")?;
+            for (col, cell) in parts.iter().enumerate() {
+                let pos = Vector2::new(origin.x + col as i32, origin.y - *row);
+                tiles.set(pos, decode_tile(cell)?);
+            }
+
+            *row += 1;
+        }
+    }
+
+    Ok(())
+}
+
+fn encode_tile(tile: Tile) -> String {
+    let (kind, modifier) = match tile {
+        Tile::Empty => ('.', '.'),
+        Tile::Detector { .. } => ('D', '.'),
+        Tile::Delay => ('T', '.'),
+        Tile::Emitter {
+            rotation, active, ..
+        } => (['e', 'E'][active as usize], DIRECTIONS[rotation as usize]),
+        Tile::Mirror { rotation } => ('M', ['/', '\\'][rotation as usize]),
+        Tile::Splitter { rotation } => ('S', ['/', '\\'][rotation as usize]),
+        Tile::Galvo { rotation } => ('G', DIRECTIONS[rotation as usize]),
+        Tile::Wall => ('#', '#'),
+        Tile::Chip { rotation, .. } => ('C', DIRECTIONS[rotation as usize]),
+    };
+
+    format!("{kind}{modifier}")
+}
+
+fn decode_tile(cell: &str) -> Result<Tile> {
+    let mut chars = cell.chars();
+    let (Some(kind), Some(modifier), None) = (chars.next(), chars.next(), chars.next()) else {
+        bail!("Cells must be two characters, found `{cell}`");
+    };
+
+    let direction = || {
+        (DIRECTIONS.iter())
+            .position(|&x| x == modifier)
+            .map(|x| Direction::ALL[x])
+    };
+    let diagonal = || {
+        ['/', '\\']
+            .iter()
+            .position(|&x| x == modifier)
+            .map(|x| x == 1)
+    };
+
+    let tile = match (kind, modifier) {
+        ('.', '.') => Some(Tile::Empty),
+        ('D', '.') => Some(Tile::Detector { id: None }),
+        ('T', '.') => Some(Tile::Delay),
+        ('#', '#') => Some(Tile::Wall),
+        ('E' | 'e', _) => direction().map(|rotation| Tile::Emitter {
+            rotation,
+            active: kind == 'E',
+            id: None,
+        }),
+        ('M', _) => diagonal().map(|rotation| Tile::Mirror { rotation }),
+        ('S', _) => diagonal().map(|rotation| Tile::Splitter { rotation }),
+        ('G', _) => direction().map(|rotation| Tile::Galvo { rotation }),
+        ('C', _) => direction().map(|rotation| Tile::Chip { rotation, index: 0 }),
+        _ => None,
+    };
+
+    tile.with_context(|| format!("Unknown tile `{cell}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{direction::Direction, map::Map};
+    use nalgebra::Vector2;
+
+    use crate::tile::Tile;
+
+    use super::{decode, encode};
+
+    const SAMPLE: &str = "\
+origin -1 2
+E> .. M\\
+.. .. ##
+.. e< D.
+id -1 2 0
+id 1 0 1
+";
+
+    #[test]
+    fn sample_is_stable() {
+        let tiles = decode(SAMPLE).unwrap();
+        assert_eq!(tiles.tiles.len(), 5);
+        assert_eq!(
+            tiles.get(Vector2::new(-1, 2)),
+            Tile::Emitter {
+                rotation: Direction::Right,
+                active: true,
+                id: Some(0),
+            }
+        );
+        assert_eq!(
+            tiles.get(Vector2::new(1, 0)),
+            Tile::Detector { id: Some(1) }
+        );
+        assert_eq!(encode(&tiles), SAMPLE);
+    }
+
+    #[test]
+    fn every_tile_round_trips() {
+        let mut tiles = Map::default();
+        let mut place = |x, y, tile| tiles.set(Vector2::new(x, y), tile);
+
+        for (i, rotation) in Direction::ALL.into_iter().enumerate() {
+            let x = i as i32 * 2 - 3;
+            place(x, 0, Tile::Galvo { rotation });
+            place(
+                x,
+                1,
+                Tile::Chip {
+                    rotation,
+                    index: i as u32,
+                },
+            );
+            for (active, y) in [(true, 2), (false, 3)] {
+                let id = active.then_some(i as u32);
+                place(
+                    x,
+                    y,
+                    Tile::Emitter {
+                        rotation,
+                        active,
+                        id,
+                    },
+                );
+            }
+        }
+
+        for (i, rotation) in [false, true].into_iter().enumerate() {
+            place(i as i32, -5, Tile::Mirror { rotation });
+            place(i as i32, -6, Tile::Splitter { rotation });
+        }
+
+        place(0, 7, Tile::Detector { id: None });
+        place(1, 7, Tile::Detector { id: Some(3) });
+        place(2, 7, Tile::Delay);
+        place(3, 7, Tile::Wall);
+
+        let text = encode(&tiles);
+        assert_eq!(decode(&text).unwrap(), tiles);
+        assert_eq!(encode(&decode(&text).unwrap()), text);
+    }
+
+    #[test]
+    fn origin_places_grid() {
+        let tiles = decode("origin 10 -20\n.. ##\n## ..").unwrap();
+        assert_eq!(tiles.get(Vector2::new(11, -20)), Tile::Wall);
+        assert_eq!(tiles.get(Vector2::new(10, -21)), Tile::Wall);
+        assert_eq!(tiles.tiles.len(), 2);
+    }
+
+    #[test]
+    fn empty_round_trips() {
+        let tiles = Map::default();
+        assert_eq!(decode(&encode(&tiles)).unwrap(), tiles);
+    }
+
+    #[test]
+    fn invalid_text() {
+        assert!(decode(".. ##").is_err());
+        assert!(decode("origin 0 0\nX?").is_err());
+        assert!(decode("origin 0 0\n##\nid 0 0 1").is_err());
+        assert!(decode("origin 0 0\nD.\nchip 0 0 1").is_err());
+    }
+}
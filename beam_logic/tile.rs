@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 
 use common::direction::Direction;
 
+use crate::chip::Chip;
+
 pub const TILE_VERSION: u32 = 2;
 
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -27,6 +29,12 @@ pub enum Tile {
         rotation: Direction,
     },
     Wall,
+    /// A nested board, see [`crate::chip::Chip`]. The index refers to the
+    /// chip list of the board the tile is placed on.
+    Chip {
+        rotation: Direction,
+        index: u32,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
@@ -38,6 +46,7 @@ pub enum TileType {
     Splitter,
     Galvo,
     Wall,
+    Chip,
 }
 
 impl Tile {
@@ -71,6 +80,7 @@ impl Tile {
             Tile::Splitter { .. } => "Splitter",
             Tile::Galvo { .. } => "Galvo",
             Tile::Wall => "Wall",
+            Tile::Chip { .. } => "Chip",
         }
     }
 
@@ -84,6 +94,17 @@ impl Tile {
             Tile::Splitter { .. } => 300,
             Tile::Galvo { .. } => 500,
             Tile::Wall => 100,
+            // The price of chips depends on their contents, see `price_with_chips`
+            Tile::Chip { .. } => 0,
+        }
+    }
+
+    /// Like `price`, but chips are priced as the sum of their contents, using
+    /// the chips of the board this tile is on.
+    pub fn price_with_chips(&self, chips: &[Chip]) -> u32 {
+        match self {
+            Tile::Chip { index, .. } => chips.get(*index as usize).map_or(0, Chip::price),
+            _ => self.price(),
         }
     }
 
@@ -97,6 +118,7 @@ impl Tile {
             Tile::Splitter { .. } => TileType::Splitter,
             Tile::Galvo { .. } => TileType::Galvo,
             Tile::Wall => TileType::Wall,
+            Tile::Chip { .. } => TileType::Chip,
         }
     }
 
@@ -124,6 +146,7 @@ impl Tile {
             (Tile::Mirror { rotation: a }, Tile::Mirror { rotation: b }) => a == b,
             (Tile::Splitter { rotation: a }, Tile::Splitter { rotation: b }) => a == b,
             (Tile::Galvo { rotation: a }, Tile::Galvo { rotation: b }) => a == b,
+            (Tile::Chip { .. }, Tile::Chip { .. }) => self == other,
             _ => false,
         }
     }
@@ -172,6 +195,10 @@ impl Tile {
             Tile::Galvo { rotation } => Tile::Galvo {
                 rotation: rotation.rotate(),
             },
+            Tile::Chip { rotation, index } => Tile::Chip {
+                rotation: rotation.rotate(),
+                index,
+            },
             x => x,
         }
     }
@@ -190,6 +217,10 @@ impl Tile {
             Tile::Galvo { rotation } => Tile::Galvo {
                 rotation: rotation.rotate_reverse(),
             },
+            Tile::Chip { rotation, index } => Tile::Chip {
+                rotation: rotation.rotate_reverse(),
+                index,
+            },
             x => x.rotate(),
         }
     }
@@ -208,6 +239,10 @@ impl Tile {
             Tile::Galvo { rotation } => Tile::Galvo {
                 rotation: rotation.flip_horizontal(),
             },
+            Tile::Chip { rotation, index } => Tile::Chip {
+                rotation: rotation.flip_horizontal(),
+                index,
+            },
             Tile::Mirror { .. } | Tile::Splitter { .. } => self.rotate(),
             x => x,
         }
@@ -227,6 +262,10 @@ impl Tile {
             Tile::Galvo { rotation } => Tile::Galvo {
                 rotation: rotation.flip_vertical(),
             },
+            Tile::Chip { rotation, index } => Tile::Chip {
+                rotation: rotation.flip_vertical(),
+                index,
+            },
             Tile::Mirror { .. } | Tile::Splitter { .. } => self.rotate(),
             x => x,
         }
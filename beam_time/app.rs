@@ -7,6 +7,7 @@ use std::{
 
 use anyhow::{Context, Result};
 use arboard::Clipboard;
+use beam_logic::chip::Chip;
 use bincode::Options;
 use common::{consts::BINCODE_OPTIONS, user::UserId};
 use engine::graphics_context::GraphicsContext;
@@ -16,7 +17,7 @@ use uuid::Uuid;
 
 use crate::{
     consts::paths, game::holding::ClipboardItem, integrations::Integrations,
-    leaderboard::LeaderboardManager, screens::Screen,
+    leaderboard::LeaderboardManager, screens::Screen, ui::toasts::Toasts,
 };
 
 pub struct App {
@@ -26,7 +27,10 @@ pub struct App {
     /// Record of all levels that have ever been solved.
     pub solved: HashSet<Uuid>,
     pub clipboard: Option<ClipboardItem>,
+    /// Sandbox copied with the "Copy as Chip" button, ready to be placed.
+    pub chip_clipboard: Option<Chip>,
     pub system_clipboard: Clipboard,
+    pub toasts: Toasts,
 
     pub config: Config,
     pub data_dir: PathBuf,
@@ -49,6 +53,19 @@ pub struct Config {
 
     pub interface_scale: f32,
     pub fullscreen: bool,
+
+    /// Uses a self-hosted leaderboard server instead of the official one.
+    pub leaderboard: Option<LeaderboardServer>,
+    /// Code of the leaderboard group to compare results with.
+    pub group: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LeaderboardServer {
+    /// Base URL of the server's api, like `https://example.com/api/`.
+    pub url: String,
+    /// Key the server uses to verify requests, if it isn't the default one.
+    pub hmac_key: Option<String>,
 }
 
 impl App {
@@ -71,10 +88,16 @@ impl App {
             id: integrations.user_id(),
             solved,
             clipboard: None,
+            chip_clipboard: None,
             system_clipboard: Clipboard::new().unwrap(),
+            toasts: Toasts::default(),
 
             integrations,
-            leaderboard: LeaderboardManager::default(),
+            leaderboard: LeaderboardManager::new(
+                &data_dir,
+                config.leaderboard.as_ref(),
+                config.group.clone(),
+            ),
 
             config,
             data_dir,
@@ -100,7 +123,10 @@ impl App {
 
     pub fn on_tick(&mut self, ctx: &mut GraphicsContext) {
         self.integrations.tick();
-        self.leaderboard.tick();
+        if let Some(ticket) = self.integrations.take_auth_ticket() {
+            self.leaderboard.set_auth_ticket(&ticket);
+        }
+        self.leaderboard.tick(&self.id);
 
         ctx.window.user_scale(self.config.interface_scale);
         ctx.window.vsync(self.config.vsync);
@@ -153,6 +179,9 @@ impl Default for Config {
 
             fullscreen: false,
             interface_scale: 1.0,
+
+            leaderboard: None,
+            group: None,
         }
     }
 }
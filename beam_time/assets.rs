@@ -77,6 +77,47 @@ define_refs! {
     }
 }
 
+/// Position of every sprite in the tilemap, which are all 16x16. These are
+/// also used to render boards on the CPU, see `game::render::export`.
+pub const TILE_SPRITES: [(SpriteRef, (u32, u32)); 36] = [
+    (EMPTY_TILE_A, (0, 288)),
+    (EMPTY_TILE_B, (16, 288)),
+    (PERMANENT_TILE_A, (32, 288)),
+    (PERMANENT_TILE_B, (48, 288)),
+    (DYNAMIC_TILE_A, (96, 304)),
+    (DYNAMIC_TILE_B, (112, 304)),
+    (DYNAMIC_TILE_OUTLINE, (96, 288)),
+    (TILE_WALL, (0, 208)),
+    (TILE_DETECTOR, (0, 192)),
+    (TILE_DELAY, (0, 256)),
+    (TILE_MIRROR_A, (0, 64)),
+    (TILE_MIRROR_B, (0, 80)),
+    (TILE_SPLITTER_A, (0, 160)),
+    (TILE_SPLITTER_B, (0, 176)),
+    (TILE_GALVO_UP, (64, 224)),
+    (TILE_GALVO_RIGHT, (0, 224)),
+    (TILE_GALVO_DOWN, (64, 240)),
+    (TILE_GALVO_LEFT, (0, 240)),
+    (TILE_EMITTER_UP, (16, 128)),
+    (TILE_EMITTER_DOWN, (16, 144)),
+    (TILE_EMITTER_LEFT, (16, 112)),
+    (TILE_EMITTER_RIGHT, (16, 96)),
+    (BEAM_FULL_HORIZONTAL, (16, 32)),
+    (BEAM_FULL_VERTICAL, (16, 48)),
+    (BEAM_REFLECT_UP_LEFT, (80, 32)),
+    (BEAM_REFLECT_DOWN_LEFT, (80, 48)),
+    (BEAM_REFLECT_UP_RIGHT, (80, 80)),
+    (BEAM_REFLECT_DOWN_RIGHT, (80, 64)),
+    (BEAM_SPLIT_UP, (80, 160)),
+    (BEAM_SPLIT_RIGHT, (80, 176)),
+    (BEAM_SPLIT_DOWN, (80, 192)),
+    (BEAM_SPLIT_LEFT, (80, 208)),
+    (BEAM_HALF_UP, (80, 0)),
+    (BEAM_HALF_RIGHT, (16, 0)),
+    (BEAM_HALF_DOWN, (80, 16)),
+    (BEAM_HALF_LEFT, (16, 16)),
+];
+
 pub fn animated_sprite(texture: SpriteRef, active: bool, frame: u8) -> Sprite {
     let offset = if active { frame + 1 } else { 0 } * 16;
     Sprite::new(texture).uv_offset(Vector2::new(offset as i32, 0))
@@ -105,45 +146,10 @@ pub fn init(assets: &mut AssetConstructor) {
     assets.register_sprite(interface, TOGGLE_INACTIVE, (10, 19), (8, 5));
     assets.register_sprite(interface, X, (33, 20), (5, 6));
 
-    let tiles = assets.register_atlas(include_atlas!("textures/tilemap.png"));
-    assets.register_sprite(tiles, EMPTY_TILE_A, (0, 288), (16, 16));
-    assets.register_sprite(tiles, EMPTY_TILE_B, (16, 288), (16, 16));
-    assets.register_sprite(tiles, PERMANENT_TILE_A, (32, 288), (16, 16));
-    assets.register_sprite(tiles, PERMANENT_TILE_B, (48, 288), (16, 16));
-    assets.register_sprite(tiles, DYNAMIC_TILE_A, (96, 304), (16, 16));
-    assets.register_sprite(tiles, DYNAMIC_TILE_B, (112, 304), (16, 16));
-    assets.register_sprite(tiles, DYNAMIC_TILE_OUTLINE, (96, 288), (16, 16));
-
-    assets.register_sprite(tiles, TILE_WALL, (0, 208), (16, 16));
-    assets.register_sprite(tiles, TILE_DETECTOR, (0, 192), (16, 16));
-    assets.register_sprite(tiles, TILE_DELAY, (0, 256), (16, 16));
-    assets.register_sprite(tiles, TILE_MIRROR_A, (0, 64), (16, 16));
-    assets.register_sprite(tiles, TILE_MIRROR_B, (0, 80), (16, 16));
-    assets.register_sprite(tiles, TILE_SPLITTER_A, (0, 160), (16, 16));
-    assets.register_sprite(tiles, TILE_SPLITTER_B, (0, 176), (16, 16));
-    assets.register_sprite(tiles, TILE_GALVO_UP, (64, 224), (16, 16));
-    assets.register_sprite(tiles, TILE_GALVO_RIGHT, (0, 224), (16, 16));
-    assets.register_sprite(tiles, TILE_GALVO_DOWN, (64, 240), (16, 16));
-    assets.register_sprite(tiles, TILE_GALVO_LEFT, (0, 240), (16, 16));
-    assets.register_sprite(tiles, TILE_EMITTER_UP, (16, 128), (16, 16));
-    assets.register_sprite(tiles, TILE_EMITTER_DOWN, (16, 144), (16, 16));
-    assets.register_sprite(tiles, TILE_EMITTER_LEFT, (16, 112), (16, 16));
-    assets.register_sprite(tiles, TILE_EMITTER_RIGHT, (16, 96), (16, 16));
-
-    assets.register_sprite(tiles, BEAM_FULL_HORIZONTAL, (16, 32), (16, 16));
-    assets.register_sprite(tiles, BEAM_FULL_VERTICAL, (16, 48), (16, 16));
-    assets.register_sprite(tiles, BEAM_REFLECT_UP_LEFT, (80, 32), (16, 16));
-    assets.register_sprite(tiles, BEAM_REFLECT_DOWN_LEFT, (80, 48), (16, 16));
-    assets.register_sprite(tiles, BEAM_REFLECT_UP_RIGHT, (80, 80), (16, 16));
-    assets.register_sprite(tiles, BEAM_REFLECT_DOWN_RIGHT, (80, 64), (16, 16));
-    assets.register_sprite(tiles, BEAM_SPLIT_UP, (80, 160), (16, 16));
-    assets.register_sprite(tiles, BEAM_SPLIT_RIGHT, (80, 176), (16, 16));
-    assets.register_sprite(tiles, BEAM_SPLIT_DOWN, (80, 192), (16, 16));
-    assets.register_sprite(tiles, BEAM_SPLIT_LEFT, (80, 208), (16, 16));
-    assets.register_sprite(tiles, BEAM_HALF_UP, (80, 0), (16, 16));
-    assets.register_sprite(tiles, BEAM_HALF_RIGHT, (16, 0), (16, 16));
-    assets.register_sprite(tiles, BEAM_HALF_DOWN, (80, 16), (16, 16));
-    assets.register_sprite(tiles, BEAM_HALF_LEFT, (16, 16), (16, 16));
+    let tiles = assets.register_atlas(tilemap());
+    for (sprite, uv) in TILE_SPRITES {
+        assets.register_sprite(tiles, sprite, uv, (16, 16));
+    }
 
     load_font(
         assets,
@@ -166,6 +172,10 @@ fn load_font(assets: &mut AssetConstructor, asset: FontRef, atlas: RgbaImage, de
     assets.register_font(font, asset, descriptor);
 }
 
+pub fn tilemap() -> RgbaImage {
+    include_atlas!("textures/tilemap.png")
+}
+
 pub fn icon(size: u32) -> Icon {
     let image = image::load_from_memory(include_asset!("textures/icon.png"))
         .unwrap()
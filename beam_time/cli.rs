@@ -0,0 +1,81 @@
+//! Commands that can be run without opening a window, like exporting boards
+//! to images.
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{Context, Result, bail};
+use beam_logic::level::default::DEFAULT_LEVELS;
+use log::info;
+
+use crate::{
+    consts::ANIMATION_EXPORT_TICKS,
+    game::{board::Board, render::export},
+};
+
+const EXPORT_USAGE: &str = "Usage: beam_time export <board> <output.png|gif|apng> [--ticks <n>] [--delay <ms>] [--scale <n>]";
+
+/// Runs the command given in the program arguments. Returns None if there
+/// was no known command, in which case the game should start as normal.
+pub fn run() -> Option<Result<()>> {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    match args.first()?.as_str() {
+        "export" => Some(export(&args[1..])),
+        _ => None,
+    }
+}
+
+fn export(args: &[String]) -> Result<()> {
+    let (mut ticks, mut delay, mut scale) = (None, 100, 4);
+    let mut positional = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--ticks" => ticks = Some(value(args.next())?),
+            "--delay" => delay = value(args.next())?,
+            "--scale" => scale = value(args.next())?,
+            _ => positional.push(arg),
+        }
+    }
+
+    let [input, output] = positional[..] else {
+        bail!(EXPORT_USAGE);
+    };
+
+    let board = load_board(input)?;
+    let path = Path::new(output);
+    if path.extension().is_some_and(|x| x == "png") {
+        let sim = ticks.map(|ticks| {
+            let mut sim = export::simulation_state(&board);
+            (0..ticks).for_each(|_| export::step(&board, &mut sim));
+            sim.board
+        });
+        export::save_png(&board, sim.as_ref(), 0, scale, path)?;
+    } else {
+        let ticks = ticks.unwrap_or(ANIMATION_EXPORT_TICKS);
+        export::save_animation(&board, ticks, delay, scale, path)?;
+    }
+
+    info!("Exported {input} to {output}");
+    Ok(())
+}
+
+/// Loads a board and attaches the level it was made for, so permanent tiles
+/// are shown correctly.
+fn load_board(path: &str) -> Result<Board> {
+    let mut board = Board::load(&PathBuf::from(path))?;
+    board.transient.level = (board.meta.level.as_ref())
+        .and_then(|meta| DEFAULT_LEVELS.iter().find(|level| level.id == meta.id));
+    Ok(board)
+}
+
+fn value<T: FromStr>(arg: Option<&String>) -> Result<T> {
+    let arg = arg.context(EXPORT_USAGE)?;
+    arg.parse()
+        .ok()
+        .with_context(|| format!("Invalid value `{arg}`"))
+}
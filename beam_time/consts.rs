@@ -7,6 +7,7 @@ use url::Url;
 pub const MAX_HISTORY: usize = 100;
 pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(60 * 5);
 pub const WATERFALL: MemoryKey = memory_key!();
+pub const ANIMATION_EXPORT_TICKS: usize = 100;
 
 pub const AUTHOR_HOMEPAGE: &str = "https://connorcode.com";
 #[cfg(any(not(feature = "steam"), feature = "discord"))]
@@ -29,6 +30,11 @@ pub mod color {
     pub const ERROR: Rgb<f32> = Rgb::hex(0xE43636);
     pub const MODAL: Rgb<f32> = Rgb::hex(0xA6A6A6);
     pub const MODAL_BORDER: Rgb<f32> = Rgb::hex(0x757575);
+
+    pub const DIFF_ADDED: Rgb<f32> = Rgb::hex(0x7BD88F);
+    pub const DIFF_REMOVED: Rgb<f32> = Rgb::hex(0xE43636);
+    pub const DIFF_CHANGED: Rgb<f32> = Rgb::hex(0xF2C94C);
+    pub const DIFF_CONFLICT: Rgb<f32> = Rgb::hex(0xF2994A);
 }
 
 pub mod layer {
@@ -58,9 +64,15 @@ pub mod spacing {
 pub mod paths {
     pub const CAMPAIGN: &str = "campaign";
     pub const SANDBOX: &str = "sandbox";
+    pub const CHALLENGES: &str = "challenges";
+
+    pub const EXPORTS: &str = "exports";
 
     pub const CONFIG: &str = "config.toml";
     pub const SOLVED: &str = "solved.bin";
+    pub const UPLOAD_QUEUE: &str = "upload_queue.bin";
+    pub const SIGNING_KEY: &str = "signing_key.bin";
+    pub const ACCOUNT_TOKEN: &str = "account_token.txt";
 }
 
 pub mod keybind {
@@ -75,6 +87,7 @@ pub mod keybind {
     // navigation
     pub const BACK: KeyCode = KeyCode::Escape;
     pub const CONTINUE: KeyCode = KeyCode::Enter;
+    pub const CHALLENGE: KeyCode = KeyCode::KeyW;
     pub const OVERWRITE: KeyCode = KeyCode::AltLeft;
 
     // selections
@@ -92,6 +105,7 @@ pub mod keybind {
     pub const FLIP_V: KeyCode = KeyCode::KeyV;
     pub const FLIP_H: KeyCode = KeyCode::KeyH;
     pub const PICK: KeyCode = KeyCode::KeyQ;
+    pub const CHIP: KeyCode = KeyCode::KeyB;
 
     // game stuff
     pub const PLAY: KeyCode = KeyCode::KeyF;
@@ -99,6 +113,8 @@ pub mod keybind {
     pub const STEP: KeyCode = KeyCode::Space;
 
     pub const NOTE: KeyCode = KeyCode::KeyN;
+    pub const EXPORT: KeyCode = KeyCode::F2;
+    pub const HIDE_DIFF: KeyCode = KeyCode::KeyG;
     pub const SPEED_UP: KeyCode = KeyCode::Equal;
     pub const SPEED_DOWN: KeyCode = KeyCode::Minus;
     pub const SPEED_RESET: KeyCode = KeyCode::Digit0;
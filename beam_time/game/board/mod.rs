@@ -1,6 +1,7 @@
 use std::{
     fs::{self, File},
-    path::PathBuf,
+    io::Cursor,
+    path::{Path, PathBuf},
     time::Instant,
 };
 
@@ -15,21 +16,27 @@ use uuid::Uuid;
 use crate::{
     app::App, consts::AUTOSAVE_INTERVAL, game::achievements::award_sandbox_playtime_achievements,
 };
-use beam_logic::{level::Level, tile::Tile};
+use beam_logic::{chip::Chip, diff::Diff, level::Level, tile::Tile};
 use common::{consts::BINCODE_OPTIONS, map::Map};
 
 use super::{history::History, holding::Holding, selection::SelectionState};
 
+pub mod text;
 pub mod unloaded;
 mod upgrade;
 
-pub const SAVE_VERSION: u32 = 6;
+pub const SAVE_VERSION: u32 = 8;
 
 #[derive(Default, Serialize, Deserialize)]
 pub struct Board {
     pub meta: BoardMeta,
     pub notes: Vec<Note>,
     pub tiles: Map<Tile>,
+    /// Chips placed on this board, indexed by the `index` of each
+    /// `Tile::Chip`.
+    pub chips: Vec<Chip>,
+    /// The solution this board was duplicated from, if any.
+    pub origin: Option<Origin>,
 
     #[serde(skip)]
     pub transient: TransientBoardState,
@@ -43,6 +50,11 @@ pub struct TransientBoardState {
     pub save_path: Option<PathBuf>,
     pub selection: SelectionState,
     pub deleting: bool,
+    /// Changes from another solution to highlight on the board.
+    pub diff: Option<Diff>,
+    /// Prevents any changes to the tiles, used when viewing other people's
+    /// solutions.
+    pub read_only: bool,
 
     pub open_timestamp: Instant,
     pub trash: bool,
@@ -73,6 +85,16 @@ pub struct LevelStats {
     pub latency: u32,
 }
 
+/// A snapshot of the solution a board was duplicated from, used as the
+/// common ancestor when overlaying the two.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Origin {
+    /// File name of the solution that was duplicated.
+    pub file: String,
+    /// Its tiles at the time it was duplicated.
+    pub tiles: Map<Tile>,
+}
+
 #[derive(Default, Clone, Serialize, Deserialize)]
 pub struct Note {
     pub position: Vector2<f32>,
@@ -96,8 +118,12 @@ impl Board {
     pub fn load(path: &PathBuf) -> Result<Self> {
         info!("Loading board from {path:?}");
 
-        let file = File::open(path)?;
-        let mut board = upgrade::load(file)?;
+        let data = fs::read(path)?;
+        let mut board = if text::is_text(&data) {
+            text::decode(str::from_utf8(&data)?)?
+        } else {
+            upgrade::load(Cursor::new(data))?
+        };
         board.transient.save_path = Some(path.to_path_buf());
 
         trace!("{:?}", board.meta);
@@ -105,8 +131,12 @@ impl Board {
     }
 
     pub fn load_meta(path: &PathBuf) -> Result<BoardMeta> {
-        let file = File::open(path)?;
-        let meta = upgrade::load_meta(file)?;
+        let data = fs::read(path)?;
+        if text::is_text(&data) {
+            return Ok(text::decode(str::from_utf8(&data)?)?.meta);
+        }
+
+        let meta = upgrade::load_meta(Cursor::new(data))?;
         Ok(meta)
     }
 
@@ -128,8 +158,12 @@ impl Board {
             fs::create_dir_all(parent)?;
         }
 
-        let file = File::create(path)?;
-        BINCODE_OPTIONS.serialize_into(file, &self)?;
+        if is_text_path(path) {
+            fs::write(path, text::encode(&self))?;
+        } else {
+            let file = File::create(path)?;
+            BINCODE_OPTIONS.serialize_into(file, &self)?;
+        }
 
         info!("Save took {:?}", start.elapsed());
         Ok(())
@@ -164,6 +198,8 @@ impl Board {
     pub fn reset(&mut self) {
         self.notes.clear();
         self.tiles = Map::default();
+        self.chips.clear();
+        self.origin = None;
 
         if let (Some(meta), Some(level)) = (&mut self.meta.level, self.transient.level) {
             meta.solved = None;
@@ -171,6 +207,47 @@ impl Board {
         };
     }
 
+    /// Adds a chip to the board, reusing an identical chip if the board
+    /// already has one. Returns the index to use for its `Tile::Chip`.
+    pub fn insert_chip(&mut self, chip: &Chip) -> u32 {
+        let index = self
+            .chips
+            .iter()
+            .position(|x| x == chip)
+            .unwrap_or_else(|| {
+                self.chips.push(chip.clone());
+                self.chips.len() - 1
+            });
+        index as u32
+    }
+
+    /// Finds the tiles this board and `other` were both derived from, for
+    /// merging them. If one was duplicated from the other, it's the snapshot
+    /// taken at the time, otherwise it's the level's starting tiles. Paths
+    /// are the save files of each board.
+    pub fn common_ancestor<'a>(
+        &'a self,
+        path: &Path,
+        other: &'a Board,
+        other_path: &Path,
+    ) -> Option<&'a Map<Tile>> {
+        let is_file =
+            |origin: &Origin, path: &Path| path.file_name().is_some_and(|x| *x == *origin.file);
+
+        match (&self.origin, &other.origin) {
+            (_, Some(theirs)) if is_file(theirs, path) => Some(&theirs.tiles),
+            (Some(ours), _) if is_file(ours, other_path) => Some(&ours.tiles),
+            // Copies of the same solution can only use their snapshot if
+            // nothing changed between duplicating each one
+            (Some(ours), Some(theirs))
+                if ours.file == theirs.file && ours.tiles == theirs.tiles =>
+            {
+                Some(&ours.tiles)
+            }
+            _ => self.transient.level.map(|x| &x.tiles),
+        }
+    }
+
     pub fn tile_props(&self, tile: &Tile, pos: &Vector2<i32>) -> (bool, bool, bool) {
         (tile.is_empty(), self.is_permanent(pos), tile.id().is_some())
     }
@@ -182,6 +259,11 @@ impl Board {
     }
 }
 
+/// Boards saved with a `.txt` extension use the text format.
+fn is_text_path(path: &Path) -> bool {
+    path.extension().is_some_and(|x| x == "txt")
+}
+
 impl BoardMeta {
     pub fn is_solved(&self) -> bool {
         self.level
@@ -201,6 +283,8 @@ impl Default for TransientBoardState {
             save_path: None,
             selection: Default::default(),
             deleting: false,
+            diff: None,
+            read_only: false,
 
             open_timestamp: Instant::now(),
             trash: false,
@@ -215,6 +299,8 @@ impl Clone for Board {
             meta: self.meta.clone(),
             notes: self.notes.clone(),
             tiles: self.tiles.clone(),
+            chips: self.chips.clone(),
+            origin: self.origin.clone(),
             transient: TransientBoardState::default(),
         }
     }
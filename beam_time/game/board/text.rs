@@ -0,0 +1,260 @@
+//! Text version of the board save format, used for any save file ending in
+//! `.txt`, like boards exported with "Export as Text" or imported from the
+//! clipboard. The tiles of the board and of each chip are stored with
+//! [`beam_logic::text`], so solutions can be checked into version control and
+//! reviewed like code.
+//!
+//! ```text
+//! beam-time board v1
+//! name: Half Adder
+//! level: 58fc60ca-3831-4f27-a29a-b4878a5dd68a
+//! solved: 12300 4
+//! size: 8 6
+//! last-played: 2025-11-14T12:00:00Z
+//! playtime: 360
+//!
+//! [tiles]
+//! origin 0 5
+//! E> .. M\
+//!
+//! [note 2.5 1] Title
+//! > Body of the note
+//!
+//! [chip 0] Xor
+//! origin 0 0
+//! ...
+//! ```
+//!
+//! Chips nested inside of other chips use their index path, so the second
+//! chip inside the first chip of the board is `[chip 0.1]`.
+
+use std::{fmt::Write, path::Path};
+
+use anyhow::{Context, Result, bail};
+use chrono::DateTime;
+use engine::exports::nalgebra::Vector2;
+use slug::slugify;
+use uuid::Uuid;
+
+use beam_logic::{chip::Chip, text};
+
+use super::{Board, LevelMeta, LevelStats, Note, SAVE_VERSION, unloaded::UnloadedBoard};
+
+pub const HEADER: &str = "beam-time board v1";
+
+pub fn is_text(data: &[u8]) -> bool {
+    data.starts_with(HEADER.as_bytes())
+}
+
+pub fn encode(board: &Board) -> String {
+    let mut out = format!("{HEADER}\n");
+    let meta = &board.meta;
+
+    writeln!(out, "name: {}", meta.name).unwrap();
+    if let Some(level) = &meta.level {
+        writeln!(out, "level: {}", level.id).unwrap();
+        if let Some(solved) = &level.solved {
+            writeln!(out, "solved: {} {}", solved.cost, solved.latency).unwrap();
+        }
+    }
+    if let Some(size) = meta.size {
+        writeln!(out, "size: {} {}", size.x, size.y).unwrap();
+    }
+    writeln!(out, "last-played: {}", meta.last_played.to_rfc3339()).unwrap();
+    writeln!(out, "playtime: {}", meta.playtime).unwrap();
+
+    write!(out, "\n[tiles]\n{}", text::encode(&board.tiles)).unwrap();
+
+    for note in board.notes.iter() {
+        let (x, y) = (note.position.x, note.position.y);
+        writeln!(out, "\n[note {x} {y}] {}", note.title).unwrap();
+        for line in note.body.lines() {
+            writeln!(out, "> {line}").unwrap();
+        }
+    }
+
+    encode_chips(&mut out, &board.chips, "");
+    out
+}
+
+pub fn decode(data: &str) -> Result<Board> {
+    let mut lines = data.lines().enumerate().peekable();
+    if lines.next().map(|(_, x)| x.trim()) != Some(HEADER) {
+        bail!("Missing `{HEADER}` header");
+    }
+
+    let mut board = Board::default();
+    board.meta.version = SAVE_VERSION;
+
+    while let Some((i, line)) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        // Collects the lines of a section, up until the next section header
+        let mut section = || {
+            let mut body = String::new();
+            while let Some((_, line)) = lines.next_if(|(_, x)| !x.starts_with('[')) {
+                body.push_str(line);
+                body.push('\n');
+            }
+            body
+        };
+
+        let context = || format!("Invalid board text on line {}", i + 1);
+        if let Some(header) = line.strip_prefix('[') {
+            let (header, title) = header.split_once(']').with_context(context)?;
+            let (title, mut args) = (title.trim(), header.split_whitespace());
+
+            match args.next() {
+                Some("tiles") => board.tiles = text::decode(&section()).with_context(context)?,
+                Some("note") => {
+                    let mut position = || args.next().context("Missing note position");
+                    let position = Vector2::new(position()?.parse()?, position()?.parse()?);
+                    let body = section();
+                    let body = (body.lines())
+                        .filter(|x| !x.trim().is_empty())
+                        .map(|x| {
+                            x.strip_prefix('>')
+                                .context("Note lines must start with `>`")
+                        })
+                        .map(|x| x.map(|x| x.strip_prefix(' ').unwrap_or(x)))
+                        .collect::<Result<Vec<_>>>()
+                        .with_context(context)?
+                        .join("\n");
+
+                    board.notes.push(Note {
+                        position,
+                        title: title.into(),
+                        body,
+                    });
+                }
+                Some("chip") => {
+                    let path = args.next().context("Missing chip index")?;
+                    let tiles = text::decode(&section()).with_context(context)?;
+                    let chip = Chip::new(title.into(), tiles, Vec::new())?;
+                    insert_chip(&mut board.chips, path, chip).with_context(context)?;
+                }
+                _ => bail!("Unknown section `[{header}]` on line {}", i + 1),
+            }
+
+            continue;
+        }
+
+        let (key, value) = line.split_once(':').with_context(context)?;
+        let value = value.trim();
+        let meta = &mut board.meta;
+        match key.trim() {
+            "name" => meta.name = value.into(),
+            "level" => {
+                meta.level = Some(LevelMeta {
+                    id: value.parse()?,
+                    solved: meta.level.take().and_then(|x| x.solved),
+                })
+            }
+            "solved" => {
+                let (cost, latency) = value.split_once(' ').with_context(context)?;
+                let level = meta
+                    .level
+                    .as_mut()
+                    .context("Solved boards must have a level")?;
+                level.solved = Some(LevelStats {
+                    cost: cost.parse()?,
+                    latency: latency.trim().parse()?,
+                });
+            }
+            "size" => {
+                let (x, y) = value.split_once(' ').with_context(context)?;
+                meta.size = Some(Vector2::new(x.parse()?, y.trim().parse()?));
+            }
+            "last-played" => meta.last_played = DateTime::parse_from_rfc3339(value)?.into(),
+            "playtime" => meta.playtime = value.parse()?,
+            _ => bail!("Unknown key `{key}` on line {}", i + 1),
+        }
+    }
+
+    Ok(board)
+}
+
+/// Creates a new save in `dir` from a board in the text format, like one
+/// copied with "Copy as Text". The board has to be for the given level, or a
+/// sandbox if there is none. The save keeps using the text format.
+pub fn import(data: &str, dir: &Path, level: Option<Uuid>) -> Result<UnloadedBoard> {
+    let board = decode(data)?;
+    match (board.meta.level.as_ref().map(|x| x.id), level) {
+        (Some(_), None) => bail!("This board is a level solution, import it from the level"),
+        (None, Some(_)) => bail!("This board is a sandbox, import it from the sandbox list"),
+        (a, b) if a != b => bail!("This board is a solution to a different level"),
+        _ => {}
+    }
+
+    let name = format!("{}_{}.txt", slugify(&board.meta.name), Uuid::new_v4());
+    let (path, meta) = (dir.join(name), board.meta.clone());
+    board.save_exact(&path)?;
+    Ok(UnloadedBoard { path, meta })
+}
+
+fn encode_chips(out: &mut String, chips: &[Chip], path: &str) {
+    for (i, chip) in chips.iter().enumerate() {
+        let path = format!("{path}{i}");
+        writeln!(out, "\n[chip {path}] {}", chip.name).unwrap();
+        out.push_str(&text::encode(&chip.tiles));
+        encode_chips(out, &chip.chips, &format!("{path}."));
+    }
+}
+
+/// Adds a chip at the given index path. Chips must be listed in order, so the
+/// last index of the path has to be the next free index of its parent.
+fn insert_chip(mut chips: &mut Vec<Chip>, path: &str, chip: Chip) -> Result<()> {
+    let path = (path.split('.'))
+        .map(|x| x.parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let (last, parents) = path.split_last().context("Empty chip path")?;
+
+    for &parent in parents {
+        chips = &mut chips
+            .get_mut(parent)
+            .context("Chip parent not defined")?
+            .chips;
+    }
+
+    if *last != chips.len() {
+        bail!("Chips must be listed in order");
+    }
+
+    chips.push(chip);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use engine::exports::nalgebra::Vector2;
+
+    use super::{HEADER, decode, encode};
+    use crate::game::board::{Board, Note};
+
+    #[test]
+    fn notes_round_trip() {
+        let mut board = Board::default();
+        board.meta.name = "Notes".into();
+        board.notes.push(Note {
+            position: Vector2::new(2.5, -1.0),
+            title: "Title".into(),
+            body: "First line\n\n> Quoted".into(),
+        });
+
+        let decoded = decode(&encode(&board)).unwrap();
+        assert_eq!(decoded.meta.name, "Notes");
+        assert_eq!(decoded.notes.len(), 1);
+        assert_eq!(decoded.notes[0].position, board.notes[0].position);
+        assert_eq!(decoded.notes[0].title, board.notes[0].title);
+        assert_eq!(decoded.notes[0].body, board.notes[0].body);
+    }
+
+    #[test]
+    fn note_lines_need_prefix() {
+        let text = format!("{HEADER}\nname: Notes\n\n[note 0 0] Title\n> Body\nStray line\n");
+        assert!(decode(&text).is_err());
+    }
+}
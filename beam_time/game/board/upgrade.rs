@@ -18,6 +18,8 @@ pub fn load<R: Read + Seek>(mut data: R) -> Result<Board> {
         3 => version_3::Board,
         4 => version_4::Board,
         5 => version_5::Board,
+        6 => version_6::Board,
+        7 => version_7::Board,
         SAVE_VERSION => super::Board
     ]);
 
@@ -34,7 +36,7 @@ pub fn load_meta<R: Read + Seek>(mut data: R) -> Result<BoardMeta> {
 
     let meta = versions!(version, data, [
         3..=5 => version_5::BoardMeta,
-        SAVE_VERSION => super::BoardMeta
+        6..=SAVE_VERSION => super::BoardMeta
     ]);
 
     Ok(meta)
@@ -195,6 +197,58 @@ mod version_5 {
     }
 }
 
+mod version_6 {
+    use crate::game::board::Note;
+
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct Board {
+        meta: BoardMeta,
+        notes: Vec<Note>,
+        tiles: Map<Tile>,
+    }
+
+    impl From<Board> for super::Board {
+        fn from(value: Board) -> Self {
+            Self {
+                meta: value.meta,
+                notes: value.notes,
+                tiles: value.tiles,
+                ..Default::default()
+            }
+        }
+    }
+}
+
+mod version_7 {
+    use beam_logic::chip::Chip;
+
+    use crate::game::board::Note;
+
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct Board {
+        meta: BoardMeta,
+        notes: Vec<Note>,
+        tiles: Map<Tile>,
+        chips: Vec<Chip>,
+    }
+
+    impl From<Board> for super::Board {
+        fn from(value: Board) -> Self {
+            Self {
+                meta: value.meta,
+                notes: value.notes,
+                tiles: value.tiles,
+                chips: value.chips,
+                ..Default::default()
+            }
+        }
+    }
+}
+
 macro versions($ver:expr, $data:expr, [$($version:pat => $module:ty),*]) {
     match $ver {
         $(
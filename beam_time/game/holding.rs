@@ -1,6 +1,8 @@
 use std::collections::BTreeSet;
 
+use ahash::{HashMap, HashMapExt};
 use beam_logic::{
+    chip::Chip,
     level::{ElementLocation, Level},
     tile::Tile,
 };
@@ -21,7 +23,47 @@ use crate::{
 
 use super::pancam::Pancam;
 
-pub type ClipboardItem = Vec<(Vector2<i32>, Tile)>;
+/// Tiles copied from a board along with the chips they use. The `index` of
+/// each `Tile::Chip` points into `chips` rather than the source board's chips,
+/// and is remapped when pasted.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardItem {
+    pub tiles: Vec<(Vector2<i32>, Tile)>,
+    pub chips: Vec<Chip>,
+}
+
+impl ClipboardItem {
+    /// Copies the chips used by the tiles out of the source board's chips.
+    /// Chip tiles that don't point at a chip are dropped.
+    pub fn new(tiles: Vec<(Vector2<i32>, Tile)>, board_chips: &[Chip]) -> Self {
+        let mut chips = Vec::new();
+        let mut remap = HashMap::new();
+
+        let tiles = tiles
+            .into_iter()
+            .filter_map(|(pos, mut tile)| {
+                if let Tile::Chip { index, .. } = &mut tile {
+                    let chip = board_chips.get(*index as usize)?;
+                    *index = *remap.entry(*index).or_insert_with(|| {
+                        chips.push(chip.clone());
+                        chips.len() as u32 - 1
+                    });
+                }
+                Some((pos, tile))
+            })
+            .collect();
+
+        Self { tiles, chips }
+    }
+
+    /// Copy of the clipboard with the state of each tile reset.
+    pub fn generic(&self) -> Self {
+        Self {
+            tiles: self.tiles.iter().map(|(p, x)| (*p, x.generic())).collect(),
+            chips: self.chips.clone(),
+        }
+    }
+}
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub enum Holding {
@@ -40,7 +82,7 @@ impl Holding {
         match self {
             Holding::None => false,
             Holding::Tile(tile) => tile.id().is_some(),
-            Holding::Paste(items) => items.iter().any(|(_p, t)| t.id().is_some()),
+            Holding::Paste(items) => items.tiles.iter().any(|(_p, t)| t.id().is_some()),
         }
     }
 
@@ -55,7 +97,7 @@ impl Holding {
         match self {
             Holding::None => {}
             Holding::Tile(tile) => insert(tile.id()),
-            Holding::Paste(items) => items.iter().for_each(|(_p, t)| insert(t.id())),
+            Holding::Paste(items) => items.tiles.iter().for_each(|(_p, t)| insert(t.id())),
         }
         out
     }
@@ -82,7 +124,7 @@ impl Holding {
 
                 render_tile(ctx, pancam, &level, *tile, ctx.input.mouse());
             }
-            Holding::Paste(tiles) => {
+            Holding::Paste(ClipboardItem { tiles, .. }) => {
                 key_events!(ctx, {
                     keybind::ROTATE => {
                         if ctx.input.key_down(keybind::SHIFT) {
@@ -122,7 +164,7 @@ impl Holding {
         {
             match self {
                 Holding::Tile(tile) if tile.id().is_none() => *self = Holding::None,
-                Holding::Paste(items) => items.retain(|(_p, t)| t.id().is_some()),
+                Holding::Paste(items) => items.tiles.retain(|(_p, t)| t.id().is_some()),
                 _ => {}
             }
         }
@@ -1,11 +1,19 @@
 use std::mem;
 
-use beam_logic::simulation::{state::BeamState, tile::BeamTile};
+use beam_logic::{
+    level::validate::BoardError,
+    simulation::{state::BeamState, tile::BeamTile},
+    tile::Tile,
+};
 use engine::{exports::winit::event::MouseButton, graphics_context::GraphicsContext};
 
 use crate::{
     consts::keybind,
-    game::{board::Board, holding::Holding, pancam::Pancam},
+    game::{
+        board::Board,
+        holding::{ClipboardItem, Holding},
+        pancam::Pancam,
+    },
     util::key_events,
 };
 
@@ -41,7 +49,7 @@ impl Board {
             *active ^= true;
         }
 
-        if shift {
+        if shift || self.transient.read_only {
             return;
         }
 
@@ -66,23 +74,38 @@ impl Board {
                         self.transient.holding = Holding::Tile(old);
                     }
                 }
-                Holding::Paste(tiles) => {
+                Holding::Paste(ClipboardItem { tiles, chips }) => {
                     *sim = None;
                     let mut old = Vec::new();
                     let mut next = Vec::new();
 
                     let level = self.transient.level;
-                    for set @ (paste_pos, paste_tile) in tiles {
+                    for set @ (paste_pos, mut paste_tile) in tiles {
                         let pos = paste_pos + pos;
                         let current_tile = self.tiles.get(pos);
-                        if level
-                            .map(|x| x.permanent.contains(&pos))
-                            .unwrap_or_default()
-                            || level.map(|x| x.out_of_bounds(pos)).unwrap_or_default()
-                            || current_tile.id().is_some()
-                        {
-                            next.push(set);
-                            continue;
+                        let duplicate = (paste_tile.id())
+                            .is_some_and(|id| self.tiles.iter().any(|(_, x)| x.id() == Some(id)));
+
+                        // Tiles that are blocked here are kept to be placed
+                        // somewhere else, while ones that can't be placed in
+                        // the level at all are dropped
+                        match level.map_or(Ok(()), |x| x.check_placement(pos, &paste_tile)) {
+                            Ok(()) if duplicate => continue,
+                            Ok(()) if current_tile.id().is_none() => {}
+                            Ok(())
+                            | Err(BoardError::ModifiedPermanentTiles | BoardError::OutOfBounds) => {
+                                next.push(set);
+                                continue;
+                            }
+                            Err(_) => continue,
+                        }
+
+                        // Point chips at this board's copy of the chip
+                        if let Tile::Chip { index, .. } = &mut paste_tile {
+                            let Some(chip) = chips.get(*index as usize) else {
+                                continue;
+                            };
+                            *index = self.insert_chip(chip);
                         }
 
                         old.push((pos, current_tile));
@@ -91,7 +114,8 @@ impl Board {
 
                     self.transient.history.track_many(old);
                     if !next.is_empty() {
-                        self.transient.holding = Holding::Paste(next);
+                        self.transient.holding =
+                            Holding::Paste(ClipboardItem { tiles: next, chips });
                     }
                 }
                 x => self.transient.holding = x,
@@ -0,0 +1,31 @@
+//! Looks up levels by id. Along with the levels built into the game, these
+//! can be challenges that were fetched from the leaderboard server.
+
+use std::sync::Mutex;
+
+use beam_logic::level::{Level, default::DEFAULT_LEVELS};
+use uuid::Uuid;
+
+static CHALLENGES: Mutex<Vec<&'static Level>> = Mutex::new(Vec::new());
+
+pub fn find(id: Uuid) -> Option<&'static Level> {
+    (DEFAULT_LEVELS.iter().find(|x| x.id == id)).or_else(|| {
+        (CHALLENGES.lock().unwrap().iter())
+            .find(|x| x.id == id)
+            .copied()
+    })
+}
+
+/// Adds a challenge level, leaking it so boards can refer to it like the
+/// built-in levels. If the challenge was already added, the existing level is
+/// returned instead.
+pub fn add_challenge(level: Level) -> &'static Level {
+    let mut challenges = CHALLENGES.lock().unwrap();
+    if let Some(existing) = challenges.iter().find(|x| x.id == level.id) {
+        return existing;
+    }
+
+    let level = Box::leak(Box::new(level));
+    challenges.push(level);
+    level
+}
@@ -3,6 +3,7 @@ pub mod board;
 pub mod history;
 pub mod holding;
 mod input;
+pub mod levels;
 pub mod pancam;
 pub mod render;
 mod selection;
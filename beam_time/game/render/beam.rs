@@ -57,77 +57,89 @@ impl BeamStateRender for BeamState {
                 continue;
             }
 
-            let sprite = |texture: SpriteRef| {
-                Sprite::new(texture)
-                    .uv_offset(Vector2::new(16 * frame as i32, 0))
+            beam_sprites(tile, frame, |sprite| {
+                sprite
                     .scale(Vector2::repeat(pancam.scale))
                     .position(render_pos, Anchor::Center)
-            };
+                    .draw(ctx)
+            });
+        }
+    }
+}
 
-            // Play animation in reverse if beam is traveling in a direction
-            // opposite to the animation frames
-            let beam = |direction: Direction| {
-                let texture = match direction {
-                    Direction::Left | Direction::Right => BEAM_FULL_HORIZONTAL,
-                    Direction::Up | Direction::Down => BEAM_FULL_VERTICAL,
-                };
-                let frame = match direction {
-                    Direction::Left | Direction::Down => 2 - frame,
-                    Direction::Right | Direction::Up => frame,
-                };
-                sprite(texture).uv_offset(Vector2::new(16 * frame as i32, 0))
-            };
+/// Picks the sprites needed to draw the beams passing through a tile. This is
+/// shared by the game and the CPU renderer in `export.rs`.
+pub fn beam_sprites(tile: BeamTile, frame: u32, mut draw: impl FnMut(Sprite)) {
+    let sprite =
+        |texture: SpriteRef| Sprite::new(texture).uv_offset(Vector2::new(16 * frame as i32, 0));
 
-            match tile {
-                BeamTile::Beam { direction, .. } => beam(direction).draw(ctx),
-                BeamTile::CrossBeam { directions } => {
-                    directions.iter().for_each(|&x| beam(x).draw(ctx))
-                }
-                BeamTile::Mirror {
-                    galvoed,
-                    powered,
-                    direction,
-                } => {
-                    for (idx, _) in powered.iter().enumerate().filter(|x| x.1.is_some()) {
-                        let dir = direction ^ galvoed.any();
-                        let texture = MIRROR_TEXTURES[idx + dir as usize * 2];
-                        sprite(texture)
-                            .z_index(layer::LASER * (idx == 1) as i16)
-                            .draw(ctx);
-                    }
-                }
-                BeamTile::Splitter { direction, powered } => {
-                    for powered in powered.iter() {
-                        let index = (powered as usize + direction as usize * 2) % 4;
-                        sprite(SPLITTER_TEXTURES[index])
-                            .z_index(layer::LASER)
-                            .draw(ctx);
-                    }
-                }
-                BeamTile::Delay {
-                    powered,
-                    last_powered,
-                } => {
-                    for (idx, set) in [powered, last_powered].into_iter().enumerate() {
-                        for dir in set.iter() {
-                            sprite(HALF_BEAM[dir.opposite_if(idx > 0) as usize]).draw(ctx);
-                        }
-                    }
-                }
-                BeamTile::Galvo { powered, .. }
-                | BeamTile::Wall { powered }
-                | BeamTile::Detector { powered } => {
-                    for dir in powered.iter() {
-                        let layer = if dir == Direction::Down {
-                            layer::UNDER_LASER
-                        } else {
-                            layer::LASER
-                        };
-                        sprite(HALF_BEAM[dir as usize]).z_index(layer).draw(ctx);
-                    }
+    // Play animation in reverse if beam is traveling in a direction
+    // opposite to the animation frames
+    let beam = |direction: Direction| {
+        let texture = match direction {
+            Direction::Left | Direction::Right => BEAM_FULL_HORIZONTAL,
+            Direction::Up | Direction::Down => BEAM_FULL_VERTICAL,
+        };
+        let frame = match direction {
+            Direction::Left | Direction::Down => 2 - frame,
+            Direction::Right | Direction::Up => frame,
+        };
+        sprite(texture).uv_offset(Vector2::new(16 * frame as i32, 0))
+    };
+
+    match tile {
+        BeamTile::Beam { direction, .. } => draw(beam(direction)),
+        BeamTile::CrossBeam { directions } => directions.iter().for_each(|&x| draw(beam(x))),
+        BeamTile::Mirror {
+            galvoed,
+            powered,
+            direction,
+        } => {
+            for (idx, _) in powered.iter().enumerate().filter(|x| x.1.is_some()) {
+                let dir = direction ^ galvoed.any();
+                let texture = MIRROR_TEXTURES[idx + dir as usize * 2];
+                draw(sprite(texture).z_index(layer::LASER * (idx == 1) as i16));
+            }
+        }
+        BeamTile::Splitter { direction, powered } => {
+            for powered in powered.iter() {
+                let index = (powered as usize + direction as usize * 2) % 4;
+                draw(sprite(SPLITTER_TEXTURES[index]).z_index(layer::LASER));
+            }
+        }
+        BeamTile::Delay {
+            powered,
+            last_powered,
+        } => {
+            for (idx, set) in [powered, last_powered].into_iter().enumerate() {
+                for dir in set.iter() {
+                    draw(sprite(HALF_BEAM[dir.opposite_if(idx > 0) as usize]));
                 }
-                _ => {}
             }
         }
+        BeamTile::Galvo { powered, .. }
+        | BeamTile::Wall { powered }
+        | BeamTile::Detector { powered } => {
+            for dir in powered.iter() {
+                let layer = if dir == Direction::Down {
+                    layer::UNDER_LASER
+                } else {
+                    layer::LASER
+                };
+                draw(sprite(HALF_BEAM[dir as usize]).z_index(layer));
+            }
+        }
+        BeamTile::Chip {
+            powered, output, ..
+        } => {
+            for dir in powered.iter() {
+                draw(sprite(HALF_BEAM[dir as usize]));
+            }
+
+            for dir in output.iter() {
+                draw(sprite(HALF_BEAM[dir.opposite() as usize]));
+            }
+        }
+        _ => {}
     }
 }
@@ -3,14 +3,18 @@ use crate::{
     assets::{DYNAMIC_TILE_A, DYNAMIC_TILE_B},
     assets::{EMPTY_TILE_A, EMPTY_TILE_B, PERMANENT_TILE_A, PERMANENT_TILE_B},
     consts::keybind,
-    consts::layer,
+    consts::{color, layer},
     game::board::Board,
     game::pancam::Pancam,
-    ui::misc::tile_label,
+    ui::misc::{side_label, tile_label},
 };
+use beam_logic::diff::Change;
 use beam_logic::level::ElementLocation;
 use beam_logic::simulation::state::BeamState;
+use beam_logic::tile::Tile;
+use common::direction::Direction;
 use engine::{
+    assets::SpriteRef,
     drawable::sprite::Sprite,
     drawable::{Anchor, Drawable},
     exports::nalgebra::Vector2,
@@ -29,6 +33,10 @@ impl Board {
     ) {
         self.tick_autosave(state);
 
+        if ctx.input.key_pressed(keybind::HIDE_DIFF) {
+            self.transient.diff = None;
+        }
+
         let tile_counts = pancam.tile_counts(ctx.size());
         let frame = state.frame();
 
@@ -39,6 +47,7 @@ impl Board {
         self.render_notes(ctx, state, pancam);
 
         if sim.is_none()
+            && !self.transient.read_only
             && ctx.input.key_down(keybind::CTRL)
             && ctx.input.key_pressed(keybind::UNDO)
         {
@@ -60,22 +69,37 @@ impl Board {
                 let tile = self.tiles.get(pos);
                 let (empty, permanent, dynamic) = self.tile_props(&tile, &pos);
 
-                let gridset_index = match (permanent, dynamic) {
-                    (true, _) => 1,
-                    (false, true) => 2,
-                    _ => 0,
-                };
-                let grid_color = (pos.x.abs() + pos.y.abs()) as usize % 2;
-                let grid_tile = [
-                    [EMPTY_TILE_A, EMPTY_TILE_B],
-                    [PERMANENT_TILE_A, PERMANENT_TILE_B],
-                    [DYNAMIC_TILE_A, DYNAMIC_TILE_B],
-                ][gridset_index][grid_color];
-                let grid = Sprite::new(grid_tile)
+                let mut grid = Sprite::new(grid_tile(pos, permanent, dynamic))
                     .scale(Vector2::repeat(pancam.scale))
                     .position(render_pos, Anchor::Center)
                     .z_index(layer::TILE_BACKGROUND);
 
+                // Tint the background of tiles that differ from the compared
+                // solution, and show a ghost of any tiles that were removed
+                let diff = self.transient.diff.as_ref();
+                let change = diff.and_then(|x| x.changes.get(&pos));
+                if let Some(change) = change {
+                    grid = grid.color(match change {
+                        Change::Added(_) => color::DIFF_ADDED,
+                        Change::Removed(_) => color::DIFF_REMOVED,
+                        Change::Changed { .. } => color::DIFF_CHANGED,
+                    });
+                }
+
+                if diff.is_some_and(|x| x.conflicts.contains(&pos)) {
+                    grid = grid.color(color::DIFF_CONFLICT);
+                }
+
+                if let Some(Change::Removed(old)) = change
+                    && empty
+                {
+                    old.asset()
+                        .scale(Vector2::repeat(pancam.scale))
+                        .position(render_pos, Anchor::Center)
+                        .color(color::DIFF_REMOVED)
+                        .draw(ctx);
+                }
+
                 let element = (tile.id())
                     .map(ElementLocation::Dynamic)
                     .unwrap_or(ElementLocation::Static(pos));
@@ -84,6 +108,21 @@ impl Board {
                     label.z_index(layer::OVERLAY).draw(ctx);
                 }
 
+                if let Tile::Chip { index, rotation } = tile
+                    && let Some(chip) = self.chips.get(index as usize)
+                {
+                    let label =
+                        tile_label(pancam.scale, pancam.scale / 2.0, render_pos, &chip.name);
+                    label.z_index(layer::OVERLAY).draw(ctx);
+
+                    for side in Direction::ALL {
+                        if let Some(port) = chip.port(rotation, side) {
+                            let label = side_label(pancam.scale, render_pos, side, &port.name);
+                            label.z_index(layer::OVERLAY).draw(ctx);
+                        }
+                    }
+                }
+
                 if !empty {
                     let sprite = sim
                         .as_ref()
@@ -102,3 +141,19 @@ impl Board {
         }
     }
 }
+
+/// The background sprite of a tile, which alternates in a checkerboard
+/// pattern.
+pub fn grid_tile(pos: Vector2<i32>, permanent: bool, dynamic: bool) -> SpriteRef {
+    let gridset_index = match (permanent, dynamic) {
+        (true, _) => 1,
+        (false, true) => 2,
+        _ => 0,
+    };
+    let grid_color = (pos.x.abs() + pos.y.abs()) as usize % 2;
+    [
+        [EMPTY_TILE_A, EMPTY_TILE_B],
+        [PERMANENT_TILE_A, PERMANENT_TILE_B],
+        [DYNAMIC_TILE_A, DYNAMIC_TILE_B],
+    ][gridset_index][grid_color]
+}
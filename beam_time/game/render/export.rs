@@ -0,0 +1,308 @@
+//! Renders boards into images on the CPU by copying sprites straight out of
+//! the tilemap, so boards can be exported without creating a window.
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs::{self, File},
+    io::BufWriter,
+    path::Path,
+};
+
+use anyhow::{Result, bail};
+use beam_logic::simulation::{level_state::LevelResult, state::BeamState, tile::BeamTile};
+use common::map::Map;
+use engine::{assets::SpriteRef, drawable::sprite::Sprite, exports::nalgebra::Vector2};
+use image::{
+    Delay, Frame, RgbaImage,
+    codecs::gif::{GifEncoder, Repeat},
+    imageops::FilterType,
+};
+
+use crate::{
+    assets::{TILE_SPRITES, tilemap},
+    consts::layer,
+    game::board::Board,
+};
+
+use super::{
+    beam::beam_sprites,
+    board::grid_tile,
+    tile::{BeamTileBaseSprite, TileAsset},
+};
+
+const TILE_SIZE: u32 = 16;
+/// The largest width or height of an exported image, in pixels.
+const MAX_IMAGE_SIZE: u64 = 8192;
+
+pub struct ImageRenderer {
+    atlas: RgbaImage,
+    sprites: HashMap<SpriteRef, Vector2<u32>>,
+}
+
+impl ImageRenderer {
+    pub fn new() -> Self {
+        let sprites = (TILE_SPRITES.iter())
+            .map(|(sprite, uv)| (*sprite, Vector2::new(uv.0, uv.1)))
+            .collect();
+
+        Self {
+            atlas: tilemap(),
+            sprites,
+        }
+    }
+
+    /// Renders a board, along with the beams of a simulation if provided.
+    /// Each tile will take up `scale` * 16 pixels, unless that would make the
+    /// image larger than `MAX_IMAGE_SIZE`, in which case the scale is lowered.
+    pub fn render(
+        &self,
+        board: &Board,
+        sim: Option<&Map<BeamTile>>,
+        frame: u8,
+        scale: u32,
+    ) -> Result<RgbaImage> {
+        let (size, scale) = image_size(board, scale)?;
+        let (min, max) = bounds(board);
+        let mut image = RgbaImage::new(size.x, size.y);
+
+        let mut sprites = Vec::new();
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let pos = Vector2::new(x, y);
+                let tile = board.tiles.get(pos);
+                let (empty, permanent, dynamic) = board.tile_props(&tile, &pos);
+
+                let grid = Sprite::new(grid_tile(pos, permanent, dynamic));
+                sprites.push(grid.z_index(layer::TILE_BACKGROUND));
+
+                if !empty {
+                    let sprite = sim.and_then(|x| x.get(pos).base_sprite(frame));
+                    sprites.push(sprite.unwrap_or_else(|| tile.asset()));
+                }
+
+                if let Some(sim) = sim {
+                    beam_sprites(sim.get(pos), frame as u32, |x| sprites.push(x));
+                }
+
+                // The image is stored top to bottom, but y points up in the
+                // world
+                let offset = Vector2::new(x - min.x, max.y - y).map(|x| x as u32 * TILE_SIZE);
+                sprites.sort_by_key(Sprite::get_z_index);
+                for sprite in sprites.drain(..) {
+                    self.blit(&mut image, &sprite, offset);
+                }
+            }
+        }
+
+        if scale > 1 {
+            let size = size * scale;
+            image = image::imageops::resize(&image, size.x, size.y, FilterType::Nearest);
+        }
+
+        Ok(image)
+    }
+
+    /// Alpha blends a sprite onto the image, tinted by the sprite's color.
+    fn blit(&self, image: &mut RgbaImage, sprite: &Sprite, offset: Vector2<u32>) {
+        let uv = self.sprites[&sprite.get_texture()].map(|x| x as i32) + sprite.get_uv_offset();
+        let color = sprite.get_color();
+        let tint = [color.r, color.g, color.b];
+
+        for y in 0..TILE_SIZE {
+            for x in 0..TILE_SIZE {
+                let source = self.atlas.get_pixel(uv.x as u32 + x, uv.y as u32 + y).0;
+                if source[3] == 0 {
+                    continue;
+                }
+
+                let alpha = source[3] as f32 / 255.0;
+
+                let target = &mut image.get_pixel_mut(offset.x + x, offset.y + y).0;
+                for i in 0..3 {
+                    let color = source[i] as f32 * tint[i];
+                    target[i] = (color * alpha + target[i] as f32 * (1.0 - alpha)) as u8;
+                }
+                target[3] = (source[3] as f32 + target[3] as f32 * (1.0 - alpha)) as u8;
+            }
+        }
+    }
+}
+
+/// Renders a board to a PNG file, creating the parent directory if needed.
+pub fn save_png(
+    board: &Board,
+    sim: Option<&Map<BeamTile>>,
+    frame: u8,
+    scale: u32,
+    path: &Path,
+) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let image = ImageRenderer::new().render(board, sim, frame, scale)?;
+    image.save(path)?;
+    Ok(())
+}
+
+/// Runs the board for `ticks` ticks and saves every tick as a frame of an
+/// animated GIF or APNG, picked by the file extension.
+pub fn save_animation(
+    board: &Board,
+    ticks: usize,
+    delay_ms: u16,
+    scale: u32,
+    path: &Path,
+) -> Result<()> {
+    let extension = path
+        .extension()
+        .and_then(|x| x.to_str())
+        .unwrap_or_default();
+    if !matches!(extension, "gif" | "apng") {
+        bail!("Animations must be saved as a `.gif` or `.apng`");
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let (size, scale) = image_size(board, scale)?;
+    let renderer = ImageRenderer::new();
+    let mut sim = simulation_state(board);
+    let frames = (0..ticks).map(|tick| {
+        step(board, &mut sim);
+        renderer.render(board, Some(&sim.board), (tick % 3) as u8, scale)
+    });
+
+    let file = BufWriter::new(File::create(path)?);
+    if extension == "gif" {
+        let mut encoder = GifEncoder::new_with_speed(file, 10);
+        encoder.set_repeat(Repeat::Infinite)?;
+
+        let delay = Delay::from_numer_denom_ms(delay_ms as u32, 1);
+        for frame in frames {
+            encoder.encode_frame(Frame::from_parts(frame?, 0, 0, delay))?;
+        }
+    } else {
+        let mut encoder = png::Encoder::new(file, size.x * scale, size.y * scale);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_animated(ticks as u32, 0)?;
+        encoder.set_frame_delay(delay_ms, 1000)?;
+
+        let mut writer = encoder.write_header()?;
+        for frame in frames {
+            writer.write_image_data(&frame?)?;
+        }
+        writer.finish()?;
+    }
+
+    Ok(())
+}
+
+/// Creates a simulation of the board. Boards made for a level run its test
+/// cases from the first one, like they do in game, so dynamic emitters are
+/// driven by the test inputs. Advance it with [`step`] so it keeps running
+/// after a test case passes or fails.
+pub fn simulation_state(board: &Board) -> BeamState {
+    simulation_from_case(board, 0)
+}
+
+/// Ticks a simulation made with [`simulation_state`]. Level simulations stop
+/// once the level has a result, so this starts over from the case after a
+/// failed one, or from the start once every case has passed.
+pub fn step(board: &Board, sim: &mut BeamState) {
+    if let Some(level) = &sim.level
+        && let Some(result) = level.result
+    {
+        let failed = matches!(result, LevelResult::Failed { .. });
+        let case = level.test_offset + level.test_case + failed as usize;
+        *sim = simulation_from_case(board, case);
+    }
+
+    sim.tick();
+}
+
+fn simulation_from_case(board: &Board, case: usize) -> BeamState {
+    let level = board.transient.level;
+    let case = level.map(|x| case % x.tests.cases.len());
+    BeamState::new(&board.tiles, level.map(Cow::Borrowed), case).with_chips(&board.chips)
+}
+
+/// The size of the rendered board in pixels at a scale of one, along with the
+/// scale lowered to keep the image within `MAX_IMAGE_SIZE`. Errors if the
+/// board doesn't fit even at a scale of one.
+fn image_size(board: &Board, scale: u32) -> Result<(Vector2<u32>, u32)> {
+    let (min, max) = bounds(board);
+    let tiles = max.map(i64::from) - min.map(i64::from);
+    let size = tiles.map(|x| (x + 1) as u64 * TILE_SIZE as u64);
+
+    let largest = size.x.max(size.y);
+    if largest > MAX_IMAGE_SIZE {
+        let (x, y) = (size.x, size.y);
+        bail!("Board is too large to export ({x}x{y} pixels, max is {MAX_IMAGE_SIZE})");
+    }
+
+    let scale = (scale.max(1) as u64).min(MAX_IMAGE_SIZE / largest) as u32;
+    Ok((size.map(|x| x as u32), scale))
+}
+
+/// The inclusive range of tiles to render. Sized boards use their size and
+/// unbounded boards use their tiles with one tile of padding.
+fn bounds(board: &Board) -> (Vector2<i32>, Vector2<i32>) {
+    if let Some(size) = board.meta.size {
+        return (Vector2::zeros(), size.map(|x| x as i32 - 1));
+    }
+
+    let (mut min, mut max) = (Vector2::repeat(i32::MAX), Vector2::repeat(i32::MIN));
+    for (pos, _) in board.tiles.iter() {
+        min = min.inf(&pos);
+        max = max.sup(&pos);
+    }
+
+    if board.tiles.tiles.is_empty() {
+        (min, max) = (Vector2::zeros(), Vector2::zeros());
+    }
+
+    (
+        min.map(|x| x.saturating_sub(1)),
+        max.map(|x| x.saturating_add(1)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use beam_logic::level::default::DEFAULT_LEVELS;
+
+    use crate::game::board::Board;
+
+    use super::{simulation_state, step};
+
+    #[test]
+    fn level_simulation_moves_through_cases() {
+        let level = &DEFAULT_LEVELS[0];
+        let mut board = Board {
+            tiles: level.tiles.clone(),
+            ..Board::default()
+        };
+        board.transient.level = Some(level);
+
+        // The unsolved board fails every case, so the simulation should keep
+        // starting over from the next one
+        let mut sim = simulation_state(&board);
+        let mut cases = Vec::new();
+        for _ in 0..1000 {
+            step(&board, &mut sim);
+            let level = sim.level.as_ref().unwrap();
+            let case = level.test_offset + level.test_case;
+            if cases.last() != Some(&case) {
+                cases.push(case);
+            }
+        }
+
+        let count = level.tests.cases.len();
+        assert!(cases.len() > count, "only ran cases {cases:?}");
+        assert!((0..count).all(|x| cases.contains(&x)));
+    }
+}
@@ -1,4 +1,5 @@
 pub mod beam;
 pub mod board;
+pub mod export;
 pub mod notes;
 pub mod tile;
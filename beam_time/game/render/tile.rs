@@ -1,11 +1,14 @@
 use beam_logic::{simulation::tile::BeamTile, tile::Tile};
 use engine::{assets::SpriteRef, drawable::sprite::Sprite, exports::nalgebra::Vector2};
 
-use crate::assets::{
-    BEAM_HALF_DOWN, BEAM_HALF_LEFT, BEAM_HALF_RIGHT, BEAM_HALF_UP, TILE_DELAY, TILE_DETECTOR,
-    TILE_EMITTER_DOWN, TILE_EMITTER_LEFT, TILE_EMITTER_RIGHT, TILE_EMITTER_UP, TILE_GALVO_DOWN,
-    TILE_GALVO_LEFT, TILE_GALVO_RIGHT, TILE_GALVO_UP, TILE_MIRROR_A, TILE_MIRROR_B,
-    TILE_SPLITTER_A, TILE_SPLITTER_B, TILE_WALL, animated_sprite,
+use crate::{
+    assets::{
+        BEAM_HALF_DOWN, BEAM_HALF_LEFT, BEAM_HALF_RIGHT, BEAM_HALF_UP, TILE_DELAY, TILE_DETECTOR,
+        TILE_EMITTER_DOWN, TILE_EMITTER_LEFT, TILE_EMITTER_RIGHT, TILE_EMITTER_UP, TILE_GALVO_DOWN,
+        TILE_GALVO_LEFT, TILE_GALVO_RIGHT, TILE_GALVO_UP, TILE_MIRROR_A, TILE_MIRROR_B,
+        TILE_SPLITTER_A, TILE_SPLITTER_B, TILE_WALL, animated_sprite,
+    },
+    consts::color,
 };
 
 pub const GALVO: [SpriteRef; 4] = [
@@ -56,6 +59,7 @@ impl TileAsset for Tile {
             Tile::Splitter { rotation, .. } => SPLITTER[*rotation as usize],
             Tile::Galvo { rotation, .. } => GALVO[*rotation as usize],
             Tile::Wall => TILE_WALL,
+            Tile::Chip { .. } => return Sprite::new(TILE_WALL).color(color::ACCENT),
         };
 
         Sprite::new(asset_ref)
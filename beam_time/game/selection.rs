@@ -10,7 +10,7 @@ use crate::{
 };
 use ahash::HashSet;
 use base64::{Engine, prelude::BASE64_STANDARD};
-use beam_logic::{level::Level, simulation::state::BeamState, tile::Tile};
+use beam_logic::{level::Level, simulation::state::BeamState, text, tile::Tile};
 use bincode::Options;
 use common::{consts::BINCODE_OPTIONS, direction::Direction, map::Map, misc::in_bounds};
 use engine::{
@@ -22,7 +22,11 @@ use engine::{
 };
 use thousands::Separable;
 
-use super::{board::Board, holding::Holding, pancam::Pancam};
+use super::{
+    board::{self, Board},
+    holding::{ClipboardItem, Holding},
+    pancam::Pancam,
+};
 
 #[derive(Default)]
 pub struct SelectionState {
@@ -56,8 +60,9 @@ impl Board {
         let ctrl = ctx.input.key_down(keybind::CTRL);
         let alt = ctx.input.key_down(keybind::OVERWRITE);
         let copy = ctx.input.key_pressed(keybind::COPY);
-        let cut = ctx.input.key_pressed(keybind::CUT);
-        let paste = ctx.input.key_pressed(keybind::PASTE);
+        let read_only = self.transient.read_only;
+        let cut = ctx.input.key_pressed(keybind::CUT) && !read_only;
+        let paste = ctx.input.key_pressed(keybind::PASTE) && !read_only;
 
         let in_level = self.transient.level.is_some();
         if let (Some((min, max)), false) = (this.working_selection, ctrl || alt || in_level) {
@@ -68,7 +73,7 @@ impl Board {
             let size = max - min + Vector2::repeat(1);
             let price = (min.x..=max.x)
                 .flat_map(|x| (min.y..=max.y).map(move |y| Vector2::new(x, y)))
-                .map(|pos| self.tiles.get(pos).price())
+                .map(|pos| self.tiles.get(pos).price_with_chips(&self.chips))
                 .sum::<u32>();
             let text = format!("{}x{} • ${}", size.x, size.y, price.separate_with_commas());
 
@@ -107,7 +112,7 @@ impl Board {
                 this.selection_start = None;
                 this.selection.clear();
             },
-            keybind::DELETE => {
+            keybind::DELETE => if !read_only {
                 let mut old = Vec::new();
                 for pos in this.selection.iter() {
                     let tile = self.tiles.get(*pos);
@@ -146,33 +151,38 @@ impl Board {
             list.iter_mut().for_each(|(pos, _)| *pos -= origin);
 
             this.selection.clear();
+            let item = ClipboardItem::new(list, &self.chips);
             if ctx.input.key_down(SHIFT) {
-                let mut map = Map::default();
-                list.into_iter().for_each(|(pos, tile)| map.set(pos, tile));
+                let mut board = Board::default();
+                (item.tiles.into_iter()).for_each(|(pos, tile)| board.tiles.set(pos, tile));
+                board.chips = item.chips;
 
-                let bytes = BINCODE_OPTIONS.serialize(&map).unwrap();
-                let b64 = BASE64_STANDARD.encode(&bytes);
-                state.system_clipboard.set_text(b64).unwrap()
+                // Only use the full board format if it's needed to carry chips
+                let text = if board.chips.is_empty() {
+                    text::encode(&board.tiles)
+                } else {
+                    board::text::encode(&board)
+                };
+                state.system_clipboard.set_text(text).unwrap()
             } else {
                 *sim = None;
-                self.transient.holding = Holding::Paste(list.clone());
-                state.clipboard = Some(list);
+                self.transient.holding = Holding::Paste(item.clone());
+                state.clipboard = Some(item);
             }
         }
 
         if ctrl && paste {
             if ctx.input.key_down(SHIFT) {
-                if let Ok(b64) = state.system_clipboard.get_text()
-                    && let Ok(bytes) = BASE64_STANDARD.decode(b64)
-                    && let Ok(tiles) = BINCODE_OPTIONS.deserialize::<Map<Tile>>(&bytes)
+                if let Ok(clipboard) = state.system_clipboard.get_text()
+                    && let Some(item) = decode_clipboard(&clipboard)
+                    && !item.tiles.is_empty()
                 {
                     *sim = None;
-                    self.transient.holding = Holding::Paste(tiles.iter().collect())
+                    self.transient.holding = Holding::Paste(item)
                 }
             } else if let Some(item) = &state.clipboard {
                 *sim = None;
-                self.transient.holding =
-                    Holding::Paste(item.iter().map(|(p, x)| (*p, x.generic())).collect());
+                self.transient.holding = Holding::Paste(item.generic());
             }
         }
     }
@@ -245,8 +255,27 @@ impl Board {
     }
 }
 
+/// Snippets with chips are copied as a whole text board, otherwise just the
+/// tiles are copied. Snippets copied before the text format was added are
+/// base64 encoded bincode.
+fn decode_clipboard(clipboard: &str) -> Option<ClipboardItem> {
+    if board::text::is_text(clipboard.as_bytes()) {
+        let board = board::text::decode(clipboard).ok()?;
+        return Some(ClipboardItem::new(
+            board.tiles.iter().collect(),
+            &board.chips,
+        ));
+    }
+
+    let tiles = text::decode(clipboard).ok().or_else(|| {
+        let bytes = BASE64_STANDARD.decode(clipboard.trim()).ok()?;
+        BINCODE_OPTIONS.deserialize::<Map<Tile>>(&bytes).ok()
+    })?;
+    Some(ClipboardItem::new(tiles.iter().collect(), &[]))
+}
+
 fn valid_tile(pos: Vector2<i32>, level: Option<&Level>, size: Option<Vector2<u32>>) -> bool {
-    let moveable = level.map(|x| x.permanent.contains(&pos)) != Some(true);
+    let moveable = level.is_none_or(|x| x.check_position(pos).is_ok());
     let in_bounds = size
         .map(|size| in_bounds(pos, (Vector2::repeat(0), size.map(|x| x as i32 - 1))))
         .unwrap_or(true);
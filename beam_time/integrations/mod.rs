@@ -47,6 +47,15 @@ impl Integrations {
         #[cfg(feature = "steam")]
         self.steam.on_tick();
     }
+
+    /// Takes the auth ticket that proves who the user is to the leaderboard,
+    /// once it's been issued.
+    pub fn take_auth_ticket(&self) -> Option<Vec<u8>> {
+        #[cfg(feature = "steam")]
+        return self.steam.take_auth_ticket();
+        #[cfg(not(feature = "steam"))]
+        return None;
+    }
 }
 
 impl Integrations {
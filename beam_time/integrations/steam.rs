@@ -1,11 +1,18 @@
+use std::sync::Arc;
+
 use anyhow::Result;
+use leaderboard::api::signing::STEAM_TICKET_IDENTITY;
 use log::{trace, warn};
-use steamworks::Client;
+use parking_lot::Mutex;
+use steamworks::{CallbackHandle, Client, TicketForWebApiResponse};
 
 use crate::{consts::STEAM_ID, integrations::RichPresence};
 
 pub struct Steam {
     client: Client,
+    /// Auth ticket for the leaderboard server, once Steam has issued it.
+    auth_ticket: Arc<Mutex<Option<Vec<u8>>>>,
+    _ticket_callback: CallbackHandle,
 }
 
 impl Steam {
@@ -13,7 +20,21 @@ impl Steam {
         let client = Client::init_app(STEAM_ID)?;
         let user_id = client.user().steam_id().raw();
         client.user_stats().request_user_stats(user_id);
-        Ok(Self { client })
+
+        let auth_ticket = Arc::<Mutex<Option<Vec<u8>>>>::default();
+        let ticket = auth_ticket.clone();
+        let _ticket_callback =
+            client.register_callback(move |x: TicketForWebApiResponse| match x.result {
+                Ok(()) => *ticket.lock() = Some(x.ticket),
+                Err(err) => warn!("Error getting auth ticket: {err}"),
+            });
+        (client.user()).authentication_session_ticket_for_webapi(STEAM_TICKET_IDENTITY);
+
+        Ok(Self {
+            client,
+            auth_ticket,
+            _ticket_callback,
+        })
     }
 
     pub fn on_tick(&mut self) {
@@ -24,6 +45,10 @@ impl Steam {
         self.client.user().steam_id().raw()
     }
 
+    pub fn take_auth_ticket(&self) -> Option<Vec<u8>> {
+        self.auth_ticket.lock().take()
+    }
+
     pub fn award_achievement(&self, name: &str) {
         trace!("Awarding achievement `{name}`");
         let stats = self.client.user_stats();
@@ -0,0 +1,32 @@
+use anyhow::Result;
+use beam_logic::level::Level;
+use chrono::{DateTime, Utc};
+
+use crate::game::levels;
+use leaderboard::api::{challenge::GetChallengeResponse, results::RankedResult};
+
+/// The challenge running on the leaderboard server.
+pub struct Challenge {
+    pub level: &'static Level,
+    pub end: DateTime<Utc>,
+
+    pub scoreboard: Vec<RankedResult>,
+    pub total: u32,
+}
+
+impl Challenge {
+    pub fn from_response(response: GetChallengeResponse) -> Result<Self> {
+        let level = Level::load_slice(response.level.as_bytes())?;
+        Ok(Self {
+            level: levels::add_challenge(level),
+            end: DateTime::from_timestamp(response.end as i64, 0).unwrap_or_default(),
+
+            scoreboard: response.scoreboard,
+            total: response.total,
+        })
+    }
+
+    pub fn has_ended(&self) -> bool {
+        Utc::now() >= self.end
+    }
+}
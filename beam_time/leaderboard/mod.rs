@@ -0,0 +1,861 @@
+use std::{
+    fmt::Debug,
+    fs,
+    hash::Hash,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use ahash::{HashMap, HashMapExt};
+use anyhow::{Context, Result};
+use bincode::Options;
+use clone_macro::clone;
+use log::{info, trace, warn};
+use poll_promise::Promise;
+use serde::de::DeserializeOwned;
+use ureq::{Agent, Body, RequestBuilder, http::Response, typestate::WithBody};
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    app::LeaderboardServer,
+    consts::{LEADERBOARD_SERVER, paths},
+};
+use beam_logic::{
+    simulation::level_state::LevelResult,
+    tile::{TILE_VERSION, Tile},
+};
+use common::{
+    consts::{API_HMAC_KEY, BINCODE_OPTIONS},
+    map::Map,
+    user::UserId,
+};
+use leaderboard::api::{
+    accounts::{AccountResponse, LinkCodeResponse, PostAccount, PostLink, PostLinkCode, PutName},
+    challenge::GetChallengeResponse,
+    errors::ApiError,
+    groups::PostGroup,
+    hmac::hash,
+    info::{API_VERSION, GetInfoResponse},
+    results::{
+        GetFrontierResponse, GetRankingResponse, GetResultsResponse, GetSolutionRef,
+        GetUserResultsResponse, PutResultsRef, Ranking,
+    },
+    signing::{PostKey, public_key, request_message, sign},
+};
+
+mod challenge;
+mod queue;
+#[cfg(test)]
+mod tests;
+pub use challenge::Challenge;
+pub use queue::UploadStatus;
+use queue::{UploadQueue, backoff};
+
+type PendingResult<T> = Promise<Result<T>>;
+
+/// How often the server info is refetched while it can't be loaded.
+const INFO_RETRY: Duration = Duration::from_secs(30);
+
+pub struct LeaderboardManager {
+    client: Agent,
+    server: Url,
+    key: Vec<u8>,
+    /// This install's secret key, used to sign requests once its public key
+    /// has been registered with the server.
+    secret: [u8; 32],
+    registration: Option<PendingResult<()>>,
+    registered: bool,
+    /// Failed registrations are retried with a backoff, or as soon as there
+    /// is a new Steam auth ticket to send.
+    registration_attempts: u32,
+    registration_retry: Instant,
+    /// Why the server refused to register the key, shown to the player.
+    registration_error: Option<ApiError>,
+    /// Hex encoded Steam auth ticket, proving to the server that the key is
+    /// being registered by the user.
+    auth_ticket: Option<String>,
+    /// Code of the group the user is in, which is sent to the server once
+    /// the key is registered and whenever it changes.
+    group: Option<String>,
+    group_synced: bool,
+    membership: Option<PendingResult<()>>,
+
+    /// The user's account, loaded from the server once the key is
+    /// registered. Its token is saved so it's available before then.
+    account: Option<AccountResponse>,
+    account_path: PathBuf,
+    account_synced: bool,
+    account_request: Option<PendingResult<AccountResponse>>,
+    link_code: Option<LinkCodeResponse>,
+    link_code_request: Option<PendingResult<LinkCodeResponse>>,
+    /// Why the last change to the account failed.
+    account_error: Option<String>,
+
+    info: Requests<(), GetInfoResponse>,
+    /// When the server info is next fetched if it isn't loaded.
+    info_retry: Instant,
+    challenge_response: Requests<(), Option<GetChallengeResponse>>,
+    challenge: Option<Challenge>,
+    results: Requests<Uuid, GetResultsResponse>,
+    group_results: Requests<Uuid, GetResultsResponse>,
+    rankings: Requests<(Uuid, Ranking), GetRankingResponse>,
+    personal: Requests<Uuid, Option<GetUserResultsResponse>>,
+    frontiers: Requests<Uuid, GetFrontierResponse>,
+    queue: UploadQueue,
+    /// In flight uploads from the queue, along with their id in the queue and
+    /// level.
+    uploads: Vec<(Uuid, Uuid, PendingResult<LevelResult>)>,
+    /// Why the server rejected the last solution uploaded for each level.
+    rejections: HashMap<Uuid, ApiError>,
+    download: Option<(Uuid, PendingResult<Map<Tile>>)>,
+}
+
+/// Background requests along with the cached responses of finished ones.
+struct Requests<K, T: Send + 'static> {
+    pending: Vec<(K, PendingResult<T>)>,
+    cache: HashMap<K, T>,
+}
+
+impl LeaderboardManager {
+    /// Creates a manager for the official leaderboard, or a self-hosted one
+    /// if configured.
+    pub fn new(data_dir: &Path, custom: Option<&LeaderboardServer>, group: Option<String>) -> Self {
+        let server = custom
+            .and_then(|x| match parse_server(&x.url) {
+                Ok(url) => Some(url),
+                Err(err) => {
+                    warn!("Invalid leaderboard server `{}`: {err}", x.url);
+                    None
+                }
+            })
+            .unwrap_or_else(|| LEADERBOARD_SERVER.clone());
+        let key = (custom.and_then(|x| x.hmac_key.as_ref()))
+            .map(|x| x.as_bytes())
+            .unwrap_or(API_HMAC_KEY)
+            .to_vec();
+        info!("Using leaderboard server {server}");
+
+        let client = Agent::new_with_config(
+            Agent::config_builder()
+                .timeout_global(Some(Duration::from_secs(15)))
+                .user_agent(concat!("beam-time/", env!("CARGO_PKG_VERSION")))
+                .build(),
+        );
+
+        let mut this = Self {
+            client,
+            queue: UploadQueue::load(data_dir.join(paths::UPLOAD_QUEUE), server.to_string()),
+            server,
+            key,
+            secret: load_secret(&data_dir.join(paths::SIGNING_KEY)),
+            registration: None,
+            registered: false,
+            registration_attempts: 0,
+            registration_retry: Instant::now(),
+            registration_error: None,
+            auth_ticket: None,
+            group,
+            group_synced: false,
+            membership: None,
+
+            account: load_account(&data_dir.join(paths::ACCOUNT_TOKEN)),
+            account_path: data_dir.join(paths::ACCOUNT_TOKEN),
+            account_synced: false,
+            account_request: None,
+            link_code: None,
+            link_code_request: None,
+            account_error: None,
+
+            info: Requests::default(),
+            info_retry: Instant::now(),
+            challenge_response: Requests::default(),
+            challenge: None,
+            results: Requests::default(),
+            group_results: Requests::default(),
+            rankings: Requests::default(),
+            personal: Requests::default(),
+            frontiers: Requests::default(),
+            uploads: Vec::new(),
+            rejections: HashMap::new(),
+            download: None,
+        };
+        this.fetch_info();
+        this
+    }
+
+    /// Queues a solution to be uploaded in the background. Once the upload
+    /// finishes, any cached results for the level are refreshed.
+    pub fn publish_solution(&mut self, user: &UserId, level: Uuid, board: &Map<Tile>) {
+        let results = PutResultsRef {
+            user,
+            board,
+            version: TILE_VERSION,
+        };
+        let body = BINCODE_OPTIONS.serialize(&results).unwrap();
+        self.queue.push(level, body);
+        self.rejections.remove(&level);
+    }
+
+    /// The status of a solution to the level that is waiting to be uploaded.
+    pub fn upload_status(&self, level: Uuid) -> Option<UploadStatus> {
+        self.queue.status(level)
+    }
+
+    /// Why the server rejected the last solution uploaded for the level, if
+    /// it was.
+    pub fn upload_error(&self, level: Uuid) -> Option<&ApiError> {
+        self.rejections.get(&level)
+    }
+
+    /// Returns the server's version if it uses an incompatible version of the
+    /// api. Nothing is uploaded to incompatible servers.
+    pub fn incompatible(&self) -> Option<&str> {
+        let info = self.info.cache.get(&())?;
+        (info.api_version != API_VERSION).then_some(&info.version)
+    }
+
+    /// Fetches the server info, which is cleared whenever the server can't be
+    /// reached so it's checked again in case the server was updated.
+    fn fetch_info(&mut self) {
+        let path = self.server.join("info").unwrap();
+        self.info.fetch(&self.client, (), path);
+        self.info_retry = Instant::now() + INFO_RETRY;
+    }
+
+    /// Registers this install's public key to the user, along with the Steam
+    /// auth ticket if there is one. Nothing is uploaded until the key is
+    /// registered, so uploads are never sent unsigned for a user with a key.
+    fn register_key(&mut self, user: &UserId) {
+        let path = self.server.join("keys").unwrap();
+        let request = PostKey {
+            user: *user,
+            public_key: public_key(&self.secret),
+        };
+        let body = BINCODE_OPTIONS.serialize(&request).unwrap();
+
+        let (key, ticket) = (self.key.clone(), self.auth_ticket.clone());
+        let promise = Promise::spawn_thread(
+            "key_register",
+            clone!([{ self.client } as client], move || {
+                let mut request = authorize(client.post(path.as_str()), &key, None, &body);
+                if let Some(ticket) = ticket {
+                    request = request.header("Steam-Ticket", ticket.as_str());
+                }
+
+                let mut response = (request.config().http_status_as_error(false).build())
+                    .header("Content-Length", body.len().to_string().as_str())
+                    .send(&body)
+                    .context("Error registering key")?;
+                check_status(&mut response)
+            }),
+        );
+
+        self.registration = Some(promise);
+    }
+
+    /// Sets the Steam auth ticket sent when registering the key, retrying
+    /// registration right away if it failed, even if the key was refused.
+    pub fn set_auth_ticket(&mut self, ticket: &[u8]) {
+        self.auth_ticket = Some(hex::encode(ticket));
+        self.registration_retry = Instant::now();
+        self.registration_error = None;
+    }
+
+    /// Why the server wouldn't register this install's key, if it didn't.
+    /// Solutions are held in the queue until it does.
+    pub fn registration_error(&self) -> Option<&ApiError> {
+        self.registration_error.as_ref()
+    }
+
+    /// Moves the user into the group with the code, or out of their current
+    /// group if None. Group results are refetched once the server has the new
+    /// membership.
+    pub fn join_group(&mut self, code: Option<String>) {
+        self.group = code;
+        self.group_synced = false;
+    }
+
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    fn sync_group(&mut self, user: &UserId) {
+        let path = self.server.join("groups").unwrap();
+        let request = PostGroup {
+            user: *user,
+            code: self.group.clone(),
+        };
+        let body = BINCODE_OPTIONS.serialize(&request).unwrap();
+
+        let (key, secret) = (self.key.clone(), self.signing_key());
+        let promise = Promise::spawn_thread(
+            "group_join",
+            clone!([{ self.client } as client], move || {
+                authorize(client.post(path.as_str()), &key, secret.as_ref(), &body)
+                    .header("Content-Length", body.len().to_string().as_str())
+                    .send(&body)
+                    .context("Error joining group")?;
+                Ok(())
+            }),
+        );
+
+        self.group_synced = true;
+        self.membership = Some(promise);
+    }
+
+    pub fn account_name(&self) -> Option<&str> {
+        self.account.as_ref()?.name.as_deref()
+    }
+
+    /// If the user has an account and no changes to it are in progress.
+    pub fn account_ready(&self) -> bool {
+        self.account.is_some() && self.account_request.is_none()
+    }
+
+    pub fn account_error(&self) -> Option<&str> {
+        self.account_error.as_deref()
+    }
+
+    /// Gets the user's account, which is created if they don't have one yet.
+    fn load_account(&mut self, user: &UserId) {
+        let path = self.server.join("accounts").unwrap();
+        let body = BINCODE_OPTIONS
+            .serialize(&PostAccount { user: *user })
+            .unwrap();
+        let (key, secret) = (self.key.clone(), self.signing_key());
+        self.account_request = Some(Promise::spawn_thread(
+            "account_load",
+            clone!([{ self.client } as client], move || {
+                let request = authorize(client.post(path.as_str()), &key, secret.as_ref(), &body);
+                account_request(request, &body)
+            }),
+        ));
+    }
+
+    /// Changes the account's display name, or removes it if None.
+    pub fn set_name(&mut self, name: Option<String>) {
+        let Some(account) = &self.account else {
+            return;
+        };
+
+        let path = self.server.join("accounts/name").unwrap();
+        let request = PutName {
+            token: account.token.clone(),
+            name,
+        };
+        let body = BINCODE_OPTIONS.serialize(&request).unwrap();
+        self.account_request = Some(Promise::spawn_thread(
+            "account_name",
+            clone!([{ self.client } as client], move || {
+                account_request(client.put(path.as_str()), &body)
+            }),
+        ));
+    }
+
+    /// Requests a code for linking another device to the account, to be
+    /// retrieved later with `link_code`.
+    pub fn request_link_code(&mut self) {
+        let Some(account) = &self.account else {
+            return;
+        };
+
+        let path = self.server.join("accounts/link-code").unwrap();
+        let request = PostLinkCode {
+            token: account.token.clone(),
+        };
+        let body = BINCODE_OPTIONS.serialize(&request).unwrap();
+        self.link_code_request = Some(Promise::spawn_thread(
+            "account_link_code",
+            clone!([{ self.client } as client], move || {
+                account_request(client.post(path.as_str()), &body)
+            }),
+        ));
+    }
+
+    pub fn link_code(&self) -> Option<&LinkCodeResponse> {
+        self.link_code.as_ref()
+    }
+
+    /// Links this device to the account that created the code, replacing
+    /// the account it was linked to.
+    pub fn link_account(&mut self, user: &UserId, code: String) {
+        let path = self.server.join("accounts/link").unwrap();
+        let body = BINCODE_OPTIONS
+            .serialize(&PostLink { user: *user, code })
+            .unwrap();
+        let (key, secret) = (self.key.clone(), self.signing_key());
+        self.account_request = Some(Promise::spawn_thread(
+            "account_link",
+            clone!([{ self.client } as client], move || {
+                let request = authorize(client.post(path.as_str()), &key, secret.as_ref(), &body);
+                account_request(request, &body)
+            }),
+        ));
+    }
+
+    /// The secret key to sign requests with, if it has been registered.
+    fn signing_key(&self) -> Option<[u8; 32]> {
+        self.registered.then_some(self.secret)
+    }
+
+    fn start_upload(&mut self, id: Uuid, level: Uuid, body: Vec<u8>) {
+        let (path, key) = (self.results_path(level), self.key.clone());
+        let secret = self.signing_key();
+        let promise = Promise::spawn_thread(
+            "solution_publish",
+            clone!([{ self.client } as client], move || {
+                let request = authorize(client.put(path.as_str()), &key, secret.as_ref(), &body);
+                let mut response = (request.config().http_status_as_error(false).build())
+                    .header("Content-Length", body.len().to_string().as_str())
+                    .send(&body)
+                    .with_context(|| format!("Error publishing solution for {level}"))?;
+                check_status(&mut response)?;
+                (response.body_mut())
+                    .read_json::<LevelResult>()
+                    .context("Error deserializing result")
+            }),
+        );
+
+        self.uploads.push((id, level, promise));
+    }
+
+    /// Starts downloading the solution at `index` in a level's ranking. The
+    /// server will only send it if the user has solved the level themselves.
+    /// Replaces any download that is still in progress.
+    pub fn download_solution(&mut self, user: &UserId, level: Uuid, ranking: Ranking, index: u32) {
+        let path = self.server.join(&format!("{level}/solution")).unwrap();
+        let request = GetSolutionRef {
+            user,
+            ranking,
+            index,
+        };
+        let body = BINCODE_OPTIONS.serialize(&request).unwrap();
+
+        let (key, secret) = (self.key.clone(), self.signing_key());
+        let promise = Promise::spawn_thread(
+            "solution_download",
+            clone!([{ self.client } as client], move || {
+                let request = authorize(client.post(path.as_str()), &key, secret.as_ref(), &body);
+                let mut response = (request.config().http_status_as_error(false).build())
+                    .header("Content-Length", body.len().to_string().as_str())
+                    .send(&body)?;
+                check_status(&mut response)?;
+
+                let solution = response.body_mut().read_to_vec()?;
+                Ok(BINCODE_OPTIONS.deserialize(&solution)?)
+            }),
+        );
+
+        self.download = Some((level, promise));
+    }
+
+    /// Returns the level and board of a finished solution download.
+    pub fn take_download(&mut self) -> Option<(Uuid, Map<Tile>)> {
+        self.download.as_ref()?.1.ready()?;
+        let (level, download) = self.download.take()?;
+        match download.block_and_take() {
+            Ok(solution) => Some((level, solution)),
+            Err(err) => {
+                warn!("Error downloading solution for {level}: {err}");
+                None
+            }
+        }
+    }
+
+    /// Fetches the challenge that is currently running in the background, to
+    /// be retrieved later with `challenge`. The previous challenge is kept
+    /// until the new one is loaded, so its scoreboard is refreshed.
+    pub fn fetch_challenge(&mut self) {
+        let path = self.server.join("challenge").unwrap();
+        (self.challenge_response).fetch(&self.client, (), path);
+    }
+
+    pub fn challenge(&self) -> Option<&Challenge> {
+        self.challenge.as_ref()
+    }
+
+    /// Will start a task to fetch the results for that level in the background.
+    /// You can retrieve the results later on using the `get_results` method.
+    pub fn fetch_results(&mut self, level: Uuid) {
+        let path = self.results_path(level);
+        (self.results).fetch(&self.client, level, path);
+    }
+
+    /// Fetches the results of just the user's group in the background, to be
+    /// retrieved later with `get_group_results`. Does nothing if the user
+    /// isn't in a group.
+    pub fn fetch_group_results(&mut self, level: Uuid) {
+        let Some(group) = &self.group else {
+            return;
+        };
+
+        let mut path = self.results_path(level);
+        path.query_pairs_mut().append_pair("group", group);
+        (self.group_results).fetch(&self.client, level, path);
+    }
+
+    /// Fetches the best solutions to a level for a ranking in the background,
+    /// to be retrieved later with `get_ranking`.
+    pub fn fetch_ranking(&mut self, level: Uuid, ranking: Ranking) {
+        let path = (self.server)
+            .join(&format!("{level}/top/{}", ranking.name()))
+            .unwrap();
+        (self.rankings).fetch(&self.client, (level, ranking), path);
+    }
+
+    /// Fetches the user's own solution to a level and its ranks in the
+    /// background, to be retrieved later with `get_personal`.
+    pub fn fetch_personal(&mut self, user: &UserId, level: Uuid) {
+        let path = (self.server)
+            .join(&format!("{level}/results/{user}"))
+            .unwrap();
+        (self.personal).fetch(&self.client, level, path);
+    }
+
+    /// Fetches the pareto frontier of all solutions to a level in the
+    /// background, to be retrieved later with `get_frontier`.
+    pub fn fetch_frontier(&mut self, level: Uuid) {
+        let path = self.server.join(&format!("{level}/frontier")).unwrap();
+        (self.frontiers).fetch(&self.client, level, path);
+    }
+
+    pub fn get_results(&self, level: Uuid) -> Option<&GetResultsResponse> {
+        self.results.cache.get(&level)
+    }
+
+    pub fn get_group_results(&self, level: Uuid) -> Option<&GetResultsResponse> {
+        self.group_results.cache.get(&level)
+    }
+
+    pub fn get_ranking(&self, level: Uuid, ranking: Ranking) -> Option<&GetRankingResponse> {
+        self.rankings.cache.get(&(level, ranking))
+    }
+
+    /// Returns None while loading or if the user has not solved the level.
+    pub fn get_personal(&self, level: Uuid) -> Option<&GetUserResultsResponse> {
+        self.personal.cache.get(&level).and_then(|x| x.as_ref())
+    }
+
+    pub fn get_frontier(&self, level: Uuid) -> Option<&GetFrontierResponse> {
+        self.frontiers.cache.get(&level)
+    }
+
+    pub fn tick(&mut self, user: &UserId) {
+        self.info.tick("server info");
+        if self.info.cache.is_empty() && Instant::now() >= self.info_retry {
+            self.fetch_info();
+        }
+
+        self.results.tick("histogram data");
+        self.group_results.tick("group histogram data");
+        self.rankings.tick("ranking");
+        self.personal.tick("personal results");
+        self.frontiers.tick("pareto frontier");
+        self.challenge_response.tick("challenge");
+
+        // Responses are taken out of the cache so the next fetch isn't skipped
+        if let Some(response) = self.challenge_response.cache.remove(&()) {
+            self.challenge = response.and_then(|x| {
+                Challenge::from_response(x)
+                    .map_err(|err| warn!("Error loading challenge: {err}"))
+                    .ok()
+            });
+        }
+
+        // A key the server refused won't be accepted by asking again, so
+        // registration waits for a Steam auth ticket that can verify it
+        let refused = (self.registration_error.as_ref()).is_some_and(|x| !x.is_temporary());
+        if !self.registered
+            && !refused
+            && self.registration.is_none()
+            && Instant::now() >= self.registration_retry
+        {
+            self.register_key(user);
+        }
+
+        if let Some(registration) = &self.registration
+            && registration.ready().is_some()
+        {
+            match self.registration.take().unwrap().block_and_take() {
+                Ok(()) => {
+                    self.registered = true;
+                    self.registration_attempts = 0;
+                    self.registration_error = None;
+                }
+                Err(err) if is_rejection(&err) => {
+                    warn!("{err}, not retrying");
+                    self.registration_error = err.downcast::<ApiError>().ok();
+                }
+                Err(err) => {
+                    warn!("{err}, will retry later");
+                    self.registration_retry = Instant::now() + backoff(self.registration_attempts);
+                    self.registration_attempts += 1;
+                    self.registration_error = err.downcast::<ApiError>().ok();
+                }
+            }
+        }
+
+        if !self.group_synced && self.registered && self.membership.is_none() {
+            self.sync_group(user);
+        }
+
+        if let Some(membership) = &self.membership
+            && membership.ready().is_some()
+        {
+            match self.membership.take().unwrap().block_and_take() {
+                Ok(()) => {
+                    let levels = self.group_results.cache.drain().map(|x| x.0);
+                    for level in levels.collect::<Vec<_>>() {
+                        self.fetch_group_results(level);
+                    }
+                }
+                Err(err) => warn!("{err}"),
+            }
+        }
+
+        // Accounts can only be used by users with a registered key
+        if !self.account_synced && self.registered {
+            self.account_synced = true;
+            self.load_account(user);
+        }
+
+        if let Some(request) = &self.account_request
+            && request.ready().is_some()
+        {
+            match self.account_request.take().unwrap().block_and_take() {
+                Ok(account) => {
+                    if let Err(err) = fs::write(&self.account_path, &account.token) {
+                        warn!("Failed to save account token: {err}");
+                    }
+                    self.account = Some(account);
+                    self.account_error = None;
+                }
+                Err(err) => {
+                    warn!("Account request failed: {err}");
+                    self.account_error = Some(err.to_string());
+                }
+            }
+        }
+
+        if let Some(request) = &self.link_code_request
+            && request.ready().is_some()
+        {
+            match self.link_code_request.take().unwrap().block_and_take() {
+                Ok(code) => self.link_code = Some(code),
+                Err(err) => self.account_error = Some(err.to_string()),
+            }
+        }
+
+        // Uploads wait for the key to be registered so they can be signed, and
+        // for the server info so they aren't sent to an incompatible server
+        let compatible = self.info.cache.contains_key(&()) && self.incompatible().is_none();
+        if compatible && self.registered {
+            for (id, level, body) in self.queue.start_due() {
+                self.start_upload(id, level, body);
+            }
+        }
+
+        let mut i = 0;
+        while i < self.uploads.len() {
+            if self.uploads[i].2.ready().is_none() {
+                i += 1;
+                continue;
+            }
+
+            let (id, level, req) = self.uploads.remove(i);
+            match req.block_and_take() {
+                Ok(resp) => {
+                    trace!("Solution published for {level}: {resp:?}");
+                    self.queue.finish(id);
+                }
+                // Solutions the server rejected won't be accepted if they are
+                // sent again, unless it was only rate limiting
+                Err(err) if is_rejection(&err) => {
+                    warn!("Solution for {level} rejected: {err}");
+                    if let Ok(err) = err.downcast::<ApiError>() {
+                        self.rejections.insert(level, err);
+                    }
+                    self.queue.finish(id);
+                    continue;
+                }
+                // The server lost the key, so it's registered again before
+                // the upload is retried
+                Err(err)
+                    if err
+                        .downcast_ref::<ApiError>()
+                        .is_some_and(|x| x.is_key_error()) =>
+                {
+                    warn!("{err}, registering key again");
+                    self.queue.retry(id);
+                    self.registered = false;
+                    self.registration_retry = Instant::now();
+                    continue;
+                }
+                Err(err) => {
+                    warn!("{err}, will retry later");
+                    self.queue.retry(id);
+                    self.info.cache.clear();
+                    self.info_retry = Instant::now();
+                    continue;
+                }
+            }
+
+            // The new solution changes all the stats for this level, so refetch
+            // everything that's been loaded
+            let refresh_results = self.results.cache.remove(&level).is_some();
+            let refresh_group = self.group_results.cache.remove(&level).is_some();
+            self.rankings.cache.retain(|(id, _), _| *id != level);
+            self.personal.cache.remove(&level);
+            self.frontiers.cache.remove(&level);
+
+            refresh_results.then(|| self.fetch_results(level));
+            refresh_group.then(|| self.fetch_group_results(level));
+            self.fetch_personal(user, level);
+            self.fetch_frontier(level);
+        }
+    }
+}
+
+impl<K, T> Requests<K, T>
+where
+    K: Copy + Debug + Eq + Hash + Send + 'static,
+    T: DeserializeOwned + Send + 'static,
+{
+    fn fetch(&mut self, client: &Agent, key: K, path: Url) {
+        if self.cache.contains_key(&key) || self.pending.iter().any(|(x, _)| *x == key) {
+            return;
+        }
+
+        trace!("Fetching {path}");
+        let client = client.clone();
+        let promise = Promise::spawn_thread("leaderboard_fetch", move || {
+            Ok(client.get(path.as_str()).call()?.body_mut().read_json()?)
+        });
+
+        self.pending.push((key, promise));
+    }
+
+    fn tick(&mut self, name: &str) {
+        let mut i = 0;
+        while i < self.pending.len() {
+            if self.pending[i].1.ready().is_none() {
+                i += 1;
+                continue;
+            }
+
+            let (key, req) = self.pending.remove(i);
+            match req.block_and_take() {
+                Ok(x) => {
+                    self.cache.insert(key, x);
+                }
+                Err(err) => warn!("Error fetching {name} for {key:?}: {err}"),
+            }
+        }
+    }
+}
+
+impl<K, T: Send + 'static> Default for Requests<K, T> {
+    fn default() -> Self {
+        Self {
+            pending: Vec::new(),
+            cache: HashMap::new(),
+        }
+    }
+}
+
+impl LeaderboardManager {
+    fn results_path(&self, level: Uuid) -> Url {
+        self.server.join(&format!("{level}/results")).unwrap()
+    }
+}
+
+/// Adds the headers authenticating a request body. It's always hashed with
+/// the HMAC key for servers that don't support signatures, and signed along
+/// with the request's method and path if the user has a registered key.
+fn authorize(
+    request: RequestBuilder<WithBody>,
+    key: &[u8],
+    secret: Option<&[u8; 32]>,
+    body: &[u8],
+) -> RequestBuilder<WithBody> {
+    let request = request.header("Authorization", hex::encode(hash(key, body)));
+    let Some(secret) = secret else {
+        return request;
+    };
+
+    let method = request.method_ref().map(|x| x.as_str()).unwrap_or_default();
+    let path = request.uri_ref().map(|x| x.path()).unwrap_or_default();
+    let signature = sign(secret, &request_message(method, path, body));
+    request.header("Signature", hex::encode(signature))
+}
+
+/// Sends a request to the account api, where errors like a rejected name are
+/// shown to the player.
+fn account_request<T: DeserializeOwned>(
+    request: RequestBuilder<WithBody>,
+    body: &[u8],
+) -> Result<T> {
+    let mut response = (request.config().http_status_as_error(false).build())
+        .header("Content-Length", body.len().to_string().as_str())
+        .send(body)?;
+    check_status(&mut response)?;
+    Ok(response.body_mut().read_json()?)
+}
+
+/// Turns error responses into errors. Servers explain why a request failed
+/// with an [`ApiError`], while older ones only have the status code.
+fn check_status(response: &mut Response<Body>) -> Result<()> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+
+    match response.body_mut().read_json::<ApiError>() {
+        Ok(err) => Err(err.into()),
+        Err(_) => Err(ureq::Error::StatusCode(status.as_u16()).into()),
+    }
+}
+
+/// Loads the token of the account from the last time the game was played.
+/// Its name is filled in once the account is loaded from the server.
+fn load_account(path: &Path) -> Option<AccountResponse> {
+    let token = fs::read_to_string(path).ok()?;
+    Some(AccountResponse {
+        token: token.trim().to_owned(),
+        name: None,
+    })
+}
+
+/// Loads this install's secret key, generating and saving a new one if there
+/// isn't one yet.
+fn load_secret(path: &Path) -> [u8; 32] {
+    if let Some(secret) = fs::read(path).ok().and_then(|x| x.try_into().ok()) {
+        return secret;
+    }
+
+    let secret = rand::random::<[u8; 32]>();
+    if let Err(err) = fs::write(path, secret) {
+        warn!("Failed to save signing key: {err}");
+    }
+    secret
+}
+
+/// Parses the base URL of a server. Paths are joined onto it, so it needs to
+/// end with a slash to keep its last segment.
+fn parse_server(url: &str) -> Result<Url, url::ParseError> {
+    let mut url = Url::parse(url)?;
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+    Ok(url)
+}
+
+/// Checks if an upload failed because the server refused the solution, rather
+/// than because it couldn't be reached or was rate limited. Only an
+/// [`ApiError`] from the server counts, as a bare status code could just as
+/// well come from a proxy in front of it.
+fn is_rejection(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<ApiError>()
+        .is_some_and(|err| !err.is_temporary())
+}
@@ -0,0 +1,168 @@
+//! Solutions waiting to be uploaded to the leaderboard. The queue is saved to
+//! disk so solves made while offline, or while the server is down, are
+//! uploaded on a later launch.
+
+use std::{
+    fs::{self, File},
+    io::ErrorKind,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use bincode::Options;
+use log::error;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::consts::BINCODE_OPTIONS;
+
+const MIN_BACKOFF: Duration = Duration::from_secs(5);
+const MAX_BACKOFF: Duration = Duration::from_secs(10 * 60);
+
+pub struct UploadQueue {
+    path: PathBuf,
+    /// The server currently in use. Uploads made for other servers are kept
+    /// until the player switches back to them.
+    server: String,
+    uploads: Vec<QueuedUpload>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct QueuedUpload {
+    id: Uuid,
+    level: Uuid,
+    /// Base URL of the server the solution was solved for.
+    server: String,
+    /// The bincode encoded `PutResults` request body.
+    body: Vec<u8>,
+    /// Number of failed attempts so far.
+    attempts: u32,
+
+    // Everything left in the queue is retried right away on launch
+    #[serde(skip, default = "Instant::now")]
+    next_attempt: Instant,
+    #[serde(skip)]
+    in_flight: bool,
+}
+
+#[derive(Clone, Copy)]
+pub enum UploadStatus {
+    Uploading,
+    Waiting { attempts: u32, retry_in: Duration },
+}
+
+impl UploadQueue {
+    /// Loads the queue saved at the path. A queue that can't be read is moved
+    /// to a backup file rather than being overwritten by the next save, so
+    /// the uploads in it aren't lost.
+    pub fn load(path: PathBuf, server: String) -> Self {
+        let uploads = match File::open(&path) {
+            Ok(file) => BINCODE_OPTIONS
+                .deserialize_from(file)
+                .unwrap_or_else(|err| {
+                    let backup = path.with_extension("bin.bak");
+                    error!("Failed to load upload queue, moving it to {backup:?}: {err}");
+                    if let Err(err) = fs::rename(&path, &backup) {
+                        error!("Failed to back up upload queue: {err}");
+                    }
+                    Vec::new()
+                }),
+            Err(err) if err.kind() == ErrorKind::NotFound => Vec::new(),
+            Err(err) => {
+                error!("Failed to open upload queue: {err}");
+                Vec::new()
+            }
+        };
+
+        Self {
+            path,
+            server,
+            uploads,
+        }
+    }
+
+    /// Adds an upload to the queue, returning its id.
+    pub fn push(&mut self, level: Uuid, body: Vec<u8>) -> Uuid {
+        let id = Uuid::new_v4();
+        self.uploads.push(QueuedUpload {
+            id,
+            level,
+            server: self.server.clone(),
+            body,
+            attempts: 0,
+            next_attempt: Instant::now(),
+            in_flight: false,
+        });
+        self.save();
+        id
+    }
+
+    /// Marks every upload that is due to be attempted as in flight and
+    /// returns their id, level, and body.
+    pub fn start_due(&mut self) -> Vec<(Uuid, Uuid, Vec<u8>)> {
+        let now = Instant::now();
+        (self.uploads.iter_mut())
+            .filter(|x| x.server == self.server && !x.in_flight && x.next_attempt <= now)
+            .map(|x| {
+                x.in_flight = true;
+                (x.id, x.level, x.body.clone())
+            })
+            .collect()
+    }
+
+    /// Removes an upload from the queue, after it either succeeded or was
+    /// rejected by the server.
+    pub fn finish(&mut self, id: Uuid) {
+        self.uploads.retain(|x| x.id != id);
+        self.save();
+    }
+
+    /// Schedules another attempt for a failed upload, waiting twice as long
+    /// after each failure.
+    pub fn retry(&mut self, id: Uuid) {
+        let Some(upload) = self.uploads.iter_mut().find(|x| x.id == id) else {
+            return;
+        };
+
+        upload.next_attempt = Instant::now() + backoff(upload.attempts);
+        upload.attempts += 1;
+        upload.in_flight = false;
+        self.save();
+    }
+
+    /// The status of the most recent upload for a level to the current server
+    /// that hasn't finished.
+    pub fn status(&self, level: Uuid) -> Option<UploadStatus> {
+        let upload =
+            (self.uploads.iter().rev()).find(|x| x.server == self.server && x.level == level)?;
+        Some(if upload.in_flight {
+            UploadStatus::Uploading
+        } else {
+            UploadStatus::Waiting {
+                attempts: upload.attempts,
+                retry_in: upload
+                    .next_attempt
+                    .saturating_duration_since(Instant::now()),
+            }
+        })
+    }
+
+    fn save(&self) {
+        let file = File::create(&self.path).context("Failed to open upload queue");
+        if let Err(err) = file.and_then(|file| {
+            BINCODE_OPTIONS
+                .serialize_into(file, &self.uploads)
+                .context("Failed to serialize upload queue")
+        }) {
+            error!("Failed to write upload queue: {err}");
+        }
+    }
+}
+
+/// How long to wait before trying again after `attempts` failures, doubling
+/// after each one.
+pub fn backoff(attempts: u32) -> Duration {
+    let backoff = MIN_BACKOFF.saturating_mul(1 << attempts.min(16));
+    backoff.min(MAX_BACKOFF)
+}
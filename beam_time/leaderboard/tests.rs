@@ -0,0 +1,302 @@
+//! Tests of the upload queue against a stand-in for the leaderboard server.
+
+use std::{
+    env, fs,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use beam_logic::simulation::level_state::LevelResult;
+use common::{map::Map, user::UserId};
+use leaderboard::api::{
+    errors::{ApiError, ErrorCode},
+    info::{API_VERSION, GetInfoResponse},
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use super::{LeaderboardManager, UploadStatus, queue::UploadQueue};
+use crate::{app::LeaderboardServer, consts::paths};
+
+const USER: UserId = UserId::Hardware(1);
+
+/// Registers keys and answers uploads with the given status codes and bodies
+/// in order, repeating the last one. Every other request other than the server
+/// info gets a 404.
+struct StandIn {
+    url: String,
+    counts: Arc<Counts>,
+}
+
+/// Requests the stand-in has received.
+#[derive(Default)]
+struct Counts {
+    infos: AtomicUsize,
+    keys: AtomicUsize,
+    uploads: AtomicUsize,
+}
+
+impl StandIn {
+    fn start(uploads: Vec<(u16, String)>) -> Self {
+        Self::with_keys(vec![(200, "Key registered".into())], uploads)
+    }
+
+    fn with_keys(keys: Vec<(u16, String)>, uploads: Vec<(u16, String)>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/api/", listener.local_addr().unwrap());
+        let counts = Arc::new(Counts::default());
+
+        let scripted = |responses: &[(u16, String)], count: &AtomicUsize| {
+            let i = count.fetch_add(1, Ordering::SeqCst);
+            responses[i.min(responses.len() - 1)].clone()
+        };
+
+        let server_counts = counts.clone();
+        thread::spawn(move || {
+            let counts = server_counts;
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let (method, path) = read_request(&stream);
+
+                let (status, body) = if path.ends_with("/info") {
+                    counts.infos.fetch_add(1, Ordering::SeqCst);
+                    let info = GetInfoResponse {
+                        version: "test".into(),
+                        api_version: API_VERSION,
+                    };
+                    (200, json!(info).to_string())
+                } else if method == "POST" && path.ends_with("/keys") {
+                    scripted(&keys, &counts.keys)
+                } else if method == "PUT" && path.ends_with("/results") {
+                    scripted(&uploads, &counts.uploads)
+                } else {
+                    (404, "Not Found".into())
+                };
+
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status} Stand-in\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            }
+        });
+
+        Self { url, counts }
+    }
+
+    fn infos(&self) -> usize {
+        self.counts.infos.load(Ordering::SeqCst)
+    }
+
+    fn keys(&self) -> usize {
+        self.counts.keys.load(Ordering::SeqCst)
+    }
+
+    fn uploads(&self) -> usize {
+        self.counts.uploads.load(Ordering::SeqCst)
+    }
+}
+
+fn api_error(code: ErrorCode, message: &str) -> String {
+    json!(ApiError::new(code, message)).to_string()
+}
+
+/// Reads a request, returning its method and path.
+fn read_request(stream: &TcpStream) -> (String, String) {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    let mut parts = line.split_whitespace().map(str::to_owned);
+    let (method, path) = (parts.next().unwrap(), parts.next().unwrap());
+
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).unwrap();
+        if header.trim().is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            length = value.trim().parse().unwrap();
+        }
+    }
+
+    reader.read_exact(&mut vec![0; length]).unwrap();
+    (method, path)
+}
+
+fn data_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("beam-time-{name}-{}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Creates a manager using the stand-in and queues a solution to upload.
+fn publish(dir: &Path, server: &StandIn, level: Uuid) -> LeaderboardManager {
+    let custom = LeaderboardServer {
+        url: server.url.clone(),
+        hmac_key: None,
+    };
+    let mut manager = LeaderboardManager::new(dir, Some(&custom), None);
+    manager.publish_solution(&USER, level, &Map::default());
+    manager
+}
+
+fn tick_until(manager: &mut LeaderboardManager, done: impl Fn(&LeaderboardManager) -> bool) {
+    for _ in 0..500 {
+        manager.tick(&USER);
+        if done(manager) {
+            return;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    panic!("Timed out waiting on the upload queue");
+}
+
+fn saved_queue(dir: &Path, server: &StandIn) -> UploadQueue {
+    UploadQueue::load(dir.join(paths::UPLOAD_QUEUE), server.url.clone())
+}
+
+#[test]
+fn accepted_upload_leaves_queue() {
+    let result = LevelResult::Success { latency: 5 };
+    let server = StandIn::start(vec![(200, json!(result).to_string())]);
+    let (dir, level) = (data_dir("accepted_upload"), Uuid::new_v4());
+
+    let mut manager = publish(&dir, &server, level);
+    tick_until(&mut manager, |x| x.upload_status(level).is_none());
+
+    assert_eq!(server.uploads(), 1);
+    assert!(manager.upload_error(level).is_none());
+    assert!(saved_queue(&dir, &server).status(level).is_none());
+}
+
+#[test]
+fn failed_upload_is_kept_for_retry() {
+    let server = StandIn::start(vec![(503, "Service Unavailable".into())]);
+    let (dir, level) = (data_dir("failed_upload"), Uuid::new_v4());
+
+    let mut manager = publish(&dir, &server, level);
+    tick_until(&mut manager, |x| {
+        matches!(
+            x.upload_status(level),
+            Some(UploadStatus::Waiting { attempts: 1, .. })
+        )
+    });
+
+    assert_eq!(server.uploads(), 1);
+    assert!(manager.upload_error(level).is_none());
+    assert!(saved_queue(&dir, &server).status(level).is_some());
+}
+
+#[test]
+fn server_info_refetched_after_failure() {
+    let server = StandIn::start(vec![(503, "Service Unavailable".into())]);
+    let (dir, level) = (data_dir("info_refetch"), Uuid::new_v4());
+
+    let mut manager = publish(&dir, &server, level);
+    tick_until(&mut manager, |x| {
+        server.infos() == 2 && x.info.cache.contains_key(&())
+    });
+    assert_eq!(server.uploads(), 1);
+}
+
+#[test]
+fn rejected_upload_is_shown() {
+    let error = api_error(
+        ErrorCode::OutOfBounds,
+        "Tiles placed outside the level area",
+    );
+    let server = StandIn::start(vec![(400, error)]);
+    let (dir, level) = (data_dir("rejected_upload"), Uuid::new_v4());
+
+    let mut manager = publish(&dir, &server, level);
+    tick_until(&mut manager, |x| x.upload_status(level).is_none());
+
+    assert_eq!(server.uploads(), 1);
+    let error = manager.upload_error(level).map(|x| x.code);
+    assert_eq!(error, Some(ErrorCode::OutOfBounds));
+}
+
+#[test]
+fn key_conflict_holds_uploads() {
+    let conflict = api_error(ErrorCode::KeyConflict, "A different key is registered");
+    let server = StandIn::with_keys(vec![(409, conflict)], vec![]);
+    let (dir, level) = (data_dir("key_conflict"), Uuid::new_v4());
+
+    let mut manager = publish(&dir, &server, level);
+    tick_until(&mut manager, |x| x.registration_error().is_some());
+
+    let error = manager.registration_error().map(|x| x.code);
+    assert_eq!(error, Some(ErrorCode::KeyConflict));
+    assert_eq!(server.uploads(), 0);
+    assert!(manager.upload_status(level).is_some());
+}
+
+#[test]
+fn key_conflict_waits_for_auth_ticket() {
+    let conflict = api_error(ErrorCode::KeyConflict, "A different key is registered");
+    let registered = (200, "Key registered".into());
+    let result = json!(LevelResult::Success { latency: 5 }).to_string();
+    let server = StandIn::with_keys(vec![(409, conflict), registered], vec![(200, result)]);
+    let (dir, level) = (data_dir("key_conflict_ticket"), Uuid::new_v4());
+
+    // The refused key isn't registered again on its own, even once the
+    // backoff has passed
+    let mut manager = publish(&dir, &server, level);
+    tick_until(&mut manager, |x| x.registration_error().is_some());
+    manager.registration_retry = Instant::now();
+    for _ in 0..20 {
+        manager.tick(&USER);
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(server.keys(), 1);
+
+    // A ticket can verify the key, so it's tried again
+    manager.set_auth_ticket(&[1, 2, 3]);
+    tick_until(&mut manager, |x| x.upload_status(level).is_none());
+    assert_eq!((server.keys(), server.uploads()), (2, 1));
+    assert!(manager.registration_error().is_none());
+}
+
+#[test]
+fn invalid_signature_registers_key_again() {
+    let invalid = api_error(ErrorCode::InvalidSignature, "Invalid signature");
+    let server = StandIn::start(vec![(401, invalid)]);
+    let (dir, level) = (data_dir("invalid_signature"), Uuid::new_v4());
+
+    let mut manager = publish(&dir, &server, level);
+    tick_until(&mut manager, |_| server.keys() == 2);
+
+    assert_eq!(server.uploads(), 1);
+    assert!(manager.upload_error(level).is_none());
+    assert!(manager.upload_status(level).is_some());
+}
+
+#[test]
+fn unreadable_queue_is_backed_up() {
+    let dir = data_dir("unreadable_queue");
+    let path = dir.join(paths::UPLOAD_QUEUE);
+    fs::write(&path, [0xFF; 16]).unwrap();
+
+    let queue = UploadQueue::load(path.clone(), "http://localhost/api/".into());
+    assert!(queue.status(Uuid::nil()).is_none());
+    assert!(!path.exists());
+    assert_eq!(
+        fs::read(path.with_extension("bin.bak")).unwrap(),
+        [0xFF; 16]
+    );
+}
@@ -14,6 +14,7 @@ use log::{LevelFilter, warn};
 
 mod app;
 mod assets;
+mod cli;
 mod consts;
 mod game;
 mod integrations;
@@ -35,6 +36,10 @@ fn main() -> Result<()> {
         .init();
 
     enable_console();
+    if let Some(result) = cli::run() {
+        return result;
+    }
+
     API_TESTING.then(|| warn!("Using test API key!"));
 
     Application::new(ApplicationArgs {
@@ -56,6 +61,7 @@ fn main() -> Result<()> {
                 app.on_tick(ctx);
 
                 screens.render(ctx, &mut app);
+                app.toasts.render(ctx);
                 screens.pop_n(mem::take(&mut app.close_screens), &mut app);
                 screens.extend(mem::take(&mut app.new_screens), &mut app);
 
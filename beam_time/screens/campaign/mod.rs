@@ -1,4 +1,4 @@
-use std::f32::consts::TAU;
+use std::{f32::consts::TAU, path::Path};
 
 use ahash::{HashMap, HashMapExt};
 use beam_logic::level::{Level, default::DEFAULT_LEVELS, tree::LevelTree};
@@ -158,7 +158,9 @@ impl Screen for CampaignScreen {
                             // We can remove it bc we're going to be going to a new
                             // screen and dropping this anyway
                             let worlds = self.worlds.remove(&item.id);
-                            self.open_level(state, worlds, self.tree.get(item.id).unwrap());
+                            let dir = state.data_dir.join(paths::CAMPAIGN);
+                            let level = self.tree.get(item.id).unwrap();
+                            Self::open_level(state, worlds, level, &dir);
                         }
                     }
                 }
@@ -223,11 +225,13 @@ impl Screen for CampaignScreen {
 }
 
 impl CampaignScreen {
+    /// Opens the most recently played solution to a level, or creates a new
+    /// one in `dir` if there are none.
     pub fn open_level(
-        &self,
         state: &mut App,
         solutions: Option<Vec<UnloadedBoard>>,
         level: &Level,
+        dir: &Path,
     ) {
         let latest = solutions
             .as_ref()
@@ -252,10 +256,7 @@ impl CampaignScreen {
             };
 
             let id = Uuid::new_v4();
-            let path = state
-                .data_dir
-                .join(paths::CAMPAIGN)
-                .join(format!("{}_{id}.bin", slugify(&level.name)));
+            let path = dir.join(format!("{}_{id}.bin", slugify(&level.name)));
             state.push_screen(GameScreen::new(board, path));
         }
     }
@@ -6,30 +6,39 @@ use std::{
     time::Duration,
 };
 
+use anyhow::Result;
+use chrono::Utc;
 use log::{info, warn};
+use poll_promise::Promise;
 use rand::{Rng, seq::SliceRandom};
+use slug::slugify;
 
 use crate::{
     App,
-    consts::{color, keybind},
+    consts::{ANIMATION_EXPORT_TICKS, color, keybind, paths},
     game::{
         achievements::award_campaign_achievements,
-        board::{Board, LevelStats, unloaded::UnloadedBoard},
+        board::{Board, BoardMeta, LevelMeta, LevelStats, unloaded::UnloadedBoard},
+        holding::Holding,
+        levels,
         pancam::Pancam,
-        render::beam::BeamStateRender,
+        render::{beam::BeamStateRender, export},
     },
     integrations::RichPresence,
     ui::{confetti::Confetti, level_panel::LevelPanel, tile_picker::TilePicker},
     util::key_events,
 };
 use beam_logic::{
-    level::default::DEFAULT_LEVELS,
+    level::Level,
     misc::price,
     simulation::{
         level_state::LevelResult, runtime::asynchronous::AsyncSimulationState, state::BeamState,
     },
+    tile::Tile,
 };
+use common::{direction::Direction, map::Map};
 use engine::{exports::nalgebra::Vector2, graphics_context::GraphicsContext};
+use leaderboard::api::results::Ranking;
 
 use super::Screen;
 
@@ -50,6 +59,7 @@ pub struct GameScreen {
 
     save_file: PathBuf,
     solutions: Vec<UnloadedBoard>,
+    exports: Vec<Promise<Result<PathBuf>>>,
 
     needs_init: bool,
     tps: f32,
@@ -99,6 +109,19 @@ impl Screen for GameScreen {
             keybind::SPEED_DOWN => self.tps -= 5.0
         });
 
+        // Pick up the chip copied from another sandbox, adding it to this
+        // board's chips if it's not already used
+        if ctx.input.key_pressed(keybind::CHIP)
+            && self.board.transient.level.is_none()
+            && let Some(chip) = &state.chip_clipboard
+        {
+            let index = self.board.insert_chip(chip);
+            self.board.transient.holding = Holding::Tile(Tile::Chip {
+                rotation: Direction::Up,
+                index,
+            });
+        }
+
         self.tps = self.tps.max(0.0);
 
         let mut sim = self.beam.get();
@@ -143,14 +166,23 @@ impl Screen for GameScreen {
                     let level = self.board.transient.level.as_ref().unwrap();
                     let (cost, _count) = price(&self.board.tiles, level);
 
-                    // Mark solved and award potential steam achievements
-                    state.mark_level_complete(level.id);
-                    award_campaign_achievements(state, level_meta.id, (cost, latency));
-
-                    // Upload solution to leaderboard server
-                    state
-                        .leaderboard
-                        .publish_solution(&state.id, level.id, &self.board.tiles);
+                    // Mark solved, award potential steam achievements, and
+                    // upload the solution to the leaderboard server. Other
+                    // people's solutions shouldn't count as your own though.
+                    if !self.board.transient.read_only {
+                        state.mark_level_complete(level.id);
+                        award_campaign_achievements(state, level_meta.id, (cost, latency));
+                        let valid = level.validate(&self.board.tiles);
+                        match valid {
+                            Ok(()) => (state.leaderboard).publish_solution(
+                                &state.id,
+                                level.id,
+                                &self.board.tiles,
+                            ),
+                            Err(err) => warn!("Not uploading invalid solution: {err}"),
+                        }
+                        self.level_panel.invalid_solution = valid.err();
+                    }
 
                     create_confetti(&mut self.confetti, ctx);
                     level_meta.solved = Some(LevelStats { cost, latency });
@@ -165,22 +197,87 @@ impl Screen for GameScreen {
         {
             stop_simulation = false;
 
-            sim.beam = Some(BeamState::new(
-                &self.board.tiles,
-                self.board.transient.level.map(Cow::Borrowed),
-                test_pressed.then(|| {
-                    let tests = &self.board.transient.level.unwrap().tests;
-                    tests.true_index(self.level_panel.case) * tests.variable_start as usize
-                }),
-            ));
+            sim.beam = Some(
+                BeamState::new(
+                    &self.board.tiles,
+                    self.board.transient.level.map(Cow::Borrowed),
+                    test_pressed.then(|| {
+                        let tests = &self.board.transient.level.unwrap().tests;
+                        tests.true_index(self.level_panel.case) * tests.variable_start as usize
+                    }),
+                )
+                .with_chips(&self.board.chips),
+            );
             self.level_result = None;
         }
 
         stop_simulation.then(|| sim.beam = None);
 
+        // Export a screenshot of the board, or an animation if shift is held
+        if ctx.input.key_pressed(keybind::EXPORT) {
+            let extension = ["png", "gif"][shift as usize];
+            let name = slugify(&self.board.meta.name);
+            let name = format!("{name}_{}.{extension}", Utc::now().timestamp());
+            let path = state.data_dir.join(paths::EXPORTS).join(name);
+
+            // Exports run in the background so large boards don't freeze the
+            // game
+            let mut board = self.board.clone();
+            board.transient.level = self.board.transient.level;
+            let export = if shift {
+                Promise::spawn_thread("animation_export", move || {
+                    export::save_animation(&board, ANIMATION_EXPORT_TICKS, 100, 4, &path)
+                        .map(|()| path)
+                })
+            } else {
+                let (beams, frame) = (sim.beam.as_ref().map(|x| x.board.clone()), state.frame());
+                Promise::spawn_thread("board_export", move || {
+                    export::save_png(&board, beams.as_ref(), frame, 4, &path).map(|()| path)
+                })
+            };
+            self.exports.push(export);
+        }
+
+        self.exports.retain(|export| {
+            let Some(result) = export.ready() else {
+                return true;
+            };
+
+            match result {
+                Ok(path) => {
+                    info!("Exported board to {path:?}");
+                    state
+                        .toasts
+                        .info(format!("Exported board to {}", path.display()));
+                }
+                Err(err) => {
+                    warn!("Failed to export board: {err}");
+                    state.toasts.error(format!("Couldn't export board. {err}."));
+                }
+            }
+            false
+        });
+
+        // Open the top solution once it's downloaded
+        let level = self.board.transient.level;
+        if let Some(level) = level
+            && mem::take(&mut self.level_panel.view_solution)
+            && !self.board.transient.read_only
+        {
+            (state.leaderboard).download_solution(&state.id, level.id, Ranking::Score, 0);
+        }
+
+        if let Some((id, tiles)) = state.leaderboard.take_download()
+            && let Some(level) = levels::find(id)
+        {
+            state.push_screen(GameScreen::read_only(level, tiles));
+        }
+
         ctx.background(color::BACKGROUND);
-        self.tile_picker
-            .render(ctx, state, sim.beam.is_some(), &mut self.board);
+        if !self.board.transient.read_only {
+            self.tile_picker
+                .render(ctx, state, sim.beam.is_some(), &mut self.board);
+        }
         self.level_panel
             .render(ctx, state, &self.board, &sim, &self.level_result);
         self.confetti.render(ctx);
@@ -190,6 +287,7 @@ impl Screen for GameScreen {
         self.board.tick_input(ctx, &self.pancam, &mut sim.beam);
 
         if self.board.transient.history.is_dirty() {
+            self.board.transient.diff = None;
             self.level_result = None;
             if let Some(level) = &mut self.board.meta.level {
                 level.solved = None;
@@ -207,7 +305,12 @@ impl Screen for GameScreen {
         }
 
         if let Some(level) = self.board.transient.level {
-            state.leaderboard.fetch_results(level.id);
+            let leaderboard = &mut state.leaderboard;
+            leaderboard.fetch_results(level.id);
+            leaderboard.fetch_group_results(level.id);
+            leaderboard.fetch_personal(&state.id, level.id);
+            leaderboard.fetch_ranking(level.id, Ranking::Score);
+            leaderboard.fetch_frontier(level.id);
         }
     }
 
@@ -215,6 +318,10 @@ impl Screen for GameScreen {
         state.integrations.rich_presence(RichPresence::None);
 
         let board = mem::take(&mut self.board);
+        if board.transient.read_only {
+            return;
+        }
+
         let trash = board.transient.trash;
         board.save(&self.save_file).unwrap();
 
@@ -228,10 +335,18 @@ impl Screen for GameScreen {
 }
 
 impl GameScreen {
+    /// Opens a board for editing. Boards for a level that isn't loaded, like
+    /// a challenge that hasn't been fetched, are opened as a read-only sandbox
+    /// so their save keeps the level.
     pub fn new(mut board: Board, save_file: PathBuf) -> Self {
-        let level_meta = board.meta.level.as_ref();
-        board.transient.level =
-            level_meta.map(|x| DEFAULT_LEVELS.iter().find(|y| y.id == x.id).unwrap());
+        if let Some(meta) = &board.meta.level {
+            board.transient.level = levels::find(meta.id);
+            if board.transient.level.is_none() {
+                warn!("Level {} not found, opening as a sandbox", meta.id);
+                board.meta.level = None;
+                board.transient.read_only = true;
+            }
+        }
 
         if let Some(level) = board.transient.level {
             let mut seen_ids = HashSet::new();
@@ -263,12 +378,34 @@ impl GameScreen {
 
             save_file,
             solutions: Vec::new(),
+            exports: Vec::new(),
 
             needs_init: true,
             tps: 20.0,
         }
     }
 
+    /// Opens someone else's solution to a level for viewing. It can be
+    /// simulated and copied from, but not changed or saved.
+    pub fn read_only(level: &Level, tiles: Map<Tile>) -> Self {
+        let mut board = Board {
+            meta: BoardMeta {
+                name: format!("{} (Top Solution)", level.name),
+                level: Some(LevelMeta {
+                    id: level.id,
+                    solved: None,
+                }),
+                size: level.size,
+                ..Default::default()
+            },
+            tiles,
+            ..Default::default()
+        };
+        board.transient.read_only = true;
+
+        GameScreen::new(board, PathBuf::new())
+    }
+
     pub fn load(save_file: impl AsRef<Path>) -> Self {
         let save_file = save_file.as_ref().to_path_buf();
         GameScreen::new(Board::load(&save_file).unwrap_or_default(), save_file)
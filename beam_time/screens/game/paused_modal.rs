@@ -1,31 +1,36 @@
 use std::time::Duration;
 
+use beam_logic::chip::Chip;
+use log::warn;
+use slug::slugify;
+
 use engine::{
     color::Rgb,
-    drawable::{spacer::Spacer, sprite::Sprite, Anchor},
+    drawable::{Anchor, spacer::Spacer, sprite::Sprite},
     exports::{nalgebra::Vector2, winit::event::MouseButton},
     graphics_context::GraphicsContext,
     layout::{
-        column::ColumnLayout, row::RowLayout, tracker::LayoutTracker, Justify, Layout,
-        LayoutElement, LayoutMethods,
+        Justify, Layout, LayoutElement, LayoutMethods, column::ColumnLayout, row::RowLayout,
+        tracker::LayoutTracker,
     },
     memory_key,
 };
 
 use crate::{
     app::App,
-    assets::{EDIT, RESET, TRASH},
+    assets::{DUPLICATE, EDIT, RESET, TRASH},
     consts::{
-        color, layer,
+        color, layer, paths,
         spacing::{MARGIN, PADDING},
     },
+    game::board::text,
     screens::game::ActiveModal,
     ui::{
         components::{
             horizontal_rule::Rule,
             key::Key,
             manual_button::ManualButton,
-            modal::{modal_buttons, Modal},
+            modal::{Modal, modal_buttons},
             slider::Slider,
         },
         misc::{body, modal_size},
@@ -41,6 +46,8 @@ const KEYBINDS: &[(&str, &str)] = &[
     ("R", "Rotates the held or hovered tile"),
     ("Q", "Copy hovered tile"),
     ("E", "Toggle the held or hovered emitter"),
+    ("F2", "Export an image, hold shift for an animation"),
+    ("G", "Hide the solution diff"),
 ];
 
 impl GameScreen {
@@ -79,8 +86,8 @@ impl GameScreen {
                         body(&playtime).layout(ctx, layout);
                         Spacer::new_y(PADDING / 2.0).layout(ctx, layout);
 
-                        let mut icon_button = |icon, text| {
-                            let key = memory_key!(icon);
+                        let mut icon_button = |icon, text: &str| {
+                            let key = memory_key!(icon, text);
                             let tracker = LayoutTracker::new(key);
                             let button = ManualButton::new(key).tracker(ctx, tracker);
                             let color =
@@ -100,16 +107,58 @@ impl GameScreen {
                             button.pressed(ctx)
                         };
 
-                        if self.board.transient.level.is_some() {
+                        // There's nothing to manage when viewing someone else's
+                        // solution
+                        let transient = &self.board.transient;
+                        if transient.level.is_some() && !transient.read_only {
                             let solutions_text =
                                 format!("Solutions ({})", self.solutions.len() + 1);
                             icon_button(EDIT, &solutions_text)
                                 .then(|| self.modal = ActiveModal::Solutions);
                             icon_button(RESET, "Reset").then(|| self.modal = ActiveModal::Reset);
-                        } else {
+                        } else if transient.level.is_none() {
+                            icon_button(DUPLICATE, "Copy as Chip").then(|| {
+                                let board = &self.board;
+                                let name = board.meta.name.clone();
+                                match Chip::new(name, board.tiles.clone(), board.chips.clone()) {
+                                    Ok(chip) => {
+                                        state.toasts.info("Copied as a chip, press B to place it");
+                                        state.chip_clipboard = Some(chip);
+                                    }
+                                    Err(err) => {
+                                        warn!("Failed to create chip: {err}");
+                                        state.toasts.error(format!("Couldn't create chip. {err}."));
+                                    }
+                                }
+                            });
                             icon_button(TRASH, "Delete World").then(|| trash = true);
                         }
 
+                        icon_button(DUPLICATE, "Copy as Text").then(|| {
+                            let text = text::encode(&self.board);
+                            match state.system_clipboard.set_text(text) {
+                                Ok(()) => state.toasts.info("Copied the board as text"),
+                                Err(err) => {
+                                    warn!("Failed to copy board: {err}");
+                                    state.toasts.error(format!("Couldn't copy board. {err}."));
+                                }
+                            }
+                        });
+
+                        // Written as a `.txt` save, which can be checked into
+                        // version control and imported again
+                        icon_button(DUPLICATE, "Export as Text").then(|| {
+                            let name = format!("{}.txt", slugify(&self.board.meta.name));
+                            let path = state.data_dir.join(paths::EXPORTS).join(name);
+                            match self.board.clone().save_exact(&path) {
+                                Ok(()) => state.toasts.info(format!("Exported board to {path:?}")),
+                                Err(err) => {
+                                    warn!("Failed to export board: {err}");
+                                    state.toasts.error(format!("Couldn't export board. {err}."));
+                                }
+                            }
+                        });
+
                         Spacer::new_y(PADDING / 2.0).layout(ctx, layout);
                         body("Simulation Speed (TPS)").layout(ctx, layout);
                         layout.nest(
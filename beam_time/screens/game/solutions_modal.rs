@@ -1,7 +1,14 @@
-use std::{cmp::Reverse, iter, mem, path::PathBuf};
+use std::{
+    cmp::Reverse,
+    iter, mem,
+    path::{Path, PathBuf},
+};
 
-use anyhow::Result;
-use beam_logic::level::Level;
+use anyhow::{Context, Result};
+use beam_logic::{
+    diff::{Diff, merge},
+    level::Level,
+};
 use engine::{
     drawable::{Anchor, dummy::DummyDrawable, spacer::Spacer, sprite::Sprite, text::Text},
     exports::{nalgebra::Vector2, winit::event::MouseButton},
@@ -12,7 +19,7 @@ use engine::{
     },
     memory_key,
 };
-use log::error;
+use log::{error, warn};
 use slug::slugify;
 use thousands::Separable;
 use uuid::Uuid;
@@ -24,7 +31,7 @@ use crate::{
         layer, paths,
         spacing::{MARGIN, PADDING},
     },
-    game::board::{Board, BoardMeta, LevelMeta, LevelStats, unloaded::UnloadedBoard},
+    game::board::{Board, BoardMeta, LevelMeta, LevelStats, Origin, text, unloaded::UnloadedBoard},
     screens::game::{ActiveModal, GameScreen},
     ui::{
         board_operations::{
@@ -113,6 +120,30 @@ impl GameScreen {
                         })
                         .layout(ctx, layout);
 
+                    Text::new(UNDEAD_FONT, "+ Import from Clipboard +")
+                        .scale(Vector2::repeat(2.0))
+                        .button(memory_key!())
+                        .on_click(ctx, || {
+                            let dir = state.data_dir.join(paths::CAMPAIGN);
+                            let import = (state.system_clipboard.get_text())
+                                .context("Nothing to import in the clipboard")
+                                .and_then(|x| text::import(&x, &dir, Some(level.id)));
+                            match import {
+                                Ok(board) => {
+                                    let name = &board.meta.name;
+                                    state.toasts.info(format!("Imported {name}"));
+                                    self.solutions.push(board);
+                                }
+                                Err(err) => {
+                                    error!("Failed to import solution: {err:?}");
+                                    state
+                                        .toasts
+                                        .error(format!("Couldn't import solution. {err}."));
+                                }
+                            }
+                        })
+                        .layout(ctx, layout);
+
                     let (back, _) = modal_buttons(ctx, layout, size.x, ("Back", ""));
                     let click = ctx.input.mouse_pressed(MouseButton::Left);
                     if click && back {
@@ -222,6 +253,7 @@ impl GameScreen {
         };
 
         let mut new_board = None;
+        let mut compare = None;
         let mut load = None;
         ColumnLayout::new(PADDING).show(ctx, layout, |ctx, layout| {
             let level = meta.level.as_ref().unwrap();
@@ -278,6 +310,16 @@ impl GameScreen {
                             .on_click(ctx, || self.modal = ActiveModal::SolutionEdit { index })
                             .layout(ctx, layout);
 
+                        if index != 0 {
+                            for (text, overlay) in [("Overlay", true), ("Diff", false)] {
+                                Text::new(UNDEAD_FONT, text)
+                                    .scale(Vector2::repeat(2.0))
+                                    .button(memory_key!(path, text))
+                                    .on_click(ctx, || compare = Some((path.to_path_buf(), overlay)))
+                                    .layout(ctx, layout);
+                            }
+                        }
+
                         Spacer::new_x(layout.available().x).layout(ctx, layout);
                     });
                 });
@@ -291,14 +333,65 @@ impl GameScreen {
             self.solutions.push(board);
         }
 
+        if let Some((path, overlay)) = compare {
+            match Board::load(&path) {
+                Ok(other) if overlay => self.overlay_solution(&other, &path),
+                Ok(other) => {
+                    self.board.transient.diff = Some(Diff::new(&other.tiles, &self.board.tiles))
+                }
+                Err(err) => error!("Failed to load solution: {err}"),
+            }
+            self.modal = ActiveModal::None;
+        }
+
         load
     }
+
+    /// Places the tiles of another solution on top of the current board. The
+    /// changes the other solution made since the two diverged are applied,
+    /// and tiles changed differently by both solutions are conflicts that
+    /// keep the current version and are highlighted.
+    fn overlay_solution(&mut self, other: &Board, path: &Path) {
+        let base = (self.board)
+            .common_ancestor(&self.save_file, other, path)
+            .unwrap();
+        let (tiles, conflicts) = merge(base, &self.board.tiles, &other.tiles);
+        let mut diff = Diff::new(&self.board.tiles, &tiles);
+        diff.conflicts = conflicts.iter().copied().collect();
+
+        let old = (diff.changes.iter())
+            .map(|(&pos, change)| (pos, change.before()))
+            .collect();
+        self.board.transient.history.track_many(old);
+        self.board.tiles = tiles;
+        self.board.transient.diff = Some(diff);
+        self.beam.get().beam = None;
+
+        // History is marked clean before the board is rendered, so the
+        // solved state has to be cleared here
+        self.level_result = None;
+        if let Some(level) = &mut self.board.meta.level {
+            level.solved = None;
+        }
+
+        if !conflicts.is_empty() {
+            warn!(
+                "Overlaid `{}` with {} conflicting tiles",
+                other.meta.name,
+                conflicts.len()
+            );
+        }
+    }
 }
 
 fn duplicate_solution(solution: PathBuf, level: &Level) -> Result<UnloadedBoard> {
     let mut board = Board::load(&solution)?;
     board.meta.playtime = 0;
     board.meta.name += " copy";
+    board.origin = Some(Origin {
+        file: solution.file_name().unwrap().to_string_lossy().into_owned(),
+        tiles: board.tiles.clone(),
+    });
 
     let id = Uuid::new_v4();
     let parent = solution.parent().unwrap();
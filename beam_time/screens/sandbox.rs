@@ -26,7 +26,7 @@ use crate::{
     assets::{ALAGARD_FONT, DUPLICATE, EDIT, TRASH, UNDEAD_FONT},
     consts::{WATERFALL, color, keybind, paths, spacing::PADDING},
     game::board::{
-        Board,
+        Board, text,
         unloaded::{UnloadedBoard, load_level_dir},
     },
     screens::game::GameScreen,
@@ -177,6 +177,29 @@ impl Screen for SandboxScreen {
                     .button(memory_key!())
                     .on_click(ctx, || self.modal = ActiveModal::Create)
                     .layout(ctx, layout);
+
+                // Boards copied with "Copy as Text", like ones checked into
+                // version control
+                Text::new(UNDEAD_FONT, "+ Import from Clipboard +")
+                    .scale(Vector2::repeat(2.0))
+                    .dark_shadow()
+                    .button(memory_key!())
+                    .on_click(ctx, || {
+                        load_worlds = true;
+                        let import = (state.system_clipboard.get_text())
+                            .context("Nothing to import in the clipboard")
+                            .and_then(|x| text::import(&x, &self.world_dir, None));
+                        match import {
+                            Ok(board) => state.toasts.info(format!("Imported {}", board.meta.name)),
+                            Err(err) => {
+                                error!("Failed to import sandbox: {err:?}");
+                                state
+                                    .toasts
+                                    .error(format!("Couldn't import sandbox. {err}."));
+                            }
+                        }
+                    })
+                    .layout(ctx, layout);
             },
         );
 
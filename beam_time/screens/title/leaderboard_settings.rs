@@ -0,0 +1,197 @@
+use chrono::{DateTime, Utc};
+use engine::{
+    drawable::{Anchor, spacer::Spacer},
+    exports::{nalgebra::Vector2, winit::event::MouseButton},
+    graphics_context::GraphicsContext,
+    layout::{Layout, LayoutElement, LayoutMethods, column::ColumnLayout, row::RowLayout},
+    memory::MemoryKey,
+    memory_key,
+};
+use leaderboard::api::{
+    accounts::{MAX_NAME_LENGTH, validate_name},
+    groups::{MAX_CODE_LENGTH, normalize_code},
+};
+
+use crate::{
+    App,
+    consts::{
+        color, layer,
+        spacing::{MARGIN, PADDING},
+    },
+    screens::title::{ActiveModal, GROUP, TitleScreen},
+    ui::{
+        components::{
+            button::ButtonExt,
+            horizontal_rule::Rule,
+            modal::{Modal, modal_buttons},
+            text_input::TextInput,
+        },
+        misc::{body, modal_size},
+    },
+};
+
+const GROUP_INPUT: MemoryKey = memory_key!();
+const NAME_INPUT: MemoryKey = memory_key!();
+const LINK_INPUT: MemoryKey = memory_key!();
+
+impl TitleScreen {
+    pub(super) fn leaderboard_modal(&mut self, state: &mut App, ctx: &mut GraphicsContext) {
+        let modal = Modal::new(modal_size(ctx))
+            .position(ctx.center(), Anchor::Center)
+            .margin(MARGIN)
+            .layer(layer::OVERLAY);
+
+        let size = modal.inner_size();
+        modal.draw(ctx, |ctx, root| {
+            let body = body(size.x);
+            let column = (size.x - PADDING * 4.8) / 2.0;
+
+            root.nest(ctx, ColumnLayout::new(PADDING), |ctx, layout| {
+                body("Leaderboard")
+                    .scale(Vector2::repeat(4.0))
+                    .layout(ctx, layout);
+                Spacer::new_y(4.0).layout(ctx, layout);
+
+                layout.nest(ctx, RowLayout::new(PADDING * 2.4), |ctx, layout| {
+                    layout.nest(ctx, ColumnLayout::new(PADDING), |ctx, layout| {
+                        group_input(ctx, layout, state, column);
+                        Spacer::new_y(4.0).layout(ctx, layout);
+                        name_input(ctx, layout, state, column);
+                    });
+
+                    let height = layout.available().y - 6.0 - PADDING * 2.0;
+                    Rule::vertical(height).layout(ctx, layout);
+
+                    layout.nest(ctx, ColumnLayout::new(PADDING), |ctx, layout| {
+                        link_devices(ctx, layout, state, column);
+                    });
+                });
+
+                if let Some(error) = state.leaderboard.account_error() {
+                    body(error).color(color::ERROR).layout(ctx, layout);
+                }
+
+                let clicking = ctx.input.mouse_pressed(MouseButton::Left);
+                let (back, _) = modal_buttons(ctx, layout, size.x, ("Back", ""));
+                (back && clicking).then(|| self.modal = ActiveModal::Settings);
+            });
+        });
+    }
+}
+
+/// The group is joined once the modal is closed, and leaving the code empty
+/// leaves the current group.
+fn group_input(ctx: &mut GraphicsContext, layout: &mut ColumnLayout, state: &App, width: f32) {
+    let body = body(width);
+    body("Group").layout(ctx, layout);
+
+    let input = TextInput::new(GROUP_INPUT)
+        .placeholder("Code to compare results with")
+        .max_chars(MAX_CODE_LENGTH as u32)
+        .width(width);
+    if let Some(group) = &state.config.group
+        && !input.is_edited(ctx)
+    {
+        input.with_content(ctx, group.to_owned());
+    }
+
+    let content = input.content(ctx);
+    input.layout(ctx, layout);
+    match normalize_code(&content) {
+        Some(code) => ctx.memory.insert(GROUP, Some(code)),
+        None if content.trim().is_empty() => ctx.memory.insert(GROUP, None::<String>),
+        None => body("Codes can only have letters, numbers, dashes, and underscores.")
+            .color(color::ERROR)
+            .layout(ctx, layout),
+    }
+}
+
+fn name_input(ctx: &mut GraphicsContext, layout: &mut ColumnLayout, state: &mut App, width: f32) {
+    let body = body(width);
+    body("Display Name").layout(ctx, layout);
+    if !state.leaderboard.account_ready() {
+        body("Connecting to the leaderboard...").layout(ctx, layout);
+        return;
+    }
+
+    let input = TextInput::new(NAME_INPUT)
+        .placeholder("Anonymous")
+        .max_chars(MAX_NAME_LENGTH as u32)
+        .width(width - 60.0);
+    if let Some(name) = state.leaderboard.account_name()
+        && !input.is_edited(ctx)
+    {
+        input.with_content(ctx, name.to_owned());
+    }
+
+    let content = input.content(ctx);
+    let name = validate_name(&content);
+    let mut save = false;
+    layout.nest(ctx, RowLayout::new(PADDING), |ctx, layout| {
+        input.layout(ctx, layout);
+        body("Save")
+            .button(memory_key!())
+            .on_click(ctx, || save = true)
+            .layout(ctx, layout);
+    });
+
+    match name {
+        Ok(name) if save => state.leaderboard.set_name(Some(name.to_owned())),
+        Err(_) if save && content.trim().is_empty() => state.leaderboard.set_name(None),
+        Err(err) if !content.trim().is_empty() => body(err).color(color::ERROR).layout(ctx, layout),
+        _ => {}
+    }
+}
+
+/// Shows a code to enter on another device, or takes one from another device,
+/// to link them to the same account.
+fn link_devices(ctx: &mut GraphicsContext, layout: &mut ColumnLayout, state: &mut App, width: f32) {
+    let body = body(width);
+    body("Link Devices").layout(ctx, layout);
+    if !state.leaderboard.account_ready() {
+        return;
+    }
+
+    let code = state.leaderboard.link_code().filter(|x| x.expires > now());
+    let text = match code {
+        Some(code) => format!(
+            "Enter {} on your other device in the next {} minutes.",
+            code.code,
+            (code.expires - now()).div_ceil(60)
+        ),
+        None => "Get a code to enter on another device, so both share one account.".into(),
+    };
+    body(&text).layout(ctx, layout);
+
+    let mut request = false;
+    body("Get Code")
+        .button(memory_key!())
+        .on_click(ctx, || request = true)
+        .layout(ctx, layout);
+    request.then(|| state.leaderboard.request_link_code());
+
+    Spacer::new_y(4.0).layout(ctx, layout);
+    let input = TextInput::new(LINK_INPUT)
+        .placeholder("Code from another device")
+        .max_chars(16)
+        .width(width - 60.0);
+    let content = input.content(ctx);
+
+    let mut link = false;
+    layout.nest(ctx, RowLayout::new(PADDING), |ctx, layout| {
+        input.layout(ctx, layout);
+        body("Link")
+            .button(memory_key!())
+            .on_click(ctx, || link = true)
+            .layout(ctx, layout);
+    });
+
+    if link && !content.trim().is_empty() {
+        let code = content.trim().to_owned();
+        state.leaderboard.link_account(&state.id, code);
+    }
+}
+
+fn now() -> u64 {
+    DateTime::timestamp(&Utc::now()) as u64
+}
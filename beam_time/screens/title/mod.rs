@@ -1,5 +1,7 @@
 use std::mem;
 
+use beam_logic::level::Level;
+use chrono::Utc;
 use engine::{
     color::Rgb,
     drawable::{Anchor, Drawable, spacer::Spacer, text::Text},
@@ -15,14 +17,17 @@ use engine::{
     memory::MemoryKey,
     memory_key,
 };
+use thousands::Separable;
 
 use crate::{
     App,
     assets::{ALAGARD_FONT, UNDEAD_FONT},
     consts::{
-        AUTHOR_HOMEPAGE, WATERFALL, color, keybind, layer,
+        AUTHOR_HOMEPAGE, WATERFALL, color, keybind, layer, paths,
         spacing::{MARGIN, PADDING},
     },
+    game::board::unloaded::load_level_dir,
+    leaderboard::Challenge,
     ui::{
         components::{
             button::{ButtonEffects, ButtonExt},
@@ -39,6 +44,7 @@ use crate::{
 use super::{Screen, campaign::CampaignScreen, sandbox::SandboxScreen};
 
 mod about;
+mod leaderboard_settings;
 
 type ButtonCallback = fn(&mut App, &mut ActiveModal);
 const BUTTONS: [(&str, KeyCode, ButtonCallback); 4] = [
@@ -65,6 +71,7 @@ enum ActiveModal {
     #[default]
     None,
     Settings,
+    Leaderboard,
     About,
 }
 
@@ -130,6 +137,9 @@ impl Screen for TitleScreen {
             Anchor::Center,
         );
 
+        // Typing in a modal shouldn't press any buttons
+        let hotkeys = matches!(self.modal, ActiveModal::None);
+        let mut open_challenge = None;
         root.nest(
             ctx,
             ColumnLayout::new(PADDING).justify(Justify::Center),
@@ -143,18 +153,83 @@ impl Screen for TitleScreen {
                         .button(key)
                         .effects(ButtonEffects::Color | ButtonEffects::Arrows);
 
-                    (button.is_clicked(ctx) || ctx.input.key_pressed(keycode))
+                    (button.is_clicked(ctx) || (hotkeys && ctx.input.key_pressed(keycode)))
                         .then(|| on_click(state, &mut self.modal));
                     button.layout(ctx, layout);
                     Spacer::new_y(5.0 - PADDING).layout(ctx, layout);
                 }
+
+                let challenge = (state.leaderboard.challenge()).filter(|x| !x.has_ended());
+                if let Some(challenge) = challenge {
+                    Spacer::new_y(20.0).layout(ctx, layout);
+                    let button =
+                        Text::new(UNDEAD_FONT, format!("Challenge: {}", challenge.level.name))
+                            .scale(Vector2::repeat(3.0))
+                            .dark_shadow()
+                            .button(memory_key!())
+                            .effects(ButtonEffects::Color | ButtonEffects::Arrows);
+                    (button.is_clicked(ctx)
+                        || (hotkeys && ctx.input.key_pressed(keybind::CHALLENGE)))
+                    .then(|| open_challenge = Some(challenge.level));
+                    button.layout(ctx, layout);
+
+                    Text::new(UNDEAD_FONT, challenge_details(challenge))
+                        .scale(Vector2::repeat(2.0))
+                        .color(Rgb::repeat(0.8))
+                        .dark_shadow()
+                        .layout(ctx, layout);
+                }
             },
         );
 
         root.draw(ctx);
+
+        if let Some(level) = open_challenge {
+            self::open_challenge(state, level);
+        }
+    }
+
+    fn on_init(&mut self, state: &mut App) {
+        state.leaderboard.fetch_challenge();
     }
 }
 
+/// Opens the player's latest solution to a challenge, or a new one.
+fn open_challenge(state: &mut App, level: &'static Level) {
+    let dir = state.data_dir.join(paths::CHALLENGES);
+    let mut solutions = if dir.exists() {
+        load_level_dir(&dir)
+    } else {
+        Vec::new()
+    };
+    solutions.retain(|x| x.meta.level.as_ref().is_some_and(|y| y.id == level.id));
+
+    let solutions = (!solutions.is_empty()).then_some(solutions);
+    CampaignScreen::open_level(state, solutions, level, &dir);
+}
+
+/// Time left in the challenge along with the top few solutions.
+fn challenge_details(challenge: &Challenge) -> String {
+    let left = (challenge.end - Utc::now()).num_minutes().max(0);
+    let (days, hours, minutes) = (left / (60 * 24), left / 60 % 24, left % 60);
+    let mut out = match days {
+        0 => format!("Ends in {hours}h {minutes}m"),
+        _ => format!("Ends in {days}d {hours}h"),
+    };
+    out += &format!(" - {} solutions", challenge.total);
+
+    for result in challenge.scoreboard.iter().take(3) {
+        out += &format!(
+            "\n#{} ${} in {} ticks",
+            result.rank,
+            result.cost.separate_with_commas(),
+            result.latency
+        );
+    }
+
+    out
+}
+
 #[cfg(feature = "steam")]
 fn review_prompt(ctx: &mut GraphicsContext) {
     let text = Text::new(
@@ -172,6 +247,7 @@ fn review_prompt(ctx: &mut GraphicsContext) {
 }
 
 const SCALE: MemoryKey = memory_key!();
+const GROUP: MemoryKey = memory_key!();
 
 impl TitleScreen {
     fn modals(&mut self, state: &mut App, ctx: &mut GraphicsContext) {
@@ -180,8 +256,16 @@ impl TitleScreen {
                 if let Some(&new_scale) = ctx.memory.get(SCALE) {
                     state.config.interface_scale = new_scale;
                 }
+
+                if let Some(group) = ctx.memory.get::<Option<String>>(GROUP)
+                    && *group != state.config.group
+                {
+                    state.config.group = group.clone();
+                    state.leaderboard.join_group(group.clone());
+                }
             }
             ActiveModal::Settings => self.settings_modal(state, ctx),
+            ActiveModal::Leaderboard => self.leaderboard_modal(state, ctx),
             ActiveModal::About => self.about_modal(state, ctx),
         }
     }
@@ -269,8 +353,10 @@ impl TitleScreen {
                 });
 
                 let clicking = ctx.input.mouse_pressed(MouseButton::Left);
-                let (back, _) = modal_buttons(ctx, layout, size.x, ("Back", ""));
+                let (back, leaderboard) =
+                    modal_buttons(ctx, layout, size.x, ("Back", "Leaderboard"));
                 (back && clicking).then(|| self.modal = ActiveModal::None);
+                (leaderboard && clicking).then(|| self.modal = ActiveModal::Leaderboard);
             });
         });
     }
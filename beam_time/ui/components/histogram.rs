@@ -3,18 +3,25 @@ use engine::{
     drawable::{Anchor, Drawable, shape::rectangle::Rectangle, sprite::Sprite, text::Text},
     exports::nalgebra::Vector2,
     graphics_context::GraphicsContext,
-    layout::{LayoutElement, bounds::Bounds2D},
+    layout::{Layout, LayoutElement, LayoutMethods, bounds::Bounds2D, row::RowLayout},
+    memory::MemoryKey,
 };
 use itertools::Itertools;
 use leaderboard::api::results;
 use thousands::Separable;
 
-use crate::assets::{DOWN_ARROW, UNDEAD_FONT};
+use crate::{
+    assets::{DOWN_ARROW, UNDEAD_FONT},
+    consts::{color, spacing::PADDING},
+    ui::components::button::{ButtonEffects, ButtonExt},
+};
 
 pub struct Histogram {
     position: Vector2<f32>,
     data: results::Histogram,
     real: Option<u32>,
+    /// Other values to mark under the histogram, highlighted if true.
+    marks: Vec<(u32, bool)>,
     title: Option<&'static str>,
 }
 
@@ -24,6 +31,7 @@ impl Histogram {
             position: Vector2::zeros(),
             data,
             real: None,
+            marks: Vec::new(),
             title: None,
         }
     }
@@ -33,26 +41,65 @@ impl Histogram {
         self
     }
 
+    pub fn marks(mut self, marks: Vec<(u32, bool)>) -> Self {
+        self.marks = marks;
+        self
+    }
+
     pub fn title(mut self, title: &'static str) -> Self {
         self.title = Some(title);
         self
     }
 }
 
+/// A row of tabs for switching between sets of histograms. Returns the index
+/// of the selected tab, which is remembered under `key`.
+pub fn histogram_tabs<L: Layout + 'static>(
+    ctx: &mut GraphicsContext,
+    layout: &mut L,
+    key: MemoryKey,
+    tabs: &[&str],
+) -> usize {
+    let mut selected = *ctx.memory.get_or_insert(key, 0_usize);
+
+    RowLayout::new(PADDING).show(ctx, layout, |ctx, layout| {
+        for (i, tab) in tabs.iter().enumerate() {
+            let color = [Rgb::hex(0x8d8d8d), color::ACCENT][(i == selected) as usize];
+            Text::new(UNDEAD_FONT, *tab)
+                .scale(Vector2::repeat(2.0))
+                .color(color)
+                .button(key.context(i))
+                .effects(ButtonEffects::Scale)
+                .on_click(ctx, || selected = i)
+                .layout(ctx, layout);
+        }
+    });
+
+    ctx.memory.insert(key, selected);
+    selected
+}
+
 impl Drawable for Histogram {
     // should prob rewrite this with the layout system at some point...
     fn draw(self, ctx: &mut GraphicsContext) {
         let px = 4.0;
-        let bar = Vector2::new(16.0, 3.0);
-        let (width, height) = (bar.x * 12.0, 15.0 * px);
+        let (width, height) = (192.0, 15.0 * px);
+        let bar = Vector2::new(width / self.data.bins.len().max(1) as f32, 3.0);
         let position = self.position + Vector2::y() * 18.0;
 
-        let max_value = *self.data.bins.iter().max().unwrap() as f32;
+        let max_value = self
+            .data
+            .bins
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or_default()
+            .max(1) as f32;
 
-        let bars = self.data.bins.into_iter().enumerate();
+        let bars = self.data.bins.iter().enumerate();
         let bars = bars
             .map(|(i, value)| {
-                let height = value as f32 / max_value * height;
+                let height = *value as f32 / max_value * height;
                 Vector2::new(bar.x * i as f32, height)
             })
             .collect::<Vec<_>>();
@@ -75,8 +122,17 @@ impl Drawable for Histogram {
                 .draw(ctx);
         }
 
+        for (value, highlight) in self.marks {
+            let t = self.data.fraction(value);
+            let color = [Rgb::hex(0xFFFFFF), color::ACCENT][highlight as usize];
+            Rectangle::new(Vector2::new(3.0, px * 2.0))
+                .position(position + Vector2::x() * t * width, Anchor::TopCenter)
+                .color(color)
+                .draw(ctx);
+        }
+
         if let Some(real) = self.real {
-            let t = (real as f32 / self.data.max as f32).clamp(0.0, 1.0);
+            let t = self.data.fraction(real);
             let offset = Vector2::new(t * width, height + px * 2.0);
             Sprite::new(DOWN_ARROW)
                 .position(position + offset, Anchor::BottomCenter)
@@ -3,8 +3,20 @@ use std::f32::consts::PI;
 use rand::{Rng, rng};
 use thousands::Separable;
 
-use crate::{app::App, assets::UNDEAD_FONT, consts::color, ui::components::histogram::Histogram};
-use beam_logic::{level::Level, simulation::level_state::LevelResult};
+use crate::{
+    app::App,
+    assets::UNDEAD_FONT,
+    consts::color,
+    leaderboard::UploadStatus,
+    ui::components::{
+        button::ButtonExt,
+        histogram::{Histogram, histogram_tabs},
+    },
+};
+use beam_logic::{
+    level::{Level, validate::BoardError},
+    simulation::level_state::LevelResult,
+};
 use engine::{
     color::{OkLab, Rgb},
     drawable::{spacer::Spacer, text::Text},
@@ -16,8 +28,9 @@ use engine::{
     },
     memory_key,
 };
+use leaderboard::api::results::{Ranking, Solution};
 
-use super::{LevelPanel, horizontal_rule};
+use super::{BoardStats, LevelPanel, horizontal_rule};
 
 const CONGRATS: &[&str] = &[
     "Nice work!",
@@ -38,15 +51,17 @@ impl LevelPanel {
         layout: &mut ColumnLayout,
         level: &Level,
         level_result: &Option<LevelResult>,
-        price: u32,
+        stats: BoardStats,
     ) {
-        if let Some((result, price)) = level_result.map(|x| (x, price)).or(self.previous_result) {
-            self.previous_result = Some((result, price));
+        if let Some((result, stats)) = level_result.map(|x| (x, stats)).or(self.previous_result) {
+            self.previous_result = Some((result, stats));
             horizontal_rule(ctx, layout);
 
             match result {
                 LevelResult::Success { latency } => {
-                    success(ctx, state, layout, level, (price, latency))
+                    let invalid = self.invalid_solution;
+                    self.view_solution |=
+                        success(ctx, state, layout, level, stats, latency, invalid)
                 }
                 LevelResult::Failed { case } => {
                     let idx = level.tests.visible_index(case) + 1;
@@ -63,16 +78,19 @@ fn success(
     state: &App,
     layout: &mut ColumnLayout,
     level: &Level,
-    (price, latency): (u32, u32),
-) {
+    stats: BoardStats,
+    latency: u32,
+    invalid: Option<BoardError>,
+) -> bool {
     let now = state.start.elapsed().as_secs_f32();
     let congrat = *ctx
         .memory
         .get_or_insert_with(memory_key!(), || rng().random_range(0..CONGRATS.len()));
+    let mut view_solution = false;
     let text = format!(
         "{} Your solution costs ${} and has a total latency of {latency} ticks.",
         CONGRATS[congrat],
-        price.separate_with_commas()
+        stats.price.separate_with_commas()
     );
 
     layout.nest(
@@ -104,8 +122,34 @@ fn success(
                 .layout(ctx, layout);
 
             Spacer::new_y(8.0).layout(ctx, layout);
-            let Some(hist_data) = state.leaderboard.get_results(level.id) else {
-                Text::new(UNDEAD_FONT, "Failed to load global leaderboard.")
+            if let Some(text) = upload_status(state, level, invalid) {
+                Text::new(UNDEAD_FONT, text)
+                    .scale(Vector2::repeat(2.0))
+                    .max_width(layout.available().x)
+                    .layout(ctx, layout);
+                Spacer::new_y(8.0).layout(ctx, layout);
+            }
+
+            // Users in a group can switch to histograms of just their group
+            let group = state.leaderboard.group().is_some()
+                && histogram_tabs(ctx, layout, memory_key!(), &["Global", "My Group"]) == 1;
+            let hist_data = if group {
+                state.leaderboard.get_group_results(level.id)
+            } else {
+                state.leaderboard.get_results(level.id)
+            };
+
+            let Some(hist_data) = hist_data else {
+                let text = match state.leaderboard.incompatible() {
+                    Some(version) => format!(
+                        "The leaderboard server (v{version}) isn't compatible with this version \
+                         of the game."
+                    ),
+                    None if group => "Failed to load group leaderboard.".into(),
+                    None => "Failed to load global leaderboard.".into(),
+                };
+                Text::new(UNDEAD_FONT, text)
+                    .max_width(layout.available().x)
                     .scale(Vector2::repeat(2.0))
                     .color(color::ERROR)
                     .layout(ctx, layout);
@@ -113,25 +157,146 @@ fn success(
                 return;
             };
 
+            // Mark the user's uploaded solutions, highlighting any that are on
+            // the global pareto frontier
+            let marks = |stat: fn(&Solution) -> u32| {
+                let personal = state.leaderboard.get_personal(level.id);
+                let frontier = state.leaderboard.get_frontier(level.id);
+                (personal.into_iter().flat_map(|x| x.solutions.iter()))
+                    .map(|x| (stat(x), frontier.is_some_and(|y| y.frontier.contains(x))))
+                    .collect::<Vec<_>>()
+            };
+
+            let tab = histogram_tabs(
+                ctx,
+                layout,
+                memory_key!(),
+                &["Cost & Latency", "Tiles & Area"],
+            );
+            let [left, right] = match tab {
+                0 => [
+                    Histogram::new(hist_data.cost.clone())
+                        .real(stats.price)
+                        .marks(marks(|x| x.cost))
+                        .title("Cost"),
+                    Histogram::new(hist_data.latency.clone())
+                        .real(latency)
+                        .marks(marks(|x| x.latency))
+                        .title("Latency"),
+                ],
+                _ => [
+                    Histogram::new(hist_data.tiles.clone())
+                        .real(stats.tiles)
+                        .marks(marks(|x| x.tiles))
+                        .title("Tiles"),
+                    Histogram::new(hist_data.area.clone())
+                        .real(stats.area)
+                        .marks(marks(|x| x.area))
+                        .title("Area"),
+                ],
+            };
+
             layout.nest(ctx, RowLayout::new(0.0), |ctx, layout| {
-                Histogram::new(hist_data.cost)
-                    .real(price)
-                    .title("Cost")
-                    .layout(ctx, layout);
+                left.layout(ctx, layout);
                 layout.nest(
                     ctx,
                     layout.clone().direction(Direction::MaxToMin),
                     |ctx, layout| {
-                        Histogram::new(hist_data.latency)
-                            .real(latency)
-                            .title("Latency")
-                            .layout(ctx, layout);
+                        right.layout(ctx, layout);
                         Spacer::new_x(layout.available().x).layout(ctx, layout);
                     },
                 );
             });
+
+            if let Some(text) = standing(state, level) {
+                Spacer::new_y(4.0).layout(ctx, layout);
+                Text::new(UNDEAD_FONT, text)
+                    .scale(Vector2::repeat(2.0))
+                    .max_width(layout.available().x)
+                    .layout(ctx, layout);
+
+                // Only shown once the server has verified the user's own
+                // solution, as it's the only way it will send solutions
+                Spacer::new_y(4.0).layout(ctx, layout);
+                Text::new(UNDEAD_FONT, "View Top Solution")
+                    .scale(Vector2::repeat(2.0))
+                    .button(memory_key!())
+                    .on_click(ctx, || view_solution = true)
+                    .layout(ctx, layout);
+            }
         },
     );
+
+    view_solution
+}
+
+/// Describes the upload of the user's solution, if it hasn't finished yet or
+/// was rejected. Solutions that break the level's rules aren't uploaded.
+fn upload_status(state: &App, level: &Level, invalid: Option<BoardError>) -> Option<String> {
+    if let Some(err) = invalid {
+        return Some(format!(
+            "Your solution wasn't uploaded to the leaderboard. {err}."
+        ));
+    }
+
+    let Some(status) = state.leaderboard.upload_status(level.id) else {
+        let err = state.leaderboard.upload_error(level.id)?;
+        return Some(format!(
+            "The leaderboard didn't accept your solution. {err}."
+        ));
+    };
+
+    if let Some(err) = state.leaderboard.registration_error()
+        && !err.is_temporary()
+    {
+        return Some(format!(
+            "The leaderboard refused this device's key, so your solutions can't be uploaded \
+             from it. {err}."
+        ));
+    } else if let Some(err) = state.leaderboard.registration_error() {
+        return Some(format!(
+            "The leaderboard didn't accept this device's key, so your solution is waiting to be \
+             uploaded. {err}."
+        ));
+    }
+
+    Some(match status {
+        UploadStatus::Uploading => "Uploading your solution...".into(),
+        UploadStatus::Waiting { attempts: 0, .. } => {
+            "Your solution is waiting to be uploaded.".into()
+        }
+        UploadStatus::Waiting { retry_in, .. } => format!(
+            "Couldn't reach the leaderboard, your solution will be uploaded once it's back. \
+             Retrying in {}s.",
+            retry_in.as_secs().max(1)
+        ),
+    })
+}
+
+/// Describes where the user's best uploaded solution ranks globally, along
+/// with the best overall solution.
+fn standing(state: &App, level: &Level) -> Option<String> {
+    let personal = state.leaderboard.get_personal(level.id)?;
+    let mut text = format!(
+        "Your best solution ranks #{} by cost, #{} by latency, and #{} overall out of {} \
+         solutions, beating or tying {:.0}% of them.",
+        personal.by_cost.rank,
+        personal.by_latency.rank,
+        personal.by_score.rank,
+        personal.total,
+        personal.by_score.percentile
+    );
+
+    let ranking = state.leaderboard.get_ranking(level.id, Ranking::Score);
+    if let Some(best) = ranking.and_then(|x| x.results.first()) {
+        text += &format!(
+            " The top solution costs ${} with a latency of {} ticks.",
+            best.cost.separate_with_commas(),
+            best.latency
+        );
+    }
+
+    Some(text)
 }
 
 fn failed(ctx: &mut GraphicsContext, layout: &mut ColumnLayout, case: usize) {
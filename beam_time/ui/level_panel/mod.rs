@@ -13,8 +13,8 @@ use crate::{
     ui::components::button::{ButtonEffects, ButtonExt},
 };
 use beam_logic::{
-    level::Level,
-    misc::price,
+    level::{Level, validate::BoardError},
+    misc::{area, price},
     simulation::{level_state::LevelResult, runtime::asynchronous::InnerAsyncSimulationState},
 };
 use engine::{
@@ -38,10 +38,22 @@ mod test_case;
 
 pub struct LevelPanel {
     pub case: usize,
+    /// Set when the user asks to view the top solution to the level.
+    pub view_solution: bool,
+    /// Why the last solution wasn't uploaded, if it broke the level's rules.
+    pub invalid_solution: Option<BoardError>,
 
     collapsed: bool,
     height: f32,
-    previous_result: Option<(LevelResult, u32)>,
+    previous_result: Option<(LevelResult, BoardStats)>,
+}
+
+/// Stats of the board that are compared against the global leaderboard.
+#[derive(Clone, Copy)]
+struct BoardStats {
+    price: u32,
+    tiles: u32,
+    area: u32,
 }
 
 const WIDTH: usize = 7;
@@ -64,6 +76,11 @@ impl LevelPanel {
 
         // idk maybe cache or smth — not that it really matters
         let (price, tiles) = price(&board.tiles, level);
+        let stats = BoardStats {
+            price,
+            tiles: tiles as u32,
+            area: area(&board.tiles, level).unwrap_or(u32::MAX),
+        };
 
         let trackers @ [base, extended] = [memory_key!(), memory_key!()].map(LayoutTracker::new);
         let tracker = trackers[level_result.is_some() as usize];
@@ -88,7 +105,7 @@ impl LevelPanel {
                     self.level_info(ctx, layout, level, price, tiles);
                     self.test_case(ctx, layout, level, sim);
                     dummy().tracked(base).layout(ctx, layout);
-                    self.level_status(ctx, state, layout, level, level_result, price);
+                    self.level_status(ctx, state, layout, level, level_result, stats);
                     dummy().tracked(extended).layout(ctx, layout);
                 });
             });
@@ -182,6 +199,8 @@ impl Default for LevelPanel {
     fn default() -> Self {
         Self {
             case: 0,
+            view_solution: false,
+            invalid_solution: None,
 
             collapsed: false,
             height: 0.0,
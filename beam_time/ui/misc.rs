@@ -3,6 +3,8 @@ use engine::{
     graphics_context::GraphicsContext,
 };
 
+use common::direction::Direction;
+
 use crate::assets::UNDEAD_FONT;
 
 pub fn tile_label(scale: f32, text_scale: f32, pos: Vector2<f32>, label: impl ToString) -> Text {
@@ -12,6 +14,14 @@ pub fn tile_label(scale: f32, text_scale: f32, pos: Vector2<f32>, label: impl To
         .position(pos + offset, Anchor::BottomRight)
 }
 
+/// Labels the side of a tile, used for the ports of chips.
+pub fn side_label(scale: f32, pos: Vector2<f32>, side: Direction, label: impl ToString) -> Text {
+    let offset = side.offset(Vector2::zeros()).map(|x| x as f32) * 5.0 * scale;
+    Text::new(UNDEAD_FONT, label)
+        .scale(Vector2::repeat((scale / 4.0).max(0.75)))
+        .position(pos + offset, Anchor::Center)
+}
+
 pub fn body(max_width: f32) -> impl Fn(&str) -> Text {
     move |text| {
         Text::new(UNDEAD_FONT, text)
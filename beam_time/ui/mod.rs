@@ -7,4 +7,5 @@ pub mod level_panel;
 pub mod misc;
 pub mod pixel_line;
 pub mod tile_picker;
+pub mod toasts;
 pub mod waterfall;
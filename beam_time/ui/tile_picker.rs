@@ -72,9 +72,8 @@ impl TilePicker {
                 x => x,
             };
 
-            let disabled = (board.transient.level)
-                .and_then(|x| x.disabled.as_ref())
-                .is_some_and(|disabled| disabled.contains(&tile.as_type()));
+            let disabled =
+                (board.transient.level).is_some_and(|level| level.is_disabled(tile.as_type()));
 
             if !disabled && ctx.input.key_pressed(key) {
                 board.transient.holding = Holding::Tile(*tile);
@@ -0,0 +1,67 @@
+use std::time::{Duration, Instant};
+
+use engine::{
+    color::Rgb,
+    drawable::{Anchor, Drawable, text::Text},
+    exports::nalgebra::Vector2,
+    graphics_context::GraphicsContext,
+};
+
+use crate::{
+    assets::UNDEAD_FONT,
+    consts::{color, layer, spacing::MARGIN},
+};
+
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+
+/// Short messages shown at the bottom of the screen for a few seconds, for
+/// the results of actions that don't show anything otherwise.
+#[derive(Default)]
+pub struct Toasts {
+    toasts: Vec<Toast>,
+}
+
+struct Toast {
+    text: String,
+    error: bool,
+    shown: Instant,
+}
+
+impl Toasts {
+    pub fn info(&mut self, text: impl Into<String>) {
+        self.push(text.into(), false);
+    }
+
+    pub fn error(&mut self, text: impl Into<String>) {
+        self.push(text.into(), true);
+    }
+
+    fn push(&mut self, text: String, error: bool) {
+        self.toasts.push(Toast {
+            text,
+            error,
+            shown: Instant::now(),
+        });
+    }
+
+    pub fn render(&mut self, ctx: &mut GraphicsContext) {
+        self.toasts.retain(|x| x.shown.elapsed() < TOAST_DURATION);
+
+        // The newest toast is at the bottom, older ones move up above it
+        let mut pos = Vector2::new(ctx.center().x, MARGIN);
+        for toast in self.toasts.iter().rev() {
+            let text = Text::new(UNDEAD_FONT, &toast.text)
+                .position(pos, Anchor::BottomCenter)
+                .scale(Vector2::repeat(2.0))
+                .max_width(ctx.size().x - MARGIN * 2.0)
+                .color(if toast.error {
+                    color::ERROR
+                } else {
+                    Rgb::repeat(1.0)
+                })
+                .z_index(layer::UI_OVERLAY + 1);
+            pos.y += text.size(ctx).y + MARGIN / 2.0;
+            text.draw(ctx);
+        }
+    }
+}
@@ -1,12 +1,23 @@
+use std::{fmt, str::FromStr};
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Hash, Serialize, Deserialize, PartialEq, Eq)]
 pub enum UserId {
     Hardware(u64),
     Steam(u64),
 }
 
 impl UserId {
+    /// Inverse of [`UserId::type_id`] and [`UserId::inner`].
+    pub fn from_parts(type_id: u8, inner: u64) -> Option<Self> {
+        match type_id {
+            0 => Some(UserId::Hardware(inner)),
+            1 => Some(UserId::Steam(inner)),
+            _ => None,
+        }
+    }
+
     pub fn type_id(&self) -> u8 {
         match self {
             UserId::Hardware(_) => 0,
@@ -19,3 +30,27 @@ impl UserId {
         }
     }
 }
+
+/// Formats ids as `hardware-<id>` or `steam-<id>` for use in urls.
+impl fmt::Display for UserId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UserId::Hardware(id) => write!(f, "hardware-{id}"),
+            UserId::Steam(id) => write!(f, "steam-{id}"),
+        }
+    }
+}
+
+impl FromStr for UserId {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, id) = s.split_once('-').ok_or("Invalid user id")?;
+        let id = id.parse().map_err(|_| "Invalid user id")?;
+        match kind {
+            "hardware" => Ok(UserId::Hardware(id)),
+            "steam" => Ok(UserId::Steam(id)),
+            _ => Err("Unknown user id type"),
+        }
+    }
+}
@@ -155,6 +155,10 @@ impl Sprite {
 }
 
 impl Sprite {
+    pub fn get_texture(&self) -> SpriteRef {
+        self.texture
+    }
+
     pub fn get_z_index(&self) -> i16 {
         self.z_index
     }
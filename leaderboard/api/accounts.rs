@@ -0,0 +1,74 @@
+//! Accounts join the ids that someone plays under, like the hardware id of
+//! each of their machines and their Steam id, into a single identity with an
+//! optional display name. Each account has a secret token issued by the
+//! server, which clients keep in their data dir and use to make changes to the
+//! account.
+
+use serde::{Deserialize, Serialize};
+
+use common::user::UserId;
+
+pub const MAX_NAME_LENGTH: usize = 24;
+
+/// Gets the account a user is linked to, creating a new one if they aren't
+/// linked to any yet.
+#[derive(Serialize, Deserialize)]
+pub struct PostAccount {
+    pub user: UserId,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AccountResponse {
+    pub token: String,
+    pub name: Option<String>,
+}
+
+/// Changes an account's display name, or removes it if None.
+#[derive(Serialize, Deserialize)]
+pub struct PutName {
+    pub token: String,
+    pub name: Option<String>,
+}
+
+/// Creates a code that links another user to the account, see [`PostLink`].
+#[derive(Serialize, Deserialize)]
+pub struct PostLinkCode {
+    pub token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LinkCodeResponse {
+    pub code: String,
+    /// Unix timestamp of when the code stops working.
+    pub expires: u64,
+}
+
+/// Links a user to the account that created the code, leaving any account
+/// they were already linked to. Responds with the new account.
+#[derive(Serialize, Deserialize)]
+pub struct PostLink {
+    pub user: UserId,
+    pub code: String,
+}
+
+/// The public parts of the account a user is linked to.
+#[derive(Serialize, Deserialize)]
+pub struct GetProfileResponse {
+    pub name: Option<String>,
+}
+
+/// Checks that a display name is a reasonable length and only uses
+/// characters that the game's font can draw, returning the name without any
+/// surrounding whitespace. The server may reject other names too.
+pub fn validate_name(name: &str) -> Result<&str, &'static str> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Err("Names must be between 1 and 24 characters long");
+    }
+
+    if !(name.chars()).all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.')) {
+        return Err("Names can only have letters, numbers, spaces, dashes, underscores, and dots");
+    }
+
+    Ok(name)
+}
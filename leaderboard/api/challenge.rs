@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+use super::results::RankedResult;
+
+/// The challenge that is currently running. Challenges are regular levels
+/// that are only open for a limited time.
+#[derive(Serialize, Deserialize)]
+pub struct GetChallengeResponse {
+    /// The level in RON, in the same format as the built-in levels.
+    pub level: String,
+    /// Unix timestamp of when the challenge ends, after which no more
+    /// solutions are accepted.
+    pub end: u64,
+
+    /// The best solutions to the challenge, ranked by score.
+    pub scoreboard: Vec<RankedResult>,
+    /// The total number of solutions submitted for the challenge.
+    pub total: u32,
+}
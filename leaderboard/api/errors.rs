@@ -0,0 +1,121 @@
+//! Errors returned by the API. Failed requests respond with an [`ApiError`]
+//! as JSON, where the code tells clients what went wrong and the message can
+//! be shown to players.
+
+use std::{error::Error, fmt};
+
+use beam_logic::level::validate::BoardError;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The request was malformed or had invalid parameters.
+    BadRequest,
+    /// The request wasn't authorized with the HMAC key.
+    InvalidAuthorization,
+    /// The request wasn't signed with the user's registered key.
+    InvalidSignature,
+    /// The server only accepts requests signed with a registered key.
+    KeyRequired,
+    /// A different key is already registered to the user.
+    KeyConflict,
+    /// The Steam auth ticket sent to register a key was missing or didn't
+    /// belong to the user.
+    InvalidTicket,
+    Banned,
+    /// The request isn't allowed, like viewing solutions to an unsolved
+    /// level.
+    Forbidden,
+    RateLimited,
+    UnknownLevel,
+    /// The challenge the level is part of isn't running.
+    ChallengeInactive,
+    NotFound,
+    UnsupportedTileVersion,
+    TooManyTiles,
+    DuplicateDynamicTiles,
+    UndefinedDynamicTiles,
+    ModifiedPermanentTiles,
+    OutOfBounds,
+    ChipsNotAllowed,
+    DisabledTiles,
+    /// The placed tiles are spread out too far for their area to be stored.
+    BoardTooLarge,
+    /// The solution didn't finish within the server's time limit.
+    OutOfTime,
+    InvalidName,
+    NameLocked,
+    Internal,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Whether sending the same request again could succeed. Rejected
+    /// solutions won't be accepted later, but rate limits and server errors
+    /// are temporary, as are problems with the user's key that go away once
+    /// it's registered. A key conflict lasts until the other key is reset or
+    /// the key is verified, so it isn't.
+    pub fn is_temporary(&self) -> bool {
+        matches!(
+            self.code,
+            ErrorCode::RateLimited
+                | ErrorCode::Internal
+                | ErrorCode::InvalidSignature
+                | ErrorCode::KeyRequired
+                | ErrorCode::InvalidTicket
+        )
+    }
+
+    /// Whether the request failed because the user's key isn't registered,
+    /// or the server doesn't have it anymore.
+    pub fn is_key_error(&self) -> bool {
+        matches!(
+            self.code,
+            ErrorCode::InvalidSignature | ErrorCode::KeyRequired
+        )
+    }
+}
+
+impl From<BoardError> for ApiError {
+    fn from(err: BoardError) -> Self {
+        let code = match err {
+            BoardError::DuplicateDynamicTiles => ErrorCode::DuplicateDynamicTiles,
+            BoardError::UndefinedDynamicTiles => ErrorCode::UndefinedDynamicTiles,
+            BoardError::ModifiedPermanentTiles => ErrorCode::ModifiedPermanentTiles,
+            BoardError::OutOfBounds => ErrorCode::OutOfBounds,
+            BoardError::ChipsNotAllowed => ErrorCode::ChipsNotAllowed,
+            BoardError::DisabledTiles => ErrorCode::DisabledTiles,
+            BoardError::TooLarge => ErrorCode::BoardTooLarge,
+        };
+        Self::new(code, format!("{err}, solution rejected"))
+    }
+}
+
+/// Unexpected errors, like database failures, are internal errors. Their
+/// details stay on the server, so clients only get a generic message.
+impl From<anyhow::Error> for ApiError {
+    fn from(_: anyhow::Error) -> Self {
+        Self::new(ErrorCode::Internal, "Internal server error")
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for ApiError {}
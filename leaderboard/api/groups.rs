@@ -0,0 +1,29 @@
+//! Groups let users compare their results with just their friends. Anyone
+//! can make a group by joining it with a new code, and results can be filtered
+//! to the members of a group by passing its code as the `group` query
+//! parameter.
+
+use serde::{Deserialize, Serialize};
+
+use common::user::UserId;
+
+pub const MAX_CODE_LENGTH: usize = 32;
+
+/// Sets the group a user is in, leaving their old one. Users can only be in
+/// one group at a time, and a code of None just leaves the current group.
+#[derive(Serialize, Deserialize)]
+pub struct PostGroup {
+    pub user: UserId,
+    pub code: Option<String>,
+}
+
+/// Codes are case insensitive and made of letters, numbers, dashes, and
+/// underscores. Returns the lowercase code, or None if it's invalid.
+pub fn normalize_code(code: &str) -> Option<String> {
+    let code = code.trim();
+    let valid = (1..=MAX_CODE_LENGTH).contains(&code.len())
+        && code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then(|| code.to_ascii_lowercase())
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// Bumped whenever the API changes in a way that older clients or servers
+/// can't handle.
+///
+/// 2. Uploads are signed with keys registered to each user.
+/// 3. Errors are sent as JSON with a code.
+/// 4. Signatures cover the request's method and path along with its body.
+pub const API_VERSION: u32 = 4;
+
+#[derive(Serialize, Deserialize)]
+pub struct GetInfoResponse {
+    /// Version of the leaderboard server.
+    pub version: String,
+    pub api_version: u32,
+}
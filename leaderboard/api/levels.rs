@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize)]
+pub struct GetLevelsResponse {
+    pub levels: Vec<LevelInfo>,
+}
+
+/// A level that solutions can be uploaded for.
+#[derive(Serialize, Deserialize)]
+pub struct LevelInfo {
+    pub id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub size: Option<[u32; 2]>,
+    /// If the level is a running challenge instead of part of the campaign.
+    pub challenge: bool,
+}
@@ -1,2 +1,9 @@
+pub mod accounts;
+pub mod challenge;
+pub mod errors;
+pub mod groups;
 pub mod hmac;
+pub mod info;
+pub mod levels;
 pub mod results;
+pub mod signing;
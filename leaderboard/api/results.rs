@@ -1,12 +1,87 @@
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 
 use beam_logic::tile::Tile;
 use common::{map::Map, user::UserId};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct GetResultsResponse {
     pub cost: Histogram,
     pub latency: Histogram,
+    /// Number of tiles placed, missing from older servers.
+    #[serde(default)]
+    pub tiles: Histogram,
+    /// Bounding box area of the placed tiles, missing from older servers.
+    #[serde(default)]
+    pub area: Histogram,
+}
+
+/// The best solutions to a level, ordered by their rank.
+#[derive(Serialize, Deserialize)]
+pub struct GetRankingResponse {
+    pub results: Vec<RankedResult>,
+    /// The total number of solutions submitted for the level.
+    pub total: u32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RankedResult {
+    /// Solutions that are tied share the same rank, starting from 1.
+    pub rank: u32,
+    pub cost: u32,
+    pub latency: u32,
+    /// Display name of the account that uploaded the solution, if it has one.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// A user's best solutions to a level and where they place against everyone
+/// else's. The cost, latency, and ranks each use the user's best solution for
+/// that stat.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GetUserResultsResponse {
+    pub cost: u32,
+    pub latency: u32,
+    pub total: u32,
+
+    pub by_cost: Standing,
+    pub by_latency: Standing,
+    pub by_score: Standing,
+
+    /// The user's pareto optimal solutions, sorted by cost.
+    pub solutions: Vec<Solution>,
+}
+
+/// The pareto frontier of every solution submitted for a level, sorted by
+/// cost.
+#[derive(Serialize, Deserialize)]
+pub struct GetFrontierResponse {
+    pub frontier: Vec<Solution>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Solution {
+    pub cost: u32,
+    pub latency: u32,
+    pub tiles: u32,
+    #[serde(default)]
+    pub area: u32,
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub struct Standing {
+    pub rank: u32,
+    /// Percent of solutions that this solution matches or beats.
+    pub percentile: f32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Ranking {
+    Cost,
+    Latency,
+    /// Ranks by the product of cost and latency, see [`score`].
+    Score,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -23,32 +98,163 @@ pub struct PutResultsRef<'a> {
     pub version: u32,
 }
 
-#[derive(Copy, Clone, Default, Serialize, Deserialize)]
+impl Ranking {
+    pub const ALL: [Ranking; 3] = [Ranking::Cost, Ranking::Latency, Ranking::Score];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Ranking::Cost => "cost",
+            Ranking::Latency => "latency",
+            Ranking::Score => "score",
+        }
+    }
+}
+
+impl FromStr for Ranking {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        (Ranking::ALL.into_iter())
+            .find(|x| x.name() == s)
+            .ok_or("Unknown ranking, expected cost, latency, or score")
+    }
+}
+
+impl Solution {
+    /// Checks if this solution is at least as good as `other` in every stat.
+    pub fn covers(&self, other: &Solution) -> bool {
+        self.cost <= other.cost
+            && self.latency <= other.latency
+            && self.tiles <= other.tiles
+            && self.area <= other.area
+    }
+}
+
+/// Filters solutions down to the ones that no other solution beats in every
+/// stat, sorted by cost. Duplicate solutions are only included once.
+pub fn pareto_frontier(mut solutions: Vec<Solution>) -> Vec<Solution> {
+    // Once sorted, a solution can only be covered by ones that come before it
+    solutions.sort_by_key(|x| (x.cost, x.latency, x.tiles, x.area));
+    solutions.dedup();
+
+    let mut frontier = Vec::<Solution>::new();
+    for solution in solutions {
+        if !frontier.iter().any(|x| x.covers(&solution)) {
+            frontier.push(solution);
+        }
+    }
+
+    frontier
+}
+
+impl Standing {
+    /// Creates a standing from the number of solutions that are strictly
+    /// better than this one.
+    pub fn new(better: u32, total: u32) -> Self {
+        Self {
+            rank: better + 1,
+            percentile: (total - better) as f32 / total.max(1) as f32 * 100.0,
+        }
+    }
+}
+
+/// The combined score of a solution used for [`Ranking::Score`], where lower
+/// is better. Multiplying keeps the score from favoring either stat just
+/// because its values tend to be larger.
+pub fn score(cost: u32, latency: u32) -> u64 {
+    cost as u64 * latency as u64
+}
+
+/// Requests the solution at `index` in a level's ranking. Solutions are only
+/// sent to users that have already solved the level themselves.
+#[derive(Serialize, Deserialize)]
+pub struct GetSolution {
+    pub user: UserId,
+    pub ranking: Ranking,
+    pub index: u32,
+}
+
+#[derive(Serialize)]
+pub struct GetSolutionRef<'a> {
+    pub user: &'a UserId,
+    pub ranking: Ranking,
+    pub index: u32,
+}
+
+/// A solution as a list of tiles, for clients that use JSON, which can't have
+/// positions as map keys.
+#[derive(Serialize, Deserialize)]
+pub struct GetSolutionTilesResponse {
+    pub tiles: Vec<SolutionTile>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SolutionTile {
+    pub x: i32,
+    pub y: i32,
+    pub tile: Tile,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct Histogram {
-    pub bins: [u32; 12],
+    pub bins: Vec<u32>,
     pub max: u32,
+    /// If the bins are spaced evenly on a log scale instead of linearly.
+    #[serde(default)]
+    pub log_scale: bool,
 }
 
 impl Histogram {
-    const BIN_COUNT: usize = 12;
-
-    pub fn new(data: &[u32]) -> Self {
+    /// Sorts data into `bins` bins spanning from zero to a max value. Linear
+    /// histograms leave out outliers by using 1.5 IQR past the third quartile
+    /// as the max, while log scale ones go up to the largest value.
+    pub fn new(data: &[u32], bins: usize, log_scale: bool) -> Self {
         let mut sorted = data.to_vec();
         sorted.sort();
 
-        let q3 = sorted[sorted.len() * 3 / 4];
-        let q1 = sorted[sorted.len() / 4];
-        let iqr = q3 - q1;
+        let max = match sorted.last() {
+            None => 0,
+            Some(&last) if log_scale => last,
+            Some(_) => {
+                let q3 = sorted[sorted.len() * 3 / 4];
+                let q1 = sorted[sorted.len() / 4];
+                let max = q3 as u64 + (q3 - q1) as u64 * 3 / 2;
+                max.min(u32::MAX as u64) as u32
+            }
+        };
 
-        let max = q3 + iqr * 3 / 2;
-        let bin_width = max as f32 / Self::BIN_COUNT as f32;
+        let mut histogram = Self {
+            bins: vec![0; bins.max(1)],
+            max,
+            log_scale,
+        };
 
-        let mut bins = [0; Self::BIN_COUNT];
         for &point in data {
-            let bin = (point as f32 / bin_width) as usize;
-            bins[bin.min(Self::BIN_COUNT - 1)] += 1;
+            let bin = histogram.bin(point);
+            histogram.bins[bin] += 1;
         }
 
-        Self { bins, max }
+        histogram
+    }
+
+    /// Where a value falls along the histogram, from zero at the start to one
+    /// at the max.
+    pub fn fraction(&self, value: u32) -> f32 {
+        let t = if self.log_scale {
+            (value as f32).ln_1p() / (self.max as f32).ln_1p()
+        } else {
+            value as f32 / self.max as f32
+        };
+
+        // A max of zero gives NaN for zero and infinity for anything else
+        if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) }
+    }
+
+    /// The index of the bin that a value falls into. Values past `max` go in
+    /// the last bin.
+    pub fn bin(&self, value: u32) -> usize {
+        let count = self.bins.len();
+        let bin = (self.fraction(value) * count as f32) as usize;
+        bin.min(count.saturating_sub(1))
     }
 }
@@ -0,0 +1,64 @@
+//! Public key signatures for requests. Each client generates its own key and
+//! registers the public half with the server, so unlike the shared HMAC key,
+//! requests for a user can't be forged by anyone else.
+
+use ed25519_dalek::{Signature, SignatureError, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use common::user::UserId;
+
+/// Identity that Steam auth tickets are requested for, which the server checks
+/// them against.
+pub const STEAM_TICKET_IDENTITY: &str = "beam-time-leaderboard";
+
+/// Binds a public key to a user. Once registered, the server only accepts
+/// requests for that user that are signed with one of their keys. Servers
+/// that check Steam users need the request to have a hex encoded auth ticket
+/// for [`STEAM_TICKET_IDENTITY`] in its `Steam-Ticket` header.
+#[derive(Serialize, Deserialize)]
+pub struct PostKey {
+    pub user: UserId,
+    pub public_key: [u8; 32],
+}
+
+pub fn public_key(secret: &[u8; 32]) -> [u8; 32] {
+    SigningKey::from_bytes(secret).verifying_key().to_bytes()
+}
+
+/// The message signed for a request. The method and path are signed along
+/// with the body, so a signed body can't be replayed against another route,
+/// like sending the same upload for a different level.
+pub fn request_message(method: &str, path: &str, body: &[u8]) -> Vec<u8> {
+    let mut message = format!("{method} {path}\n").into_bytes();
+    message.extend_from_slice(body);
+    message
+}
+
+pub fn sign(secret: &[u8; 32], val: &[u8]) -> Vec<u8> {
+    SigningKey::from_bytes(secret).sign(val).to_vec()
+}
+
+pub fn verify(public_key: &[u8; 32], val: &[u8], signature: &[u8]) -> Result<(), SignatureError> {
+    let key = VerifyingKey::from_bytes(public_key)?;
+    key.verify(val, &Signature::from_slice(signature)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{public_key, request_message, sign, verify};
+
+    #[test]
+    fn signatures_cover_method_and_path() {
+        let (secret, body) = ([7; 32], b"solution");
+        let key = public_key(&secret);
+
+        let message = request_message("PUT", "/api/a/results", body);
+        let signature = sign(&secret, &message);
+        assert!(verify(&key, &message, &signature).is_ok());
+
+        for (method, path) in [("PUT", "/api/b/results"), ("POST", "/api/a/results")] {
+            let message = request_message(method, path, body);
+            assert!(verify(&key, &message, &signature).is_err());
+        }
+    }
+}
@@ -1,25 +1,170 @@
-use std::{env::args, fs};
+use std::{fs, sync::Arc};
 
+use afire::{HeaderName, Request};
 use anyhow::{Context, Result};
+use beam_logic::level::{Level, default::DEFAULT_LEVELS};
+use common::{consts::API_HMAC_KEY, user::UserId};
+use leaderboard::api::{
+    accounts::validate_name,
+    errors::{ApiError, ErrorCode},
+    hmac, signing,
+};
 use rusqlite::Connection;
+use uuid::Uuid;
 
-use crate::{config::Config, database::Database};
+use crate::{
+    challenge::Challenge,
+    config::Config,
+    database::{Database, histograms::HistogramLayout},
+    histogram_worker::HistogramWorker,
+    metrics::Metrics,
+    middleware::rate_limit::RateLimiter,
+};
 
 pub struct App {
     pub config: Config,
-    pub db: Database,
+    pub db: Arc<Database>,
+    pub histograms: HistogramWorker,
+    pub upload_limit: RateLimiter<UserId>,
+    pub challenges: Vec<Challenge>,
+    pub metrics: Arc<Metrics>,
 }
 
 impl App {
-    pub fn new() -> Result<Self> {
-        let config_path = args().nth(1).unwrap_or_else(|| "config.toml".to_string());
+    /// Loads the config and opens the database, running any pending
+    /// migrations. With `dry_run`, migrations are rolled back once they finish.
+    pub fn new(config_path: &str, dry_run: bool) -> Result<Self> {
         let raw_config = fs::read_to_string(config_path).context("While reading config")?;
         let config = toml::from_str::<Config>(&raw_config).context("While parsing config")?;
 
         fs::create_dir_all(config.server.database_path.parent().unwrap())?;
-        let db = Database::new(Connection::open(&config.server.database_path)?);
-        db.init()?;
+        let connection = Connection::open(&config.server.database_path)?;
+        let layout = HistogramLayout {
+            bins: config.histograms.bins,
+            log_scale: config.histograms.log_scale,
+        };
+        let db = Arc::new(Database::new(connection, layout));
+        db.init(dry_run)?;
 
-        Ok(Self { config, db })
+        let histograms = HistogramWorker::spawn(db.clone(), &config.histograms);
+
+        let challenges = (config.challenges.iter())
+            .map(Challenge::load)
+            .collect::<Result<Vec<_>>>()?;
+
+        let upload_limit = RateLimiter::per_minute(config.rate_limit.user_uploads);
+        Ok(Self {
+            config,
+            db,
+            histograms,
+            upload_limit,
+            challenges,
+            metrics: Arc::default(),
+        })
+    }
+
+    /// Finds a campaign level or challenge, even if the challenge isn't
+    /// running.
+    pub fn find_level(&self, id: Uuid) -> Option<&'static Level> {
+        let challenge = self.challenges.iter().find(|x| x.level.id == id);
+        (DEFAULT_LEVELS.iter().find(|x| x.id == id)).or(challenge.map(|x| x.level))
+    }
+
+    /// Every campaign level, followed by the challenge that is running.
+    pub fn levels(&self) -> impl Iterator<Item = (&'static Level, bool)> {
+        let challenge = self.challenge().map(|x| (x.level, true));
+        (DEFAULT_LEVELS.iter().map(|x| (x, false))).chain(challenge)
+    }
+
+    /// The challenge that is currently running, if any.
+    pub fn challenge(&self) -> Option<&Challenge> {
+        self.challenges.iter().find(|x| x.is_active())
+    }
+
+    /// Finds a level that solutions can currently be uploaded to.
+    pub fn upload_level(&self, id: Uuid) -> Result<&'static Level, ApiError> {
+        if let Some(level) = DEFAULT_LEVELS.iter().find(|x| x.id == id) {
+            return Ok(level);
+        }
+
+        let challenge = (self.challenges.iter())
+            .find(|x| x.level.id == id)
+            .ok_or_else(|| ApiError::new(ErrorCode::UnknownLevel, "Level not found"))?;
+        if !challenge.is_active() {
+            return Err(ApiError::new(
+                ErrorCode::ChallengeInactive,
+                "This challenge isn't running",
+            ));
+        }
+        Ok(challenge.level)
+    }
+
+    /// Checks that a display name is allowed, returning it cleaned up. This
+    /// is where any other moderation of names should happen.
+    pub fn moderate_name<'a>(&self, name: &'a str) -> Result<&'a str, ApiError> {
+        let name = validate_name(name).map_err(|x| ApiError::new(ErrorCode::InvalidName, x))?;
+        let lower = name.to_lowercase();
+        if (self.config.accounts.blocked_words.iter()).any(|x| lower.contains(&x.to_lowercase())) {
+            return Err(ApiError::new(
+                ErrorCode::InvalidName,
+                "That name isn't allowed",
+            ));
+        }
+        Ok(name)
+    }
+
+    pub fn hmac_key(&self) -> &[u8] {
+        (self.config.server.hmac_key.as_ref())
+            .map(|x| x.as_bytes())
+            .unwrap_or(API_HMAC_KEY)
+    }
+
+    /// Checks that a request was sent by `user`. Users with registered keys
+    /// have to sign their requests with one of them, while everyone else can
+    /// use the HMAC key unless signatures are required.
+    pub fn authorize(&self, user: &UserId, req: &Request) -> Result<(), ApiError> {
+        let body = req.body.as_slice();
+        let signature = req.headers.get("Signature");
+        let hash = req.headers.get(HeaderName::Authorization);
+
+        // Internal errors only reach the client as a generic message, so the
+        // details are logged here
+        let keys = self.db.user_keys(user).map_err(|err| {
+            log::error!("Error loading user keys: {err:#}");
+            ApiError::from(err)
+        })?;
+        if !keys.is_empty() {
+            let signature = signature.and_then(|x| hex::decode(x).ok());
+            let Some(signature) = signature else {
+                return Err(ApiError::new(
+                    ErrorCode::InvalidSignature,
+                    "Request must be signed with your registered key",
+                ));
+            };
+            let message = signing::request_message(&req.method.to_string(), &req.path, body);
+            if !(keys.iter()).any(|key| signing::verify(key, &message, &signature).is_ok()) {
+                return Err(ApiError::new(
+                    ErrorCode::InvalidSignature,
+                    "Invalid signature",
+                ));
+            }
+            return Ok(());
+        }
+
+        if self.config.server.require_signatures {
+            return Err(ApiError::new(
+                ErrorCode::KeyRequired,
+                "A key must be registered before uploading",
+            ));
+        }
+
+        let hash = hash.and_then(|x| hex::decode(x).ok());
+        if hash.is_none_or(|hash| hmac::verify(self.hmac_key(), body, &hash).is_err()) {
+            return Err(ApiError::new(
+                ErrorCode::InvalidAuthorization,
+                "Invalid authorization",
+            ));
+        }
+        Ok(())
     }
 }
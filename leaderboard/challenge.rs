@@ -0,0 +1,52 @@
+//! Levels that are only open for a limited time. They are loaded from the
+//! server's config and sent to clients, so new levels don't need a game update.
+
+use std::{
+    fs,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result, bail};
+use beam_logic::level::{Level, default::DEFAULT_LEVELS};
+
+use crate::config::ChallengeConfig;
+
+pub struct Challenge {
+    /// Leaked when loaded so it can be simulated like the built-in levels.
+    pub level: &'static Level,
+    /// The level's RON source, which is sent to clients as is.
+    pub source: String,
+    pub start: u64,
+    pub end: u64,
+}
+
+impl Challenge {
+    pub fn load(config: &ChallengeConfig) -> Result<Self> {
+        let source = fs::read_to_string(&config.level)
+            .with_context(|| format!("While reading challenge {:?}", config.level))?;
+        let level = Level::load_slice(source.as_bytes())
+            .with_context(|| format!("While parsing challenge {:?}", config.level))?;
+
+        if DEFAULT_LEVELS.iter().any(|x| x.id == level.id) {
+            bail!(
+                "Challenge {:?} has the same id as a campaign level",
+                config.level
+            );
+        }
+
+        Ok(Self {
+            level: Box::leak(Box::new(level)),
+            source,
+            start: config.start,
+            end: config.end,
+        })
+    }
+
+    pub fn is_active(&self) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        (self.start..self.end).contains(&now)
+    }
+}
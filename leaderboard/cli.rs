@@ -0,0 +1,403 @@
+//! Admin commands that work on the database directly, without starting the
+//! server. Run as `leaderboard <command> [args] [--config <path>]`.
+
+use std::{borrow::Cow, collections::HashMap, fmt::Write, fs, str::FromStr, time::Duration};
+
+use anyhow::{Context, Result, anyhow, bail};
+use beam_logic::{
+    level::{Level, default::DEFAULT_LEVELS},
+    misc::{area, price},
+    simulation::{level_state::LevelResult, runtime::testing::TestingSimulationState},
+    tile::Tile,
+};
+use bincode::Options;
+use common::{consts::BINCODE_OPTIONS, map::Map, user::UserId};
+use log::{info, warn};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{app::App, database::admin::StoredResult};
+
+const USAGE: &str = "Usage:
+  leaderboard [config] [--dry-run]
+  leaderboard list [--level <level>] [--user <user>]
+  leaderboard delete <user> [--level <level>]
+  leaderboard ban <user> [--reason <reason>]
+  leaderboard unban <user>
+  leaderboard reset-key <user>
+  leaderboard account <user>
+  leaderboard clear-name <user> [--lock]
+  leaderboard unlock-name <user>
+  leaderboard verify [--delete]
+  leaderboard histograms
+  leaderboard export <csv|json> [output] [--level <level>] [--user <user>] [--solutions]
+
+Levels can be given by id or name and users as `hardware-<id>` or `steam-<id>`.
+Every command also accepts `--config <path>`.";
+
+/// Parsed arguments of a command.
+struct Args {
+    positional: Vec<String>,
+    options: HashMap<&'static str, String>,
+    flags: Vec<&'static str>,
+}
+
+/// Runs the admin command given in the program arguments. Returns None if
+/// there was no known command, in which case the server should start.
+pub fn run(args: &[String]) -> Option<Result<()>> {
+    let command = match args.first()?.as_str() {
+        "list" => list,
+        "delete" => delete,
+        "ban" => ban,
+        "unban" => unban,
+        "reset-key" => reset_key,
+        "account" => account,
+        "clear-name" => clear_name,
+        "unlock-name" => unlock_name,
+        "verify" => verify,
+        "histograms" => histograms,
+        "export" => export,
+        "help" | "--help" => {
+            println!("{USAGE}");
+            return Some(Ok(()));
+        }
+        _ => return None,
+    };
+
+    Some(Args::parse(&args[1..]).and_then(|args| {
+        let config = args.option("config").unwrap_or("config.toml");
+        let app = App::new(config, false)?;
+        command(&app, &args)
+    }))
+}
+
+fn list(app: &App, args: &Args) -> Result<()> {
+    let results = app.db.list_results(args.level()?, args.user()?.as_ref())?;
+
+    println!("id\tuser\tlevel\tcost\tlatency\ttiles\tarea\ttimestamp\tip");
+    for result in results.iter() {
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            result.id,
+            result.user,
+            level_name(&result.level_id),
+            result.cost,
+            result.latency,
+            result.tiles,
+            result.area,
+            result.timestamp,
+            result.ip_address
+        );
+    }
+
+    println!("{} results", results.len());
+    Ok(())
+}
+
+fn delete(app: &App, args: &Args) -> Result<()> {
+    let user = args.positional_user()?;
+    let deleted = app.db.delete_user_results(&user, args.level()?)?;
+    info!("Deleted {deleted} results from {user}");
+    Ok(())
+}
+
+fn ban(app: &App, args: &Args) -> Result<()> {
+    let user = args.positional_user()?;
+    let reason = args.option("reason").unwrap_or_default();
+    let deleted = app.db.ban_user(&user, reason)?;
+    info!("Banned {user} and deleted their {deleted} results");
+    Ok(())
+}
+
+fn unban(app: &App, args: &Args) -> Result<()> {
+    let user = args.positional_user()?;
+    if app.db.unban_user(&user)? {
+        info!("Unbanned {user}");
+    } else {
+        warn!("{user} was not banned");
+    }
+    Ok(())
+}
+
+/// Lets a user that lost their key, or had one registered by someone else,
+/// register a new one.
+fn reset_key(app: &App, args: &Args) -> Result<()> {
+    let user = args.positional_user()?;
+    if app.db.reset_key(&user)? {
+        info!("Removed the keys registered to {user}");
+    } else {
+        warn!("{user} has no registered keys");
+    }
+    Ok(())
+}
+
+/// Shows the account a user is linked to and every other user linked to it.
+fn account(app: &App, args: &Args) -> Result<()> {
+    let user = args.positional_user()?;
+    let Some(account) = app.db.user_account(&user)? else {
+        warn!("{user} isn't linked to an account");
+        return Ok(());
+    };
+
+    println!("id\t{}", account.id);
+    println!("name\t{}", account.name.as_deref().unwrap_or("-"));
+    println!("locked\t{}", account.name_locked);
+    for linked in app.db.linked_users(account.id)? {
+        println!("user\t{linked}");
+    }
+    Ok(())
+}
+
+/// Removes the display name of a user's account. With `--lock`, the account
+/// also can't choose a new name until it's unlocked.
+fn clear_name(app: &App, args: &Args) -> Result<()> {
+    let user = args.positional_user()?;
+    if !app.db.clear_name(&user)? {
+        warn!("{user} isn't linked to an account");
+        return Ok(());
+    }
+
+    if args.flag("lock") {
+        app.db.lock_name(&user, true)?;
+        info!("Cleared and locked the name of {user}'s account");
+    } else {
+        info!("Cleared the name of {user}'s account");
+    }
+    Ok(())
+}
+
+fn unlock_name(app: &App, args: &Args) -> Result<()> {
+    let user = args.positional_user()?;
+    if app.db.lock_name(&user, false)? {
+        info!("Unlocked the name of {user}'s account");
+    } else {
+        warn!("{user} isn't linked to an account");
+    }
+    Ok(())
+}
+
+/// Runs every stored solution again against the current levels, reporting
+/// any that no longer solve their level or have different stats than were
+/// stored. With `--delete`, those results are removed.
+fn verify(app: &App, args: &Args) -> Result<()> {
+    let results = app.db.list_results(None, None)?;
+    let mut invalid = Vec::new();
+
+    for result in results.iter() {
+        if let Err(err) = verify_result(app, result) {
+            warn!(
+                "Result {} from {} is invalid: {err}",
+                result.id, result.user
+            );
+            invalid.push(result.id);
+        }
+    }
+
+    info!(
+        "Verified {} results, {} were invalid",
+        results.len(),
+        invalid.len()
+    );
+
+    if args.flag("delete") && !invalid.is_empty() {
+        app.db.delete_results(&invalid)?;
+        info!("Deleted {} invalid results", invalid.len());
+    }
+
+    Ok(())
+}
+
+fn verify_result(app: &App, result: &StoredResult) -> Result<()> {
+    let level = app
+        .find_level(*result.level_id)
+        .context("Level no longer exists")?;
+    let board = BINCODE_OPTIONS.deserialize::<Map<Tile>>(&result.solution)?;
+
+    // The same checks that are done before accepting an upload
+    if board.tiles.len() > app.config.simulation.max_tiles {
+        bail!("Solution has too many tiles");
+    }
+    level.validate(&board).map_err(|err| anyhow!("{err}"))?;
+
+    let timeout = Duration::from_millis(app.config.simulation.timeout_ms);
+    let sim = TestingSimulationState::new(
+        &board,
+        Cow::Borrowed(level),
+        app.config.simulation.max_ticks,
+    )
+    .run_for(timeout)
+    .context("Simulation took too long")?;
+
+    let LevelResult::Success { latency } = sim else {
+        bail!("Solution no longer passes ({sim:?})");
+    };
+
+    let (cost, tiles) = price(&board, level);
+    let stored = (result.cost, result.latency, result.tiles, result.area);
+    let actual = (
+        cost,
+        latency,
+        tiles as u32,
+        area(&board, level).unwrap_or(u32::MAX),
+    );
+    if stored != actual {
+        bail!("Stored cost, latency, tiles, and area were {stored:?}, but are now {actual:?}");
+    }
+
+    Ok(())
+}
+
+fn histograms(app: &App, _args: &Args) -> Result<()> {
+    let levels = app.db.update_all_histograms()?;
+    info!("Rebuilt histograms for {levels} levels");
+    Ok(())
+}
+
+fn export(app: &App, args: &Args) -> Result<()> {
+    let format = args.positional.first().context(USAGE)?;
+    let results = app.db.list_results(args.level()?, args.user()?.as_ref())?;
+    let solutions = args.flag("solutions");
+
+    let out = match format.as_str() {
+        "csv" => {
+            let mut out = String::from("id,user,level,cost,latency,tiles,area,timestamp,ip");
+            solutions.then(|| out.push_str(",solution"));
+            out.push('\n');
+
+            for result in results.iter() {
+                write!(
+                    out,
+                    "{},{},{},{},{},{},{},{},{}",
+                    result.id,
+                    result.user,
+                    *result.level_id,
+                    result.cost,
+                    result.latency,
+                    result.tiles,
+                    result.area,
+                    result.timestamp,
+                    result.ip_address
+                )?;
+                if solutions {
+                    write!(out, ",{}", hex::encode(&result.solution))?;
+                }
+                out.push('\n');
+            }
+            out
+        }
+        "json" => {
+            let results = (results.iter())
+                .map(|result| {
+                    let mut json = json!({
+                        "id": result.id,
+                        "user": result.user.to_string(),
+                        "level": *result.level_id,
+                        "cost": result.cost,
+                        "latency": result.latency,
+                        "tiles": result.tiles,
+                        "area": result.area,
+                        "timestamp": result.timestamp,
+                        "ip": result.ip_address,
+                    });
+                    if solutions {
+                        json["solution"] = hex::encode(&result.solution).into();
+                    }
+                    json
+                })
+                .collect::<Vec<_>>();
+            serde_json::to_string_pretty(&results)?
+        }
+        _ => bail!("Unknown export format `{format}`, expected `csv` or `json`"),
+    };
+
+    match args.positional.get(1) {
+        Some(path) => {
+            fs::write(path, out)?;
+            info!("Exported {} results to {path}", results.len());
+        }
+        None => print!("{out}"),
+    }
+
+    Ok(())
+}
+
+impl Args {
+    const OPTIONS: &[&str] = &["config", "level", "user", "reason"];
+    const FLAGS: &[&str] = &["delete", "solutions", "lock"];
+
+    fn parse(args: &[String]) -> Result<Self> {
+        let mut out = Args {
+            positional: Vec::new(),
+            options: HashMap::new(),
+            flags: Vec::new(),
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                out.positional.push(arg.to_owned());
+                continue;
+            };
+
+            if let Some(option) = Self::OPTIONS.iter().find(|x| **x == name) {
+                let value = args
+                    .next()
+                    .with_context(|| format!("Missing value for {arg}"))?;
+                out.options.insert(option, value.to_owned());
+            } else if let Some(flag) = Self::FLAGS.iter().find(|x| **x == name) {
+                out.flags.push(flag);
+            } else {
+                bail!("Unknown option `{arg}`\n\n{USAGE}");
+            }
+        }
+
+        Ok(out)
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(|x| x.as_str())
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.contains(&name)
+    }
+
+    fn level(&self) -> Result<Option<Uuid>> {
+        self.option("level").map(parse_level).transpose()
+    }
+
+    fn user(&self) -> Result<Option<UserId>> {
+        self.option("user").map(parse_user).transpose()
+    }
+
+    fn positional_user(&self) -> Result<UserId> {
+        parse_user(self.positional.first().context(USAGE)?)
+    }
+}
+
+/// Levels can be referred to by id or by their name, ignoring case.
+fn parse_level(level: &str) -> Result<Uuid> {
+    if let Ok(id) = Uuid::from_str(level) {
+        return Ok(id);
+    }
+
+    (DEFAULT_LEVELS.iter())
+        .find(|x| x.name.eq_ignore_ascii_case(level))
+        .map(|x| x.id)
+        .with_context(|| format!("Unknown level `{level}`"))
+}
+
+fn parse_user(user: &str) -> Result<UserId> {
+    UserId::from_str(user).map_err(|err| anyhow::anyhow!("{err} `{user}`"))
+}
+
+fn find_level(id: &Uuid) -> Option<&'static Level> {
+    DEFAULT_LEVELS.iter().find(|x| x.id == *id)
+}
+
+fn level_name(id: &Uuid) -> Cow<'static, str> {
+    match find_level(id) {
+        Some(level) => Cow::Borrowed(&level.name),
+        None => Cow::Owned(id.to_string()),
+    }
+}
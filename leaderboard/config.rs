@@ -6,6 +6,16 @@ use serde::Deserialize;
 pub struct Config {
     pub server: ServerConfig,
     pub simulation: SimulationConfig,
+    pub rate_limit: RateLimitConfig,
+    pub histograms: HistogramConfig,
+    #[serde(default)]
+    pub challenges: Vec<ChallengeConfig>,
+    #[serde(default)]
+    pub accounts: AccountConfig,
+    /// Checks that keys for Steam users are registered by that user. Without
+    /// it, the first key registered for a user is trusted.
+    #[serde(default)]
+    pub steam: Option<SteamConfig>,
 }
 
 #[derive(Deserialize)]
@@ -15,9 +25,79 @@ pub struct ServerConfig {
     pub threads: usize,
 
     pub database_path: PathBuf,
+    /// Key used to verify uploads, for self-hosted servers. Defaults to the
+    /// key the server was built with.
+    #[serde(default)]
+    pub hmac_key: Option<String>,
+    /// Rejects requests that are only authenticated with the HMAC key, once
+    /// older clients that can't sign requests are no longer in use.
+    #[serde(default)]
+    pub require_signatures: bool,
+    /// Lets anyone download the top solutions from the web frontend, without
+    /// having to solve the level first.
+    #[serde(default)]
+    pub public_solutions: bool,
+    /// Token scrapers have to send as a bearer token to read `/metrics`. The
+    /// metrics are public if this isn't set.
+    #[serde(default)]
+    pub metrics_token: Option<String>,
 }
 
 #[derive(Deserialize)]
 pub struct SimulationConfig {
     pub max_ticks: u32,
+    /// Uploaded boards with more tiles than this are rejected without being
+    /// simulated.
+    pub max_tiles: usize,
+    /// Wall-clock time a simulation can run for before being given up on.
+    pub timeout_ms: u64,
+}
+
+#[derive(Deserialize)]
+pub struct RateLimitConfig {
+    /// Requests each IP address can make per minute.
+    pub ip_requests: u32,
+    /// Solutions each user can upload per minute.
+    pub user_uploads: u32,
+}
+
+#[derive(Deserialize)]
+pub struct HistogramConfig {
+    /// Time to wait for more uploads to a level before updating its
+    /// histograms.
+    pub debounce_ms: u64,
+    /// Longest an update can be put off while uploads keep coming in.
+    pub max_delay_ms: u64,
+
+    /// Number of bins in each histogram.
+    pub bins: usize,
+    /// Spaces bins evenly on a log scale, which works better for levels where
+    /// results cover a wide range of values.
+    pub log_scale: bool,
+}
+
+#[derive(Default, Deserialize)]
+pub struct AccountConfig {
+    /// Display names containing any of these words, ignoring case, are
+    /// rejected.
+    #[serde(default)]
+    pub blocked_words: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct SteamConfig {
+    /// Publisher Web API key, used to check the auth tickets sent when
+    /// registering keys.
+    pub web_api_key: String,
+    pub app_id: u32,
+}
+
+#[derive(Deserialize)]
+pub struct ChallengeConfig {
+    /// Path to the level's RON file.
+    pub level: PathBuf,
+    /// Unix timestamps of when the challenge opens and closes. Challenges
+    /// shouldn't overlap, as only one is shown at a time.
+    pub start: u64,
+    pub end: u64,
 }
@@ -0,0 +1,324 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use rand::Rng;
+use rusqlite::{Connection, OptionalExtension, Row, params};
+
+use common::user::UserId;
+
+use super::Database;
+
+/// Link codes are typed in by hand, so they leave out characters that are
+/// easy to mix up.
+const LINK_CODE_CHARS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const LINK_CODE_LENGTH: usize = 8;
+/// How long a link code works for after it's created, in seconds.
+const LINK_CODE_LIFETIME: u64 = 10 * 60;
+
+const SELECT_ACCOUNT: &str = "SELECT id, token, name, name_locked FROM accounts";
+/// Subquery for the id of the account a user is linked to.
+const USER_ACCOUNT_ID: &str =
+    "(SELECT account FROM account_users WHERE user_type = ? AND user = ?)";
+
+pub struct Account {
+    pub id: i64,
+    pub token: String,
+    pub name: Option<String>,
+    /// If moderators have stopped the account from changing its name.
+    pub name_locked: bool,
+}
+
+impl Database {
+    /// The account a user is linked to, if any.
+    pub fn user_account(&self, user: &UserId) -> Result<Option<Account>> {
+        user_account(&self.lock(), user)
+    }
+
+    pub fn token_account(&self, token: &str) -> Result<Option<Account>> {
+        let account = self
+            .lock()
+            .query_row(
+                &format!("{SELECT_ACCOUNT} WHERE token = ?"),
+                [token],
+                parse_account,
+            )
+            .optional()?;
+        Ok(account)
+    }
+
+    /// Gets the account a user is linked to, creating a new one with a random
+    /// token if they aren't linked to one yet.
+    pub fn get_or_create_account(&self, user: &UserId) -> Result<Account> {
+        let mut db = self.lock();
+        let trans = db.transaction()?;
+        if let Some(account) = user_account(&trans, user)? {
+            return Ok(account);
+        }
+
+        let token = hex::encode(rand::random::<[u8; 32]>());
+        trans.execute(
+            "INSERT INTO accounts (token, timestamp) VALUES (?, ?)",
+            params![token, now()],
+        )?;
+        let id = trans.last_insert_rowid();
+        trans.execute(
+            "INSERT INTO account_users VALUES (?, ?, ?, ?)",
+            params![id, user.type_id(), user.inner() as i64, now()],
+        )?;
+        trans.commit()?;
+
+        Ok(Account {
+            id,
+            token,
+            name: None,
+            name_locked: false,
+        })
+    }
+
+    /// Every user linked to an account.
+    pub fn linked_users(&self, account: i64) -> Result<Vec<UserId>> {
+        let db = self.lock();
+        let mut stmt = db.prepare("SELECT user_type, user FROM account_users WHERE account = ?")?;
+        let users = stmt
+            .query_map([account], |row| {
+                let (user_type, user) = (row.get::<_, u8>(0)?, row.get::<_, i64>(1)?);
+                UserId::from_parts(user_type, user as u64).ok_or(
+                    rusqlite::Error::IntegralValueOutOfRange(0, user_type as i64),
+                )
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(users)
+    }
+
+    pub fn set_name(&self, account: i64, name: Option<&str>) -> Result<()> {
+        self.lock().execute(
+            "UPDATE accounts SET name = ? WHERE id = ?",
+            params![name, account],
+        )?;
+        Ok(())
+    }
+
+    /// Removes the name of the account a user is linked to. Returns false if
+    /// the user doesn't have an account.
+    pub fn clear_name(&self, user: &UserId) -> Result<bool> {
+        let changed = self.lock().execute(
+            &format!("UPDATE accounts SET name = NULL WHERE id = {USER_ACCOUNT_ID}"),
+            params![user.type_id(), user.inner() as i64],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Sets if the account a user is linked to can change its name. Returns
+    /// false if the user doesn't have an account.
+    pub fn lock_name(&self, user: &UserId, locked: bool) -> Result<bool> {
+        let changed = self.lock().execute(
+            &format!("UPDATE accounts SET name_locked = ? WHERE id = {USER_ACCOUNT_ID}"),
+            params![locked, user.type_id(), user.inner() as i64],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Creates a code that links another user to the account, returning it
+    /// along with the time it expires at. Expired codes are cleaned up here.
+    pub fn create_link_code(&self, account: i64) -> Result<(String, u64)> {
+        let mut rng = rand::rng();
+        let code = (0..LINK_CODE_LENGTH)
+            .map(|_| LINK_CODE_CHARS[rng.random_range(0..LINK_CODE_CHARS.len())] as char)
+            .collect::<String>();
+        let expires = now() + LINK_CODE_LIFETIME;
+
+        let db = self.lock();
+        db.execute("DELETE FROM link_codes WHERE expires < ?", [now()])?;
+        db.execute(
+            "INSERT INTO link_codes VALUES (?, ?, ?)",
+            params![code, account, expires],
+        )?;
+        Ok((code, expires))
+    }
+
+    /// Links a user to the account that created a link code, moving them out
+    /// of any account they were in. Accounts that are left without any users
+    /// are deleted along with their link codes. Each code only works once,
+    /// returns None if it's invalid or expired.
+    pub fn redeem_link_code(&self, code: &str, user: &UserId) -> Result<Option<Account>> {
+        let mut db = self.lock();
+        let trans = db.transaction()?;
+
+        let account = trans
+            .query_row(
+                "SELECT account FROM link_codes WHERE code = ? AND expires >= ?",
+                params![code.to_ascii_uppercase(), now()],
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()?;
+        let Some(account) = account else {
+            return Ok(None);
+        };
+
+        trans.execute(
+            "DELETE FROM link_codes WHERE code = ?",
+            [code.to_ascii_uppercase()],
+        )?;
+        trans.execute(
+            "INSERT OR REPLACE INTO account_users VALUES (?, ?, ?, ?)",
+            params![account, user.type_id(), user.inner() as i64, now()],
+        )?;
+        trans.execute(
+            "DELETE FROM accounts WHERE id NOT IN (SELECT account FROM account_users)",
+            [],
+        )?;
+        trans.execute(
+            "DELETE FROM link_codes WHERE account NOT IN (SELECT id FROM accounts)",
+            [],
+        )?;
+
+        let account = user_account(&trans, user)?;
+        trans.commit()?;
+        Ok(account)
+    }
+}
+
+fn user_account(db: &Connection, user: &UserId) -> Result<Option<Account>> {
+    let account = db
+        .query_row(
+            &format!("{SELECT_ACCOUNT} WHERE id = {USER_ACCOUNT_ID}"),
+            params![user.type_id(), user.inner() as i64],
+            parse_account,
+        )
+        .optional()?;
+    Ok(account)
+}
+
+fn parse_account(row: &Row<'_>) -> rusqlite::Result<Account> {
+    Ok(Account {
+        id: row.get(0)?,
+        token: row.get(1)?,
+        name: row.get(2)?,
+        name_locked: row.get(3)?,
+    })
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use common::{map::Map, user::UserId};
+    use leaderboard::api::results::Ranking;
+    use rusqlite::Connection;
+    use uuid::Uuid;
+
+    use crate::database::{Database, histograms::HistogramLayout, results::Results};
+
+    const FIRST: UserId = UserId::Steam(1);
+    const SECOND: UserId = UserId::Hardware(2);
+    const THIRD: UserId = UserId::Hardware(3);
+
+    fn database() -> Database {
+        let layout = HistogramLayout {
+            bins: 12,
+            log_scale: false,
+        };
+        let database = Database::new(Connection::open_in_memory().unwrap(), layout);
+        database.migrate(false).unwrap();
+        database
+    }
+
+    fn upload(database: &Database, user: UserId, level: Uuid, cost: u32) {
+        let result = Results {
+            user_id: user,
+            ip_address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            timestamp: 0,
+            level_id: level.into(),
+            solution: Map::default(),
+            cost,
+            latency: 10,
+            tiles: 0,
+            area: 0,
+        };
+        database.insert_result(result).unwrap();
+    }
+
+    fn link(database: &Database, from: &UserId, to: &UserId) {
+        let account = database.get_or_create_account(from).unwrap();
+        let (code, _) = database.create_link_code(account.id).unwrap();
+        database.redeem_link_code(&code, to).unwrap().unwrap();
+    }
+
+    #[test]
+    fn linked_users_rank_as_one_player() {
+        let (database, level) = (database(), Uuid::new_v4());
+        upload(&database, FIRST, level, 20);
+        upload(&database, SECOND, level, 30);
+
+        let (results, total) = database
+            .top_results(level, Ranking::Cost, 10, None)
+            .unwrap();
+        assert_eq!((results.len(), total), (2, 2));
+
+        link(&database, &FIRST, &SECOND);
+        let (results, total) = database
+            .top_results(level, Ranking::Cost, 10, None)
+            .unwrap();
+        assert_eq!((results.len(), total), (1, 1));
+        assert_eq!(results[0].cost, 20);
+    }
+
+    #[test]
+    fn linked_users_share_results() {
+        let (database, level) = (database(), Uuid::new_v4());
+        upload(&database, FIRST, level, 20);
+        upload(&database, SECOND, level, 30);
+        upload(&database, THIRD, level, 25);
+
+        let results = database.user_results(level, &SECOND, None).unwrap();
+        let results = results.unwrap();
+        assert_eq!((results.cost, results.total), (30, 3));
+        assert_eq!(results.by_cost.rank, 3);
+
+        // The second device now ranks with the first device's solution
+        link(&database, &FIRST, &SECOND);
+        for user in [FIRST, SECOND] {
+            let results = database.user_results(level, &user, None).unwrap();
+            let results = results.unwrap();
+            assert_eq!((results.cost, results.total), (20, 2));
+            assert_eq!(results.by_cost.rank, 1);
+            assert_eq!(results.solutions.len(), 1);
+        }
+
+        let results = database.user_results(level, &THIRD, None).unwrap();
+        assert_eq!(results.unwrap().by_cost.rank, 2);
+    }
+
+    #[test]
+    fn linked_users_share_solved_levels() {
+        let (database, level) = (database(), Uuid::new_v4());
+        upload(&database, FIRST, level, 20);
+        assert!(!database.has_solved(level, &SECOND).unwrap());
+
+        link(&database, &FIRST, &SECOND);
+        assert!(database.has_solved(level, &SECOND).unwrap());
+    }
+
+    #[test]
+    fn orphaned_link_codes_are_deleted() {
+        let database = database();
+        let old = database.get_or_create_account(&SECOND).unwrap();
+        let (code, _) = database.create_link_code(old.id).unwrap();
+
+        // Moving the only user out of the old account deletes it, so its code
+        // can't be used to link to an account that doesn't exist
+        link(&database, &FIRST, &SECOND);
+        assert!(database.redeem_link_code(&code, &FIRST).unwrap().is_none());
+        let codes: u32 = (database.lock())
+            .query_row("SELECT COUNT(*) FROM link_codes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(codes, 0);
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    collections::HashSet,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use common::user::UserId;
+use rusqlite::{OptionalExtension, params};
+use uuid::Uuid;
+
+use super::{Database, histograms::update_histograms, types::DbUuid};
+
+/// A row of the results table, as seen by the admin commands.
+pub struct StoredResult {
+    pub id: i64,
+    pub user: UserId,
+    pub ip_address: String,
+    pub timestamp: u64,
+
+    pub level_id: DbUuid,
+    /// The bincode encoded board.
+    pub solution: Vec<u8>,
+    pub cost: u32,
+    pub latency: u32,
+    pub tiles: u32,
+    pub area: u32,
+}
+
+impl Database {
+    /// Lists stored results, filtered to a level and user if they are given.
+    pub fn list_results(
+        &self,
+        level: Option<Uuid>,
+        user: Option<&UserId>,
+    ) -> Result<Vec<StoredResult>> {
+        let db = self.lock();
+        let mut stmt = db.prepare(include_str!("sql/list_results.sql"))?;
+        let results = stmt
+            .query_map(
+                params![
+                    level.map(DbUuid::from),
+                    user.map(|x| x.type_id()),
+                    user.map(|x| x.inner() as i64)
+                ],
+                |row| {
+                    let (user_type, user) = (row.get::<_, u8>(1)?, row.get::<_, i64>(2)?);
+                    Ok(StoredResult {
+                        id: row.get(0)?,
+                        user: UserId::from_parts(user_type, user as u64).ok_or(
+                            rusqlite::Error::IntegralValueOutOfRange(1, user_type as i64),
+                        )?,
+                        ip_address: row.get(3)?,
+                        timestamp: row.get(4)?,
+
+                        level_id: row.get(5)?,
+                        solution: row.get(6)?,
+                        cost: row.get(7)?,
+                        latency: row.get(8)?,
+                        tiles: row.get(9)?,
+                        area: row.get(10)?,
+                    })
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(results)
+    }
+
+    /// Deletes results by their row id and rebuilds the histograms of the
+    /// levels they were for.
+    pub fn delete_results(&self, ids: &[i64]) -> Result<()> {
+        let mut db = self.lock();
+        let trans = db.transaction()?;
+
+        let mut levels = HashSet::new();
+        for id in ids {
+            let level = trans
+                .query_row(
+                    "DELETE FROM results WHERE rowid = ? RETURNING level",
+                    [id],
+                    |row| row.get::<_, DbUuid>(0),
+                )
+                .optional()?;
+            levels.extend(level.map(|x| *x));
+        }
+
+        for level in levels {
+            update_histograms(&trans, level, self.layout)?;
+        }
+
+        trans.commit()?;
+        Ok(())
+    }
+
+    /// Deletes all of a user's results, or only those for one level. Returns
+    /// the number of results deleted.
+    pub fn delete_user_results(&self, user: &UserId, level: Option<Uuid>) -> Result<usize> {
+        let ids = (self.list_results(level, Some(user))?.iter())
+            .map(|x| x.id)
+            .collect::<Vec<_>>();
+        self.delete_results(&ids)?;
+        Ok(ids.len())
+    }
+
+    /// Stops a user from uploading any more results and deletes the ones they
+    /// already have. Returns the number of results deleted.
+    pub fn ban_user(&self, user: &UserId, reason: &str) -> Result<usize> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        self.lock().execute(
+            "INSERT OR REPLACE INTO banned_users VALUES (?, ?, ?, ?)",
+            params![user.type_id(), user.inner() as i64, reason, timestamp],
+        )?;
+        self.delete_user_results(user, None)
+    }
+
+    /// Lifts a ban, returning if the user was banned.
+    pub fn unban_user(&self, user: &UserId) -> Result<bool> {
+        let changed = self.lock().execute(
+            "DELETE FROM banned_users WHERE user_type = ? AND user = ?",
+            params![user.type_id(), user.inner() as i64],
+        )?;
+        Ok(changed > 0)
+    }
+
+    pub fn is_banned(&self, user: &UserId) -> Result<bool> {
+        let banned = self.lock().query_row(
+            "SELECT EXISTS(SELECT 1 FROM banned_users WHERE user_type = ? AND user = ?)",
+            params![user.type_id(), user.inner() as i64],
+            |row| row.get(0),
+        )?;
+        Ok(banned)
+    }
+}
@@ -0,0 +1,80 @@
+-- A database from the first release of the leaderboard, before any of the
+-- later migrations were added. Used to test that old databases still migrate.
+PRAGMA user_version = 1;
+
+CREATE TABLE IF NOT EXISTS results (
+    -- User type differences between hardware ids (0) and steam ids (1) and user
+    -- is some u32 bit value specific to each user
+    user_type INTEGER NOT NULL,
+    user INTEGER NOT NULL,
+
+    -- Information about the request that uploaded the result
+    ip INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+
+    -- The textual representation of the level UUID (i know, its painful)
+    level TEXT NOT NULL,
+    -- The bincode (varint) encoded board (Map<Tile>)
+    solution BLOB NOT NULL,
+    -- The cost and latency of the solution
+    cost INTEGER NOT NULL,
+    latency INTEGER NOT NULL,
+
+    UNIQUE(user_type, user, level)
+);
+
+CREATE TABLE IF NOT EXISTS histograms (
+    -- Level UUID
+    level TEXT NOT NULL UNIQUE,
+
+    -- Cost bins
+    cost_max INTEGER NOT NULL,
+    cost_01 INTEGER NOT NULL,
+    cost_02 INTEGER NOT NULL,
+    cost_03 INTEGER NOT NULL,
+    cost_04 INTEGER NOT NULL,
+    cost_05 INTEGER NOT NULL,
+    cost_06 INTEGER NOT NULL,
+    cost_07 INTEGER NOT NULL,
+    cost_08 INTEGER NOT NULL,
+    cost_09 INTEGER NOT NULL,
+    cost_10 INTEGER NOT NULL,
+    cost_11 INTEGER NOT NULL,
+    cost_12 INTEGER NOT NULL,
+
+    -- Latency bins
+    latency_max INTEGER NOT NULL,
+    latency_01 INTEGER NOT NULL,
+    latency_02 INTEGER NOT NULL,
+    latency_03 INTEGER NOT NULL,
+    latency_04 INTEGER NOT NULL,
+    latency_05 INTEGER NOT NULL,
+    latency_06 INTEGER NOT NULL,
+    latency_07 INTEGER NOT NULL,
+    latency_08 INTEGER NOT NULL,
+    latency_09 INTEGER NOT NULL,
+    latency_10 INTEGER NOT NULL,
+    latency_11 INTEGER NOT NULL,
+    latency_12 INTEGER NOT NULL
+);
+
+-- A solution to the Accumulator level with one mirror and one wall
+INSERT INTO results VALUES (
+    0, 1, 2130706433, 1735689600, 'ab666a1a-4f8a-4bdb-97db-451d279dfe6a',
+    X'130c0001010e02080302010101120803020101100000010108040001010a02000101090a0001010d04080302010102060803020101030a08030201010508080302010104080001010c000803020101000e0803020101070004070c080302010106060001010b0e0001010f00020400',
+    300, 12
+);
+
+-- A result for a level that no longer exists
+INSERT INTO results VALUES (
+    1, 2, 2130706433, 1735689600, '00000000-0000-0000-0000-000000000000',
+    X'00', 100, 5
+);
+
+-- A solution with walls in opposite corners of the board, whose area doesn't
+-- fit in a u32
+INSERT INTO results VALUES (
+    0, 3, 2130706433, 1735689600, 'ab666a1a-4f8a-4bdb-97db-451d279dfe6a',
+    X'130c0001010e04080302010102080803020101040c08030201010600080302010100fcfefffffffcfeffffff070a0001010dfcfffffffffcffffffff070e080302010107080001010c040001010a0000010108060001010b060803020101030208030201010102000101090a0803020101050e0001010f12080302010110',
+    200, 12
+);
@@ -0,0 +1,34 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use rusqlite::params;
+
+use common::user::UserId;
+
+use super::Database;
+
+impl Database {
+    /// Moves a user into a group, or out of their current one if `code` is
+    /// None. Codes should already be normalized with
+    /// [`leaderboard::api::groups::normalize_code`].
+    pub fn set_group(&self, user: &UserId, code: Option<&str>) -> Result<()> {
+        let db = self.lock();
+        let Some(code) = code else {
+            db.execute(
+                "DELETE FROM group_members WHERE user_type = ? AND user = ?",
+                params![user.type_id(), user.inner() as i64],
+            )?;
+            return Ok(());
+        };
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        db.execute(
+            "INSERT OR REPLACE INTO group_members VALUES (?, ?, ?, ?)",
+            params![user.type_id(), user.inner() as i64, code, timestamp],
+        )?;
+        Ok(())
+    }
+}
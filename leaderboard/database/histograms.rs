@@ -1,97 +1,261 @@
 use anyhow::Result;
-use rusqlite::{Row, params};
+use bincode::Options;
+use common::{consts::BINCODE_OPTIONS, user::UserId};
+use rusqlite::{Connection, OptionalExtension, Row, params, types::FromSqlError};
 use uuid::Uuid;
 
-use leaderboard::api::results::Histogram;
+use leaderboard::api::results::{GetResultsResponse, Histogram};
 
-use super::{Database, DbResult, SimplifyDbResult, types::DbUuid};
+use super::{Database, types::DbUuid};
+
+/// The metrics that histograms are stored for, in the same order as the
+/// columns of `sql/user_bests.sql`.
+const METRICS: [&str; 4] = ["cost", "latency", "tiles", "area"];
+
+const SELECT_HISTOGRAMS: &str =
+    "SELECT metric, max, log_scale, bins, samples, updates FROM histograms WHERE level = ?";
+
+/// Once this fraction of a level's users have had their bests changed since
+/// its histograms were built, they are rebuilt instead of being updated in
+/// place, as the bin edges may no longer fit the data.
+const REBUILD_FRACTION: f32 = 0.1;
+
+/// A user's best value of each metric on a level, in the order of
+/// [`METRICS`].
+pub type Bests = [u32; 4];
+
+/// How a user's bests on a level changed after uploading a result. These
+/// let histograms be updated without looking at every other result.
+pub struct BestsChange {
+    pub level: Uuid,
+    pub old: Option<Bests>,
+    pub new: Bests,
+}
+
+/// How histograms are binned, set from the config.
+#[derive(Clone, Copy)]
+pub struct HistogramLayout {
+    pub bins: usize,
+    pub log_scale: bool,
+}
+
+/// A row of the histograms table.
+struct StoredHistogram {
+    metric: String,
+    histogram: Histogram,
+    /// Number of users when the histogram was last rebuilt.
+    samples: u32,
+    /// Number of changes applied in place since the last rebuild.
+    updates: u32,
+}
 
 impl Database {
-    /// -> (Cost, Latency)
-    pub fn get_histogram(&self, level: Uuid) -> Result<(Histogram, Histogram)> {
-        fn parse_histogram(row: &Row<'_>, start: usize) -> rusqlite::Result<Histogram> {
-            Ok(Histogram {
-                bins: [
-                    row.get(start + 1)?,
-                    row.get(start + 2)?,
-                    row.get(start + 3)?,
-                    row.get(start + 4)?,
-                    row.get(start + 5)?,
-                    row.get(start + 6)?,
-                    row.get(start + 7)?,
-                    row.get(start + 8)?,
-                    row.get(start + 9)?,
-                    row.get(start + 10)?,
-                    row.get(start + 11)?,
-                    row.get(start + 12)?,
-                ],
-                max: row.get(start)?,
-            })
+    /// Gets a level's histograms, only counting members of `group` if one is
+    /// given. Groups are small, so their histograms are built when requested
+    /// instead of being stored.
+    pub fn get_histogram(&self, level: Uuid, group: Option<&str>) -> Result<GetResultsResponse> {
+        let db = self.lock();
+        if let Some(group) = group {
+            let bests = level_bests(&db, level, Some(group))?;
+            if bests.is_empty() {
+                return Ok(GetResultsResponse::default());
+            }
+
+            let layout = self.layout;
+            let histogram = |i: usize| {
+                let data = bests.iter().map(|x| x[i]).collect::<Vec<_>>();
+                Histogram::new(&data, layout.bins, layout.log_scale)
+            };
+            return Ok(GetResultsResponse {
+                cost: histogram(0),
+                latency: histogram(1),
+                tiles: histogram(2),
+                area: histogram(3),
+            });
         }
 
-        let data = self
-            .lock()
-            .query_row(
-                "SELECT * FROM histograms WHERE level = ?",
-                [level.to_string()],
-                |row| Ok((parse_histogram(row, 1)?, parse_histogram(row, 14)?)),
-            )
-            .simplify()?;
-
-        Ok(match data {
-            DbResult::Ok(data) => data,
-            DbResult::NotFound => (Histogram::default(), Histogram::default()),
-        })
+        let mut stmt = db.prepare(SELECT_HISTOGRAMS)?;
+        let rows = stmt.query_map([level.to_string()], parse_histogram)?;
+
+        // Metrics without any results yet are left empty
+        let mut response = GetResultsResponse::default();
+        for row in rows {
+            let StoredHistogram {
+                metric, histogram, ..
+            } = row?;
+            match metric.as_str() {
+                "cost" => response.cost = histogram,
+                "latency" => response.latency = histogram,
+                "tiles" => response.tiles = histogram,
+                "area" => response.area = histogram,
+                _ => {}
+            }
+        }
+
+        Ok(response)
     }
 
-    pub fn update_histograms(&self, level: Uuid) -> Result<()> {
-        let db = self.lock();
+    pub fn update_all_histograms(&self) -> Result<usize> {
+        update_all_histograms(&self.lock(), self.layout)
+    }
+
+    /// Applies a batch of changes to a level's histograms by moving each
+    /// user's old bests to the bins of their new ones. The histograms are
+    /// fully rebuilt if they don't exist yet or enough users have been added
+    /// since they were last built.
+    pub fn apply_bests_changes(&self, level: Uuid, changes: &[BestsChange]) -> Result<()> {
+        let mut db = self.lock();
+        let trans = db.transaction()?;
+
+        let mut stmt = trans.prepare(SELECT_HISTOGRAMS)?;
+        let mut histograms = stmt
+            .query_map(params![DbUuid::from(level)], parse_histogram)?
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+
+        // Histograms built with a different layout in the config are also
+        // rebuilt, as the changes can't be applied to their bins
+        let layout = self.layout;
+        let stale = histograms.iter().any(|x| {
+            (x.updates as usize + changes.len()) as f32 > x.samples as f32 * REBUILD_FRACTION
+                || x.histogram.bins.len() != layout.bins
+                || x.histogram.log_scale != layout.log_scale
+        });
+
+        if histograms.len() != METRICS.len() || stale {
+            update_histograms(&trans, level, layout)?;
+            trans.commit()?;
+            return Ok(());
+        }
+
+        for stored in histograms.iter_mut() {
+            let i = METRICS.iter().position(|x| *x == stored.metric).unwrap();
+            let histogram = &mut stored.histogram;
+            for change in changes {
+                if let Some(old) = change.old {
+                    let bin = histogram.bin(old[i]);
+                    histogram.bins[bin] = histogram.bins[bin].saturating_sub(1);
+                }
 
-        let mut stmt = db.prepare("SELECT cost, latency FROM results WHERE level = ?")?;
-        let results = stmt.query_map(params![DbUuid::from(level)], |row| {
-            Ok((row.get::<_, u32>(0)?, row.get::<_, u32>(1)?))
-        })?;
+                let bin = histogram.bin(change.new[i]);
+                histogram.bins[bin] += 1;
+            }
 
-        let (cost, latency) = results
-            .filter_map(|x| x.ok())
-            .unzip::<_, _, Vec<_>, Vec<_>>();
+            stored.updates += changes.len() as u32;
+            upsert_histogram(&trans, level, stored)?;
+        }
+
+        trans.commit()?;
+        Ok(())
+    }
+}
+
+fn parse_histogram(row: &Row<'_>) -> rusqlite::Result<StoredHistogram> {
+    let bins = row.get::<_, Vec<u8>>(3)?;
+    let bins = BINCODE_OPTIONS
+        .deserialize(&bins)
+        .map_err(|err| FromSqlError::Other(err))?;
 
-        let (cost, latency) = (Histogram::new(&cost), Histogram::new(&latency));
+    Ok(StoredHistogram {
+        metric: row.get(0)?,
+        histogram: Histogram {
+            bins,
+            max: row.get(1)?,
+            log_scale: row.get(2)?,
+        },
+        samples: row.get(4)?,
+        updates: row.get(5)?,
+    })
+}
+
+/// A user's best value of each metric on a level, or None if they haven't
+/// solved it.
+pub(super) fn user_bests(db: &Connection, level: Uuid, user: &UserId) -> Result<Option<Bests>> {
+    let bests = db
+        .query_row(
+            "SELECT MIN(cost), MIN(latency), MIN(tiles), MIN(area) FROM results \
+             WHERE level = ? AND user_type = ? AND user = ? HAVING COUNT(*) > 0",
+            params![DbUuid::from(level), user.type_id(), user.inner() as i64],
+            |row| Ok([row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?]),
+        )
+        .optional()?;
+    Ok(bests)
+}
+
+/// Rebuilds the histograms of every level with results, returning how many
+/// levels there were.
+pub(super) fn update_all_histograms(db: &Connection, layout: HistogramLayout) -> Result<usize> {
+    let mut stmt = db.prepare("SELECT DISTINCT level FROM results")?;
+    let levels = stmt
+        .query_map([], |row| row.get::<_, DbUuid>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for level in levels.iter() {
+        update_histograms(db, **level, layout)?;
+    }
 
-        // its fine...
+    Ok(levels.len())
+}
+
+/// Rebuilds the histograms of every metric for a level. Users can have many
+/// solutions, so only their best value of each metric is counted.
+pub(super) fn update_histograms(
+    db: &Connection,
+    level: Uuid,
+    layout: HistogramLayout,
+) -> Result<()> {
+    let bests = level_bests(db, level, None)?;
+
+    // Levels that lost all their results, like when a user is deleted,
+    // shouldn't keep showing old histograms
+    if bests.is_empty() {
         db.execute(
-            include_str!("sql/upsert_histograms.sql"),
-            params![
-                DbUuid::from(level),
-                cost.max,
-                cost.bins[0],
-                cost.bins[1],
-                cost.bins[2],
-                cost.bins[3],
-                cost.bins[4],
-                cost.bins[5],
-                cost.bins[6],
-                cost.bins[7],
-                cost.bins[8],
-                cost.bins[9],
-                cost.bins[10],
-                cost.bins[11],
-                latency.max,
-                latency.bins[0],
-                latency.bins[1],
-                latency.bins[2],
-                latency.bins[3],
-                latency.bins[4],
-                latency.bins[5],
-                latency.bins[6],
-                latency.bins[7],
-                latency.bins[8],
-                latency.bins[9],
-                latency.bins[10],
-                latency.bins[11],
-            ],
+            "DELETE FROM histograms WHERE level = ?",
+            params![DbUuid::from(level)],
         )?;
+        return Ok(());
+    }
 
-        Ok(())
+    for (i, metric) in METRICS.iter().enumerate() {
+        let data = bests.iter().map(|x| x[i]).collect::<Vec<_>>();
+        let stored = StoredHistogram {
+            metric: metric.to_string(),
+            histogram: Histogram::new(&data, layout.bins, layout.log_scale),
+            samples: bests.len() as u32,
+            updates: 0,
+        };
+        upsert_histogram(db, level, &stored)?;
     }
+
+    Ok(())
+}
+
+/// The bests of every user that solved a level, optionally only members of a
+/// group.
+fn level_bests(db: &Connection, level: Uuid, group: Option<&str>) -> Result<Vec<Bests>> {
+    let mut stmt = db.prepare(include_str!("sql/user_bests.sql"))?;
+    let bests = stmt
+        .query_map(params![DbUuid::from(level), group], |row| {
+            Ok([row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?])
+        })?
+        .collect::<Result<Vec<Bests>, _>>()?;
+    Ok(bests)
+}
+
+fn upsert_histogram(db: &Connection, level: Uuid, stored: &StoredHistogram) -> Result<()> {
+    let histogram = &stored.histogram;
+    db.execute(
+        include_str!("sql/upsert_histograms.sql"),
+        params![
+            DbUuid::from(level),
+            stored.metric,
+            histogram.max,
+            histogram.log_scale,
+            BINCODE_OPTIONS.serialize(&histogram.bins)?,
+            stored.samples,
+            stored.updates,
+        ],
+    )?;
+
+    Ok(())
 }
@@ -0,0 +1,127 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use rusqlite::{Connection, params};
+
+use common::user::UserId;
+
+use super::Database;
+
+impl Database {
+    /// The public keys registered to a user, one for each device they play on.
+    pub fn user_keys(&self, user: &UserId) -> Result<Vec<[u8; 32]>> {
+        user_keys(&self.lock(), user)
+    }
+
+    /// Registers a key to a user. Verified keys, whose owner proved they are
+    /// the user, can always be added and replace any unverified keys. An
+    /// unverified key is only accepted if the user doesn't have a different
+    /// one, returning false otherwise. Keys can be removed with `reset_key`.
+    pub fn register_key(
+        &self,
+        user: &UserId,
+        public_key: &[u8; 32],
+        verified: bool,
+    ) -> Result<bool> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let mut db = self.lock();
+        let trans = db.transaction()?;
+        if verified {
+            trans.execute(
+                "DELETE FROM user_keys WHERE user_type = ? AND user = ? AND verified = 0",
+                params![user.type_id(), user.inner() as i64],
+            )?;
+        } else {
+            let keys = user_keys(&trans, user)?;
+            if keys.contains(public_key) {
+                return Ok(true);
+            } else if !keys.is_empty() {
+                return Ok(false);
+            }
+        }
+
+        trans.execute(
+            "INSERT INTO user_keys VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT DO UPDATE SET verified = max(verified, excluded.verified)",
+            params![
+                user.type_id(),
+                user.inner() as i64,
+                public_key,
+                verified,
+                timestamp
+            ],
+        )?;
+        trans.commit()?;
+        Ok(true)
+    }
+
+    /// Removes all of a user's keys, so they can register new ones after
+    /// losing them. Returns if the user had any keys.
+    pub fn reset_key(&self, user: &UserId) -> Result<bool> {
+        let changed = self.lock().execute(
+            "DELETE FROM user_keys WHERE user_type = ? AND user = ?",
+            params![user.type_id(), user.inner() as i64],
+        )?;
+        Ok(changed > 0)
+    }
+}
+
+fn user_keys(db: &Connection, user: &UserId) -> Result<Vec<[u8; 32]>> {
+    let mut query =
+        db.prepare("SELECT public_key FROM user_keys WHERE user_type = ? AND user = ?")?;
+    let keys = query
+        .query_map(params![user.type_id(), user.inner() as i64], |row| {
+            row.get(0)
+        })?
+        .collect::<Result<_, _>>()?;
+    Ok(keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::user::UserId;
+    use rusqlite::Connection;
+
+    use crate::database::{Database, histograms::HistogramLayout};
+
+    const USER: UserId = UserId::Steam(1);
+
+    fn database() -> Database {
+        let layout = HistogramLayout {
+            bins: 12,
+            log_scale: false,
+        };
+        let database = Database::new(Connection::open_in_memory().unwrap(), layout);
+        database.migrate(false).unwrap();
+        database
+    }
+
+    #[test]
+    fn unverified_keys_conflict() {
+        let database = database();
+        assert!(database.register_key(&USER, &[1; 32], false).unwrap());
+        assert!(database.register_key(&USER, &[1; 32], false).unwrap());
+        assert!(!database.register_key(&USER, &[2; 32], false).unwrap());
+        assert_eq!(database.user_keys(&USER).unwrap(), [[1; 32]]);
+    }
+
+    #[test]
+    fn verified_keys_replace_unverified() {
+        let database = database();
+        database.register_key(&USER, &[1; 32], false).unwrap();
+        assert!(database.register_key(&USER, &[2; 32], true).unwrap());
+        assert!(database.register_key(&USER, &[3; 32], true).unwrap());
+        assert_eq!(database.user_keys(&USER).unwrap(), [[2; 32], [3; 32]]);
+
+        // Nobody can add a key without proving who they are once the user has
+        // one
+        assert!(!database.register_key(&USER, &[1; 32], false).unwrap());
+        assert!(database.register_key(&USER, &[2; 32], false).unwrap());
+        assert!(database.reset_key(&USER).unwrap());
+        assert!(database.user_keys(&USER).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,220 @@
+use anyhow::{Context, Result, bail};
+use beam_logic::{
+    level::{Level, default::DEFAULT_LEVELS},
+    misc::{area, price},
+    tile::Tile,
+};
+use bincode::Options;
+use common::{consts::BINCODE_OPTIONS, map::Map};
+use log::{info, warn};
+use rusqlite::{Transaction, params};
+
+use super::{Database, histograms::update_all_histograms, types::DbUuid};
+
+/// Schema migrations in the order they are applied. The database's
+/// `user_version` is the number of migrations that have been applied to it, so
+/// existing migrations must never be edited or reordered, only appended to.
+const MIGRATIONS: &[(&str, Migration)] = &[
+    (
+        "initial",
+        Migration::Sql(include_str!("sql/migrations/001_initial.sql")),
+    ),
+    (
+        "pareto_results",
+        Migration::Sql(include_str!("sql/migrations/002_pareto_results.sql")),
+    ),
+    ("count_tiles", Migration::Code(count_tiles)),
+    ("solution_area", Migration::Code(solution_area)),
+    (
+        "histogram_metrics",
+        Migration::Sql(include_str!("sql/migrations/005_histogram_metrics.sql")),
+    ),
+    (
+        "ip_addresses",
+        Migration::Sql(include_str!("sql/migrations/006_ip_addresses.sql")),
+    ),
+    (
+        "banned_users",
+        Migration::Sql(include_str!("sql/migrations/007_banned_users.sql")),
+    ),
+    (
+        "histogram_bins",
+        Migration::Sql(include_str!("sql/migrations/008_histogram_bins.sql")),
+    ),
+    (
+        "user_keys",
+        Migration::Sql(include_str!("sql/migrations/009_user_keys.sql")),
+    ),
+    (
+        "groups",
+        Migration::Sql(include_str!("sql/migrations/010_groups.sql")),
+    ),
+    (
+        "accounts",
+        Migration::Sql(include_str!("sql/migrations/011_accounts.sql")),
+    ),
+    (
+        "multiple_user_keys",
+        Migration::Sql(include_str!("sql/migrations/012_multiple_user_keys.sql")),
+    ),
+];
+
+enum Migration {
+    Sql(&'static str),
+    /// For changes that can't be done in SQL alone, like ones that need to
+    /// decode solutions.
+    Code(fn(&Transaction) -> Result<()>),
+}
+
+impl Database {
+    /// Brings the database up to the latest schema version by applying each
+    /// pending migration in order, all within a single transaction. With
+    /// `dry_run` the migrations are still run to check that they work, but the
+    /// transaction is rolled back afterwards.
+    pub(super) fn migrate(&self, dry_run: bool) -> Result<()> {
+        let mut this = self.lock();
+        let path = this.path().unwrap_or_default().to_owned();
+
+        let version =
+            this.pragma_query_value(None, "user_version", |row| row.get::<_, usize>(0))?;
+        let latest = MIGRATIONS.len();
+
+        if version > latest {
+            bail!(
+                "Database at `{path}` is at version {version}, but the newest known version is \
+                 {latest}. Is the server out of date?"
+            );
+        } else if version == latest {
+            info!("Loaded database at `{path}`");
+            return Ok(());
+        }
+
+        info!("Migrating database at `{path}` from version {version} to {latest}");
+        let trans = this.transaction()?;
+        for (i, (name, migration)) in MIGRATIONS.iter().enumerate().skip(version) {
+            info!(" \\ Applying migration {} ({name})", i + 1);
+            match migration {
+                Migration::Sql(sql) => trans.execute_batch(sql).map_err(Into::into),
+                Migration::Code(migrate) => migrate(&trans),
+            }
+            .with_context(|| format!("While applying migration {} ({name})", i + 1))?;
+            trans.pragma_update(None, "user_version", i + 1)?;
+        }
+
+        // Histograms are derived from the results, so they are rebuilt with the
+        // latest code in case a migration changed the results or how histograms
+        // are stored
+        update_all_histograms(&trans, self.layout)?;
+
+        if dry_run {
+            trans.rollback()?;
+            info!("Dry run finished, all migrations applied cleanly and were rolled back");
+        } else {
+            trans.commit()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Fills in the tile count of results uploaded before it was stored.
+fn count_tiles(trans: &Transaction) -> Result<()> {
+    fill_column(trans, "tiles", |board, level| price(board, level).1 as u32)
+}
+
+/// Adds the bounding box area of each solution.
+fn solution_area(trans: &Transaction) -> Result<()> {
+    trans.execute(
+        "ALTER TABLE results ADD COLUMN area INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+    // Results that are too spread out were never meant to be accepted, but
+    // they are kept with the largest area rather than failing the migration
+    fill_column(trans, "area", |board, level| {
+        area(board, level).unwrap_or(u32::MAX)
+    })
+}
+
+/// Sets a column of every result to a value computed from its solution.
+fn fill_column(
+    trans: &Transaction,
+    column: &str,
+    value: fn(&Map<Tile>, &Level) -> u32,
+) -> Result<()> {
+    let mut select = trans.prepare("SELECT rowid, level, solution FROM results")?;
+    let mut update = trans.prepare(&format!("UPDATE results SET {column} = ? WHERE rowid = ?"))?;
+
+    let rows = select.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, DbUuid>(1)?,
+            row.get::<_, Vec<u8>>(2)?,
+        ))
+    })?;
+
+    for row in rows {
+        let (id, level_id, solution) = row?;
+        let Some(level) = DEFAULT_LEVELS.iter().find(|x| x.id == *level_id) else {
+            warn!("Result {id} is for unknown level {level_id:?}, leaving {column} at zero");
+            continue;
+        };
+
+        let board = BINCODE_OPTIONS.deserialize::<Map<Tile>>(&solution)?;
+        update.execute(params![value(&board, level), id])?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::Connection;
+
+    use super::MIGRATIONS;
+    use crate::database::{Database, histograms::HistogramLayout};
+
+    const FIXTURE: &str = include_str!("fixtures/version_1.sql");
+    const LAYOUT: HistogramLayout = HistogramLayout {
+        bins: 12,
+        log_scale: false,
+    };
+
+    fn fixture() -> Database {
+        let connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch(FIXTURE).unwrap();
+        Database::new(connection, LAYOUT)
+    }
+
+    fn version(database: &Database) -> usize {
+        (database.lock())
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn migrate_fixture() {
+        let database = fixture();
+        database.migrate(false).unwrap();
+        assert_eq!(version(&database), MIGRATIONS.len());
+
+        let metrics = (database.lock())
+            .prepare("SELECT tiles, area FROM results ORDER BY user")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<Vec<(u32, u32)>, _>>()
+            .unwrap();
+        assert_eq!(metrics, [(2, 2), (0, 0), (2, u32::MAX)]);
+
+        // Migrating an up to date database does nothing
+        database.migrate(false).unwrap();
+        assert_eq!(version(&database), MIGRATIONS.len());
+    }
+
+    #[test]
+    fn dry_run_rolls_back() {
+        let database = fixture();
+        database.migrate(true).unwrap();
+        assert_eq!(version(&database), 1);
+    }
+}
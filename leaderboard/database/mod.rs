@@ -1,27 +1,39 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
 use anyhow::Result;
-use log::{error, info};
 use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
 use rusqlite::Connection;
 
+use histograms::HistogramLayout;
+
+pub mod accounts;
+pub mod admin;
+mod groups;
 pub mod histograms;
+mod keys;
+mod migrations;
 pub mod results;
 mod types;
 
-// Increment every time schema changes, even in dev
-const DATABASE_VERSION: u64 = 1;
 pub struct Database {
     inner: Mutex<Option<Connection>>,
-}
-
-enum DbResult<T> {
-    Ok(T),
-    NotFound,
+    layout: HistogramLayout,
+    /// Number of times the connection was locked and the total nanoseconds
+    /// spent waiting for it, as every query goes through one connection.
+    locks: AtomicU64,
+    lock_wait: AtomicU64,
 }
 
 impl Database {
-    pub fn new(connection: Connection) -> Self {
+    pub fn new(connection: Connection, layout: HistogramLayout) -> Self {
         Self {
             inner: Mutex::new(Some(connection)),
+            layout,
+            locks: AtomicU64::new(0),
+            lock_wait: AtomicU64::new(0),
         }
     }
 
@@ -31,45 +43,41 @@ impl Database {
     }
 
     fn lock(&self) -> MappedMutexGuard<'_, Connection> {
-        MutexGuard::map(self.inner.lock(), |x: &mut Option<Connection>| {
+        let start = Instant::now();
+        let guard = self.inner.lock();
+        let wait = start.elapsed().as_nanos() as u64;
+        self.locks.fetch_add(1, Ordering::Relaxed);
+        self.lock_wait.fetch_add(wait, Ordering::Relaxed);
+
+        MutexGuard::map(guard, |x: &mut Option<Connection>| {
             x.as_mut().expect("No value to take")
         })
     }
+
+    /// How many times the connection has been locked, and the total time
+    /// spent waiting on other queries to get it.
+    pub fn lock_stats(&self) -> (u64, Duration) {
+        let wait = self.lock_wait.load(Ordering::Relaxed);
+        (
+            self.locks.load(Ordering::Relaxed),
+            Duration::from_nanos(wait),
+        )
+    }
 }
 
 impl Database {
-    pub fn init(&self) -> Result<()> {
-        let mut this = self.lock();
-
+    pub fn init(&self, dry_run: bool) -> Result<()> {
+        let this = self.lock();
         this.pragma_update(None, "journal_mode", "WAL")?;
         this.pragma_update(None, "synchronous", "NORMAL")?;
+        drop(this);
 
-        let db_version =
-            this.pragma_query_value(None, "user_version", |row| row.get::<_, u64>(0))?;
-        match db_version {
-            DATABASE_VERSION => info!("Loaded database at `{}`", this.path().unwrap()),
-            0 => {
-                info!("Creating database at `{}`", this.path().unwrap());
-                this.pragma_update(None, "user_version", DATABASE_VERSION)?;
-            }
-            i => {
-                error!(
-                    "Database version mismatch. Expected {DATABASE_VERSION}, got {i}. Please run migrations, or \
-                     just like delete the database and start over.",
-                );
-                std::process::exit(1);
-            }
-        }
-
-        let trans = this.transaction()?;
-        for i in [
-            include_str!("sql/create_results.sql"),
-            include_str!("sql/create_histograms.sql"),
-        ] {
-            trans.execute(i, [])?;
-        }
+        self.migrate(dry_run)
+    }
 
-        trans.commit()?;
+    /// Checks that the database can still be queried.
+    pub fn health_check(&self) -> Result<()> {
+        self.lock().query_row("SELECT 1", [], |_| Ok(()))?;
         Ok(())
     }
 
@@ -82,17 +90,3 @@ impl Database {
         Ok(())
     }
 }
-
-trait SimplifyDbResult<T> {
-    fn simplify(self) -> Result<DbResult<T>, rusqlite::Error>;
-}
-
-impl<T> SimplifyDbResult<T> for Result<T, rusqlite::Error> {
-    fn simplify(self) -> Result<DbResult<T>, rusqlite::Error> {
-        match self {
-            Ok(t) => Ok(DbResult::Ok(t)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(DbResult::NotFound),
-            Err(e) => Err(e),
-        }
-    }
-}
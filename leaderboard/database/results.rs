@@ -1,43 +1,307 @@
-use std::net::Ipv4Addr;
+use std::net::IpAddr;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use beam_logic::tile::Tile;
 use bincode::Options;
 use common::{consts::BINCODE_OPTIONS, map::Map, user::UserId};
-use rusqlite::params;
+use rusqlite::{Connection, params};
+use uuid::Uuid;
 
-use super::{Database, types::DbUuid};
+use leaderboard::api::results::{
+    GetUserResultsResponse, RankedResult, Ranking, Solution, Standing, pareto_frontier, score,
+};
+
+use super::{
+    Database,
+    histograms::{BestsChange, user_bests},
+    types::DbUuid,
+};
 
 #[derive(Debug)]
 pub struct Results {
     pub user_id: UserId,
-    pub ip_address: Ipv4Addr,
+    pub ip_address: IpAddr,
     pub timestamp: u64,
 
     pub level_id: DbUuid,
     pub solution: Map<Tile>,
     pub cost: u32,
     pub latency: u32,
+    pub tiles: u32,
+    pub area: u32,
 }
 
 impl Database {
-    pub fn insert_result(&self, result: Results) -> Result<()> {
+    /// Adds a result to the user's pareto optimal set of solutions for the
+    /// level, removing any of their solutions that it is at least as good as in
+    /// every stat. If one of the user's existing solutions is already at least
+    /// as good as the new result, nothing is changed. If the result was added,
+    /// returns how the user's bests changed so histograms can be updated.
+    pub fn insert_result(&self, result: Results) -> Result<Option<BestsChange>> {
         let solution = BINCODE_OPTIONS.serialize(&result.solution)?;
+        let new = Solution {
+            cost: result.cost,
+            latency: result.latency,
+            tiles: result.tiles,
+            area: result.area,
+        };
+
+        let mut db = self.lock();
+        let trans = db.transaction()?;
+
+        let existing = user_solutions(&trans, *result.level_id, &result.user_id)?;
+        if existing.iter().any(|(_, x)| x.covers(&new)) {
+            return Ok(None);
+        }
 
-        self.lock().execute(
-            include_str!("sql/upsert_results.sql"),
+        let old = user_bests(&trans, *result.level_id, &result.user_id)?;
+
+        for (id, _) in existing.iter().filter(|(_, x)| new.covers(x)) {
+            trans.execute("DELETE FROM results WHERE rowid = ?", [id])?;
+        }
+
+        trans.execute(
+            include_str!("sql/insert_result.sql"),
             params![
                 result.user_id.type_id(),
                 result.user_id.inner() as i64,
-                result.ip_address.to_bits(),
+                result.ip_address.to_string(),
                 result.timestamp,
                 result.level_id,
                 solution,
                 result.cost,
                 result.latency,
+                result.tiles,
+                result.area,
             ],
         )?;
 
-        Ok(())
+        let new = user_bests(&trans, *result.level_id, &result.user_id)?
+            .context("Inserted result is missing")?;
+        trans.commit()?;
+
+        Ok(Some(BestsChange {
+            level: *result.level_id,
+            old,
+            new,
+        }))
+    }
+
+    /// Returns the best solution of the `count` best players on a level, along
+    /// with the total number of players that solved it, where users linked to
+    /// the same account count as one player. Ties are broken by the other
+    /// stat, then by which solution was submitted first. If a group is given,
+    /// only its members are ranked.
+    pub fn top_results(
+        &self,
+        level: Uuid,
+        ranking: Ranking,
+        count: usize,
+        group: Option<&str>,
+    ) -> Result<(Vec<RankedResult>, u32)> {
+        let db = self.lock();
+        let total = db.query_row(
+            include_str!("sql/count_users.sql"),
+            params![DbUuid::from(level), group],
+            |row| row.get::<_, u32>(0),
+        )?;
+
+        let rows = top_results(&db, level, ranking, count, 0, group)?;
+
+        let key = |cost: u32, latency: u32| match ranking {
+            Ranking::Cost => cost as u64,
+            Ranking::Latency => latency as u64,
+            Ranking::Score => score(cost, latency),
+        };
+
+        let mut results = Vec::<RankedResult>::with_capacity(rows.len());
+        let mut last_key = None;
+        for (i, (_id, cost, latency, name)) in rows.into_iter().enumerate() {
+            let tied = last_key == Some(key(cost, latency));
+            let rank = match results.last() {
+                Some(last) if tied => last.rank,
+                _ => i as u32 + 1,
+            };
+            last_key = Some(key(cost, latency));
+            results.push(RankedResult {
+                rank,
+                cost,
+                latency,
+                name,
+            });
+        }
+
+        Ok((results, total))
+    }
+
+    /// Gets a player's solutions to a level and how their best ones rank
+    /// against the best solutions of every other player, or just the members
+    /// of a group, or None if they haven't solved the level. Solutions from
+    /// every user linked to the same account count as the player's.
+    pub fn user_results(
+        &self,
+        level: Uuid,
+        user: &UserId,
+        group: Option<&str>,
+    ) -> Result<Option<GetUserResultsResponse>> {
+        let db = self.lock();
+        let solutions = player_solutions(&db, level, user)?;
+
+        let best = |stat: fn(&Solution) -> u64| solutions.iter().map(stat).min();
+        let (Some(cost), Some(latency), Some(score)) = (
+            best(|x| x.cost as u64),
+            best(|x| x.latency as u64),
+            best(|x| score(x.cost, x.latency)),
+        ) else {
+            return Ok(None);
+        };
+
+        let (total, cost_better, latency_better, score_better) = db.query_row(
+            include_str!("sql/count_better_results.sql"),
+            params![DbUuid::from(level), cost, latency, score as i64, group],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+
+        Ok(Some(GetUserResultsResponse {
+            cost: cost as u32,
+            latency: latency as u32,
+            total,
+
+            by_cost: Standing::new(cost_better, total),
+            by_latency: Standing::new(latency_better, total),
+            by_score: Standing::new(score_better, total),
+
+            solutions: pareto_frontier(solutions),
+        }))
+    }
+
+    /// Gets the solution at `index` in the ranking returned by `top_results`.
+    pub fn top_solution(
+        &self,
+        level: Uuid,
+        ranking: Ranking,
+        index: usize,
+    ) -> Result<Option<Map<Tile>>> {
+        let db = self.lock();
+        let Some(&(id, ..)) = top_results(&db, level, ranking, 1, index, None)?.first() else {
+            return Ok(None);
+        };
+
+        let solution = db.query_row(
+            "SELECT solution FROM results WHERE rowid = ?",
+            [id],
+            |row| row.get::<_, Vec<u8>>(0),
+        )?;
+        Ok(Some(BINCODE_OPTIONS.deserialize(&solution)?))
+    }
+
+    /// Checks if a user, or another user linked to the same account, has a
+    /// verified solution to a level.
+    pub fn has_solved(&self, level: Uuid, user: &UserId) -> Result<bool> {
+        let solved = self.lock().query_row(
+            include_str!("sql/has_solved.sql"),
+            params![DbUuid::from(level), user.type_id(), user.inner() as i64],
+            |row| row.get(0),
+        )?;
+        Ok(solved)
+    }
+
+    /// The pareto frontier of all solutions to a level.
+    pub fn frontier(&self, level: Uuid) -> Result<Vec<Solution>> {
+        let db = self.lock();
+        let mut stmt =
+            db.prepare("SELECT cost, latency, tiles, area FROM results WHERE level = ?")?;
+        let solutions = stmt
+            .query_map(params![DbUuid::from(level)], |row| {
+                Ok(Solution {
+                    cost: row.get(0)?,
+                    latency: row.get(1)?,
+                    tiles: row.get(2)?,
+                    area: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(pareto_frontier(solutions))
+    }
+
+    /// Number of solutions stored across every level.
+    pub fn count_results(&self) -> Result<u64> {
+        let count =
+            (self.lock()).query_row("SELECT COUNT(*) FROM results", [], |row| row.get(0))?;
+        Ok(count)
     }
 }
+
+/// A player's best solution as (row id, cost, latency, account name).
+type TopResult = (i64, u32, u32, Option<String>);
+
+/// Each player's best solution for a ranking.
+fn top_results(
+    db: &Connection,
+    level: Uuid,
+    ranking: Ranking,
+    count: usize,
+    offset: usize,
+    group: Option<&str>,
+) -> Result<Vec<TopResult>> {
+    let order = match ranking {
+        Ranking::Cost => "cost, latency",
+        Ranking::Latency => "latency, cost",
+        Ranking::Score => "cost * latency, cost",
+    };
+
+    let mut stmt = db.prepare(&include_str!("sql/top_results.sql").replace("{order}", order))?;
+    let rows = stmt
+        .query_map(params![DbUuid::from(level), group, count, offset], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(rows)
+}
+
+/// All solutions to a level from a user and every user linked to the same
+/// account.
+fn player_solutions(db: &Connection, level: Uuid, user: &UserId) -> Result<Vec<Solution>> {
+    let mut stmt = db.prepare(include_str!("sql/player_solutions.sql"))?;
+    let solutions = stmt
+        .query_map(
+            params![DbUuid::from(level), user.type_id(), user.inner() as i64],
+            |row| {
+                Ok(Solution {
+                    cost: row.get(0)?,
+                    latency: row.get(1)?,
+                    tiles: row.get(2)?,
+                    area: row.get(3)?,
+                })
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(solutions)
+}
+
+/// All of a user's solutions to a level, along with their row ids.
+fn user_solutions(db: &Connection, level: Uuid, user: &UserId) -> Result<Vec<(i64, Solution)>> {
+    let mut stmt = db.prepare(
+        "SELECT rowid, cost, latency, tiles, area FROM results WHERE level = ? AND user_type = ? \
+         AND user = ?",
+    )?;
+    let solutions = stmt
+        .query_map(
+            params![DbUuid::from(level), user.type_id(), user.inner() as i64],
+            |row| {
+                let solution = Solution {
+                    cost: row.get(1)?,
+                    latency: row.get(2)?,
+                    tiles: row.get(3)?,
+                    area: row.get(4)?,
+                };
+                Ok((row.get(0)?, solution))
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(solutions)
+}
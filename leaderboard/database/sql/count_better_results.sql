@@ -0,0 +1,32 @@
+-- Counts the players with solutions to a level along with how many of them
+-- have a lower best cost, latency, and score than the given values, optionally
+-- only counting members of a group (?5). Users linked to the same account are
+-- one player.
+WITH best AS (
+    SELECT MIN(results.cost) AS cost,
+        MIN(results.latency) AS latency,
+        MIN(results.cost * results.latency) AS score
+    FROM results
+        LEFT JOIN account_users ON account_users.user_type = results.user_type
+        AND account_users.user = results.user
+        LEFT JOIN accounts ON accounts.id = account_users.account
+    WHERE results.level = ?1
+        AND (
+            ?5 IS NULL
+            OR (results.user_type, results.user) IN (
+                SELECT user_type,
+                    user
+                FROM group_members
+                WHERE code = ?5
+            )
+        )
+    GROUP BY COALESCE(
+            'account-' || accounts.id,
+            results.user_type || '-' || results.user
+        )
+)
+SELECT COUNT(*),
+    COALESCE(SUM(cost < ?2), 0),
+    COALESCE(SUM(latency < ?3), 0),
+    COALESCE(SUM(score < ?4), 0)
+FROM best;
@@ -0,0 +1,22 @@
+-- Counts the players with solutions to a level (?1), optionally only members
+-- of a group (?2). Users linked to the same account are one player.
+SELECT COUNT(
+        DISTINCT COALESCE(
+            'account-' || accounts.id,
+            results.user_type || '-' || results.user
+        )
+    )
+FROM results
+    LEFT JOIN account_users ON account_users.user_type = results.user_type
+    AND account_users.user = results.user
+    LEFT JOIN accounts ON accounts.id = account_users.account
+WHERE results.level = ?1
+    AND (
+        ?2 IS NULL
+        OR (results.user_type, results.user) IN (
+            SELECT user_type,
+                user
+            FROM group_members
+            WHERE code = ?2
+        )
+    );
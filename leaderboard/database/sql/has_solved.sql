@@ -0,0 +1,19 @@
+-- Checks if a user (?2, ?3), or any user linked to the same account, has a
+-- solution to a level (?1)
+SELECT EXISTS (
+        SELECT 1
+        FROM results
+        WHERE level = ?1
+            AND (user_type, user) IN (
+                SELECT ?2,
+                    ?3
+                UNION
+                SELECT linked.user_type,
+                    linked.user
+                FROM account_users AS own
+                    JOIN accounts ON accounts.id = own.account
+                    JOIN account_users AS linked ON linked.account = accounts.id
+                WHERE own.user_type = ?2
+                    AND own.user = ?3
+            )
+    );
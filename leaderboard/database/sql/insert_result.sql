@@ -0,0 +1,13 @@
+INSERT INTO results (
+        user_type,
+        user,
+        ip,
+        timestamp,
+        level,
+        solution,
+        cost,
+        latency,
+        tiles,
+        area
+    )
+VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?);
@@ -0,0 +1,19 @@
+-- Every result, optionally only those for one level (?1) or user (?2, ?3)
+SELECT rowid,
+    user_type,
+    user,
+    ip,
+    timestamp,
+    level,
+    solution,
+    cost,
+    latency,
+    tiles,
+    area
+FROM results
+WHERE (?1 IS NULL OR level = ?1)
+    AND (?2 IS NULL OR (user_type = ?2 AND user = ?3))
+ORDER BY level,
+    user_type,
+    user,
+    timestamp;
@@ -0,0 +1,27 @@
+-- Users can now have multiple solutions per level, one for each point on their
+-- cost / latency / tile count pareto frontier. SQLite can't drop constraints,
+-- so the table is rebuilt without UNIQUE(user_type, user, level).
+CREATE TABLE results_new (
+    user_type INTEGER NOT NULL,
+    user INTEGER NOT NULL,
+
+    ip INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+
+    level TEXT NOT NULL,
+    solution BLOB NOT NULL,
+    cost INTEGER NOT NULL,
+    latency INTEGER NOT NULL,
+    -- The number of tiles placed by the user, filled in by the following
+    -- migration for existing results
+    tiles INTEGER NOT NULL DEFAULT 0
+);
+
+INSERT INTO results_new (user_type, user, ip, timestamp, level, solution, cost, latency)
+SELECT user_type, user, ip, timestamp, level, solution, cost, latency
+FROM results;
+
+DROP TABLE results;
+ALTER TABLE results_new RENAME TO results;
+
+CREATE INDEX results_level_user ON results (level, user_type, user);
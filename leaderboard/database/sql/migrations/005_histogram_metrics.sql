@@ -0,0 +1,27 @@
+-- Histograms are now stored as one row per level and metric, so new metrics
+-- don't need their own set of columns. The histograms are recomputed right
+-- after this runs, so nothing is copied over.
+DROP TABLE histograms;
+
+CREATE TABLE histograms (
+    -- Level UUID
+    level TEXT NOT NULL,
+    -- One of cost, latency, tiles, or area
+    metric TEXT NOT NULL,
+
+    max INTEGER NOT NULL,
+    bin_01 INTEGER NOT NULL,
+    bin_02 INTEGER NOT NULL,
+    bin_03 INTEGER NOT NULL,
+    bin_04 INTEGER NOT NULL,
+    bin_05 INTEGER NOT NULL,
+    bin_06 INTEGER NOT NULL,
+    bin_07 INTEGER NOT NULL,
+    bin_08 INTEGER NOT NULL,
+    bin_09 INTEGER NOT NULL,
+    bin_10 INTEGER NOT NULL,
+    bin_11 INTEGER NOT NULL,
+    bin_12 INTEGER NOT NULL,
+
+    UNIQUE(level, metric)
+);
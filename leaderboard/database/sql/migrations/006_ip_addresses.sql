@@ -0,0 +1,10 @@
+-- IP addresses are stored as text so that IPv6 addresses fit. Every existing
+-- address is an IPv4 address packed into an integer, with IPv6 clients having
+-- been stored as 0.0.0.0.
+ALTER TABLE results ADD COLUMN ip_address TEXT NOT NULL DEFAULT '0.0.0.0';
+
+UPDATE results
+SET ip_address = ((ip >> 24) & 255) || '.' || ((ip >> 16) & 255) || '.' || ((ip >> 8) & 255) || '.' || (ip & 255);
+
+ALTER TABLE results DROP COLUMN ip;
+ALTER TABLE results RENAME COLUMN ip_address TO ip;
@@ -0,0 +1,10 @@
+-- Users that can no longer upload results, managed with the admin commands
+CREATE TABLE banned_users (
+    user_type INTEGER NOT NULL,
+    user INTEGER NOT NULL,
+
+    reason TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+
+    UNIQUE(user_type, user)
+);
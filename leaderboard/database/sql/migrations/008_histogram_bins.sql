@@ -0,0 +1,26 @@
+-- Histograms can have any number of bins, so they are stored as an encoded
+-- array instead of a column per bin. They are rebuilt once all the migrations
+-- have run, so nothing is copied over.
+DROP TABLE histograms;
+
+CREATE TABLE histograms (
+    -- Level UUID
+    level TEXT NOT NULL,
+    -- One of cost, latency, tiles, or area
+    metric TEXT NOT NULL,
+
+    max INTEGER NOT NULL,
+    -- If the bins are spaced on a log scale
+    log_scale INTEGER NOT NULL,
+    -- The bincode (varint) encoded bin counts (Vec<u32>)
+    bins BLOB NOT NULL,
+
+    -- Histograms are updated in place as results come in, and only rebuilt
+    -- once enough users have changed since the last rebuild that the bin
+    -- edges may no longer fit. Samples is the number of users at the last
+    -- rebuild and updates is the number of changes made in place since then.
+    samples INTEGER NOT NULL,
+    updates INTEGER NOT NULL,
+
+    UNIQUE(level, metric)
+);
@@ -0,0 +1,10 @@
+-- Public keys that users sign their requests with, see `api::signing`
+CREATE TABLE user_keys (
+    user_type INTEGER NOT NULL,
+    user INTEGER NOT NULL,
+
+    public_key BLOB NOT NULL,
+    timestamp INTEGER NOT NULL,
+
+    UNIQUE(user_type, user)
+);
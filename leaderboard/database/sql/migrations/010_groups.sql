@@ -0,0 +1,12 @@
+-- The group each user is in, see `api::groups`
+CREATE TABLE group_members (
+    user_type INTEGER NOT NULL,
+    user INTEGER NOT NULL,
+
+    code TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+
+    UNIQUE(user_type, user)
+);
+
+CREATE INDEX group_members_code ON group_members (code);
@@ -0,0 +1,26 @@
+-- Identities that users can be linked to, see `api::accounts`
+CREATE TABLE accounts (
+    id INTEGER PRIMARY KEY,
+    token TEXT NOT NULL UNIQUE,
+
+    name TEXT,
+    -- Set by moderators to stop the account from choosing a new name
+    name_locked INTEGER NOT NULL DEFAULT 0,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE account_users (
+    account INTEGER NOT NULL,
+    user_type INTEGER NOT NULL,
+    user INTEGER NOT NULL,
+
+    timestamp INTEGER NOT NULL,
+
+    UNIQUE(user_type, user)
+);
+
+CREATE TABLE link_codes (
+    code TEXT NOT NULL UNIQUE,
+    account INTEGER NOT NULL,
+    expires INTEGER NOT NULL
+);
@@ -0,0 +1,20 @@
+-- Users can register a key on each device they play on. Keys registered with
+-- a Steam auth ticket are verified, and replace any unverified keys that
+-- someone else could have registered first.
+ALTER TABLE user_keys RENAME TO old_user_keys;
+
+CREATE TABLE user_keys (
+    user_type INTEGER NOT NULL,
+    user INTEGER NOT NULL,
+
+    public_key BLOB NOT NULL,
+    verified INTEGER NOT NULL DEFAULT 0,
+    timestamp INTEGER NOT NULL,
+
+    UNIQUE(user_type, user, public_key)
+);
+
+INSERT INTO user_keys (user_type, user, public_key, timestamp)
+SELECT user_type, user, public_key, timestamp FROM old_user_keys;
+
+DROP TABLE old_user_keys;
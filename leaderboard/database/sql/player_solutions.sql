@@ -0,0 +1,24 @@
+-- All solutions to a level (?1) by the player a user (?2, ?3) belongs to.
+-- Users linked to the same account are one player, grouped the same way as in
+-- top_results.
+SELECT results.cost,
+    results.latency,
+    results.tiles,
+    results.area
+FROM results
+    LEFT JOIN account_users ON account_users.user_type = results.user_type
+    AND account_users.user = results.user
+    LEFT JOIN accounts ON accounts.id = account_users.account
+WHERE results.level = ?1
+    AND COALESCE(
+        'account-' || accounts.id,
+        results.user_type || '-' || results.user
+    ) = (
+        SELECT COALESCE('account-' || accounts.id, ?2 || '-' || ?3)
+        FROM (
+                SELECT 1
+            )
+            LEFT JOIN account_users ON account_users.user_type = ?2
+            AND account_users.user = ?3
+            LEFT JOIN accounts ON accounts.id = account_users.account
+    );
@@ -0,0 +1,40 @@
+-- Each player's best solution to a level (?1) for the ordering that replaces
+-- {order}, optionally only for members of a group (?2), along with the name
+-- of their account. Users linked to the same account are one player.
+SELECT best.id,
+    best.cost,
+    best.latency,
+    best.name
+FROM (
+        SELECT results.rowid AS id,
+            results.cost,
+            results.latency,
+            results.timestamp,
+            accounts.name,
+            ROW_NUMBER() OVER (
+                PARTITION BY COALESCE(
+                    'account-' || accounts.id,
+                    results.user_type || '-' || results.user
+                )
+                ORDER BY {order},
+                    results.timestamp
+            ) AS n
+        FROM results
+            LEFT JOIN account_users ON account_users.user_type = results.user_type
+            AND account_users.user = results.user
+            LEFT JOIN accounts ON accounts.id = account_users.account
+        WHERE results.level = ?1
+            AND (
+                ?2 IS NULL
+                OR (results.user_type, results.user) IN (
+                    SELECT user_type,
+                        user
+                    FROM group_members
+                    WHERE code = ?2
+                )
+            )
+    ) AS best
+WHERE best.n = 1
+ORDER BY {order},
+    best.timestamp
+LIMIT ?3 OFFSET ?4;
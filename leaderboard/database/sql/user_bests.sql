@@ -0,0 +1,19 @@
+-- The best of each stat across each user's solutions to a level (?1),
+-- optionally only for members of a group (?2)
+SELECT MIN(cost),
+    MIN(latency),
+    MIN(tiles),
+    MIN(area)
+FROM results
+WHERE level = ?1
+    AND (
+        ?2 IS NULL
+        OR (user_type, user) IN (
+            SELECT user_type,
+                user
+            FROM group_members
+            WHERE code = ?2
+        )
+    )
+GROUP BY user_type,
+    user;
@@ -0,0 +1,95 @@
+use std::{
+    collections::HashMap,
+    mem,
+    sync::{
+        Arc,
+        mpsc::{self, RecvTimeoutError, Sender},
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use log::{trace, warn};
+use parking_lot::Mutex;
+use uuid::Uuid;
+
+use crate::{
+    config::HistogramConfig,
+    database::{Database, histograms::BestsChange},
+};
+
+/// Updates histograms on a background thread so uploads don't have to wait
+/// for them. Changes are batched up per level and applied once no more have
+/// come in for a while.
+pub struct HistogramWorker {
+    tx: Sender<Message>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+enum Message {
+    Update(BestsChange),
+    Stop,
+}
+
+impl HistogramWorker {
+    pub fn spawn(db: Arc<Database>, config: &HistogramConfig) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let debounce = Duration::from_millis(config.debounce_ms);
+        let max_delay = Duration::from_millis(config.max_delay_ms);
+
+        let handle = thread::spawn(move || {
+            let mut pending = HashMap::<Uuid, Vec<BestsChange>>::new();
+            let mut oldest = Instant::now();
+
+            loop {
+                // Wait forever when there is nothing to do, otherwise wait
+                // for the rest of the debounce time, without letting the
+                // oldest change be delayed too long
+                let message = if pending.is_empty() {
+                    rx.recv().map_err(|_| RecvTimeoutError::Disconnected)
+                } else {
+                    let left = max_delay.saturating_sub(oldest.elapsed());
+                    rx.recv_timeout(debounce.min(left))
+                };
+
+                match message {
+                    Ok(Message::Update(change)) => {
+                        pending.is_empty().then(|| oldest = Instant::now());
+                        pending.entry(change.level).or_default().push(change);
+                    }
+                    Err(RecvTimeoutError::Timeout) => flush(&db, &mut pending),
+                    Ok(Message::Stop) | Err(RecvTimeoutError::Disconnected) => {
+                        flush(&db, &mut pending);
+                        break;
+                    }
+                }
+            }
+        });
+
+        Self {
+            tx,
+            handle: Mutex::new(Some(handle)),
+        }
+    }
+
+    pub fn update(&self, change: BestsChange) {
+        let _ = self.tx.send(Message::Update(change));
+    }
+
+    /// Applies any pending changes and waits for the worker to exit.
+    pub fn stop(&self) {
+        let _ = self.tx.send(Message::Stop);
+        if let Some(handle) = self.handle.lock().take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn flush(db: &Database, pending: &mut HashMap<Uuid, Vec<BestsChange>>) {
+    for (level, changes) in mem::take(pending) {
+        trace!("Applying {} histogram changes to {level}", changes.len());
+        if let Err(err) = db.apply_bests_changes(level, &changes) {
+            warn!("Failed to update histograms for {level}: {err}");
+        }
+    }
+}
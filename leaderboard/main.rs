@@ -1,4 +1,4 @@
-use std::process;
+use std::{env::args, process};
 
 use afire::{Middleware, Server, trace, trace::Level};
 use anyhow::Result;
@@ -10,13 +10,21 @@ use log::{LevelFilter, info, warn};
 
 use middleware::{
     logger::{AfireLogger, RequestLogger},
+    rate_limit::RateLimit,
     version::Version,
 };
 mod app;
+mod challenge;
+mod cli;
 mod config;
 mod database;
+mod histogram_worker;
+mod metrics;
 mod middleware;
 mod routes;
+mod steam;
+#[cfg(test)]
+mod tests;
 
 fn main() -> Result<()> {
     trace::set_log_level(Level::Trace);
@@ -27,20 +35,28 @@ fn main() -> Result<()> {
         .write_style(WriteStyle::Always)
         .init();
 
-    API_TESTING.then(|| warn!("Using test API key!"));
+    let args = args().skip(1).collect::<Vec<_>>();
+    if let Some(result) = cli::run(&args) {
+        return result;
+    }
 
-    let app = App::new()?;
-    let mut server = Server::<App>::new(&app.config.server.host, app.config.server.port)
-        .workers(app.config.server.threads)
-        .state(app);
-
-    Version.attach(&mut server);
-    RequestLogger.attach(&mut server);
-    routes::attach(&mut server);
+    // Checks that pending database migrations apply cleanly without saving
+    // any of the changes
+    let dry_run = args.iter().any(|x| x == "--dry-run");
+    let config_path = (args.iter())
+        .find(|x| !x.starts_with("--"))
+        .map_or("config.toml", |x| x.as_str());
+    let app = App::new(config_path, dry_run)?;
+    (API_TESTING && app.config.server.hmac_key.is_none()).then(|| warn!("Using test API key!"));
+    if dry_run {
+        return Ok(());
+    }
 
+    let server = server(app);
     let app = server.app();
     ctrlc::set_handler(move || {
         info!("Exiting");
+        app.histograms.stop();
         app.db.cleanup().unwrap();
         process::exit(0);
     })
@@ -49,3 +65,18 @@ fn main() -> Result<()> {
     server.run()?;
     Ok(())
 }
+
+/// Creates the server with all of its middleware and routes attached.
+fn server(app: App) -> Server<App> {
+    let rate_limit = RateLimit::new(&app.config.rate_limit);
+    let logger = RequestLogger::new(app.metrics.clone());
+    let mut server = Server::<App>::new(&app.config.server.host, app.config.server.port)
+        .workers(app.config.server.threads)
+        .state(app);
+
+    Version.attach(&mut server);
+    logger.attach(&mut server);
+    rate_limit.attach(&mut server);
+    routes::attach(&mut server);
+    server
+}
@@ -0,0 +1,192 @@
+//! Request, simulation, and database stats, served in the Prometheus text
+//! format at `/metrics`.
+
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use anyhow::Result;
+use common::user::UserId;
+use parking_lot::Mutex;
+use uuid::Uuid;
+
+use crate::database::Database;
+
+/// Upper bounds of the latency histogram buckets, in seconds.
+const BUCKETS: [f64; 10] = [0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 1.0, 5.0];
+
+/// Requests to paths past this many distinct routes are counted together, so
+/// scanners hitting random paths can't grow the metrics forever.
+const MAX_ROUTES: usize = 128;
+
+#[derive(Default)]
+pub struct Metrics {
+    /// Stats for each method and route.
+    requests: Mutex<HashMap<(String, String), Timings>>,
+    simulations: Mutex<Timings>,
+    simulation_ticks: AtomicU64,
+}
+
+#[derive(Default)]
+struct Timings {
+    count: u64,
+    seconds: f64,
+    /// Cumulative count of each bucket in [`BUCKETS`].
+    buckets: [u64; BUCKETS.len()],
+}
+
+impl Metrics {
+    pub fn record_request(&self, method: &str, path: &str, elapsed: Duration) {
+        let mut requests = self.requests.lock();
+        let mut key = (method.to_owned(), route(path));
+        if requests.len() >= MAX_ROUTES && !requests.contains_key(&key) {
+            key.1 = "other".to_owned();
+        }
+
+        requests.entry(key).or_default().record(elapsed);
+    }
+
+    /// Records the verification of an uploaded solution.
+    pub fn record_simulation(&self, ticks: u64, elapsed: Duration) {
+        self.simulations.lock().record(elapsed);
+        self.simulation_ticks.fetch_add(ticks, Ordering::Relaxed);
+    }
+
+    pub fn render(&self, db: &Database) -> Result<String> {
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "leaderboard_request_duration_seconds",
+            "histogram",
+            "Time taken to handle requests to each route.",
+        );
+        let requests = self.requests.lock();
+        let mut routes = requests.iter().collect::<Vec<_>>();
+        routes.sort_by(|a, b| a.0.cmp(b.0));
+        for ((method, route), timings) in routes {
+            let labels = format!("method=\"{}\",route=\"{}\"", escape(method), escape(route));
+            timings.render(&mut out, "leaderboard_request_duration_seconds", &labels);
+        }
+        drop(requests);
+
+        header(
+            &mut out,
+            "leaderboard_simulation_duration_seconds",
+            "histogram",
+            "Wall time taken to verify uploaded solutions.",
+        );
+        (self.simulations.lock()).render(&mut out, "leaderboard_simulation_duration_seconds", "");
+
+        header(
+            &mut out,
+            "leaderboard_simulation_ticks_total",
+            "counter",
+            "Ticks simulated while verifying uploaded solutions.",
+        );
+        let ticks = self.simulation_ticks.load(Ordering::Relaxed);
+        let _ = writeln!(out, "leaderboard_simulation_ticks_total {ticks}");
+
+        header(
+            &mut out,
+            "leaderboard_results",
+            "gauge",
+            "Solutions stored in the results table.",
+        );
+        let _ = writeln!(out, "leaderboard_results {}", db.count_results()?);
+
+        let (locks, wait) = db.lock_stats();
+        header(
+            &mut out,
+            "leaderboard_db_locks_total",
+            "counter",
+            "Times the database connection was locked.",
+        );
+        let _ = writeln!(out, "leaderboard_db_locks_total {locks}");
+        header(
+            &mut out,
+            "leaderboard_db_lock_wait_seconds_total",
+            "counter",
+            "Time spent waiting for other queries to release the database.",
+        );
+        let wait = wait.as_secs_f64();
+        let _ = writeln!(out, "leaderboard_db_lock_wait_seconds_total {wait}");
+
+        Ok(out)
+    }
+}
+
+impl Timings {
+    fn record(&mut self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        self.count += 1;
+        self.seconds += seconds;
+
+        for (bucket, le) in self.buckets.iter_mut().zip(BUCKETS) {
+            *bucket += (seconds <= le) as u64;
+        }
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let bucket = |le: &str| match labels {
+            "" => format!("{{le=\"{le}\"}}"),
+            labels => format!("{{{labels},le=\"{le}\"}}"),
+        };
+        for (count, le) in self.buckets.iter().zip(BUCKETS) {
+            let _ = writeln!(out, "{name}_bucket{} {count}", bucket(&le.to_string()));
+        }
+
+        let (count, sum) = (self.count, self.seconds);
+        let labels = match labels {
+            "" => String::new(),
+            labels => format!("{{{labels}}}"),
+        };
+        let _ = writeln!(out, "{name}_bucket{} {count}", bucket("+Inf"));
+        let _ = writeln!(out, "{name}_sum{labels} {sum}");
+        let _ = writeln!(out, "{name}_count{labels} {count}");
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} {kind}");
+}
+
+/// Escapes a label value, as request paths can contain anything.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Replaces the parameters in a request path with their names, so requests
+/// to the same route are counted together.
+fn route(path: &str) -> String {
+    let segments = path.split('/').map(|segment| {
+        if segment.parse::<Uuid>().is_ok() {
+            "{level}"
+        } else if segment.parse::<UserId>().is_ok() {
+            "{user}"
+        } else if !segment.is_empty() && segment.bytes().all(|x| x.is_ascii_digit()) {
+            "{index}"
+        } else {
+            segment
+        }
+    });
+
+    segments.collect::<Vec<_>>().join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::escape;
+
+    #[test]
+    fn labels_are_escaped() {
+        assert_eq!(escape("/api/levels"), "/api/levels");
+        assert_eq!(escape("/\"a\"\\b\nc"), "/\\\"a\\\"\\\\b\\nc");
+    }
+}
@@ -1,12 +1,20 @@
-use std::fmt::Arguments;
+use std::{cell::Cell, fmt::Arguments, sync::Arc, time::Instant};
 
 use afire::{
-    Middleware, Request,
+    Middleware, Request, Response,
     prelude::MiddleResult,
     trace::{Formatter, Level as AfireLevel},
 };
 use log::{Level, RecordBuilder, trace};
 
+use crate::metrics::Metrics;
+
+thread_local! {
+    // Each request is handled start to finish on one worker thread, so its
+    // start time can be kept here between the pre and post hooks
+    static START: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
 pub struct AfireLogger;
 
 impl Formatter for AfireLogger {
@@ -28,11 +36,29 @@ impl Formatter for AfireLogger {
     }
 }
 
-pub struct RequestLogger;
+/// Logs requests and records how long each took in the metrics.
+pub struct RequestLogger {
+    metrics: Arc<Metrics>,
+}
+
+impl RequestLogger {
+    pub fn new(metrics: Arc<Metrics>) -> Self {
+        Self { metrics }
+    }
+}
 
 impl Middleware for RequestLogger {
     fn pre(&self, req: &mut Request) -> MiddleResult {
         trace!("{} {}{}", req.method, req.path, req.query);
+        START.set(Some(Instant::now()));
+        MiddleResult::Continue
+    }
+
+    fn post(&self, req: &Request, _res: &mut Response) -> MiddleResult {
+        if let Some(start) = START.take() {
+            let method = req.method.to_string();
+            (self.metrics).record_request(&method, &req.path, start.elapsed());
+        }
         MiddleResult::Continue
     }
 }
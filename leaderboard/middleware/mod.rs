@@ -1,2 +1,3 @@
 pub mod logger;
+pub mod rate_limit;
 pub mod version;
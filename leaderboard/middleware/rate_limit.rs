@@ -0,0 +1,128 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+use afire::{
+    Content, Middleware, Request, Response, Status, extensions::RealIp, prelude::MiddleResult,
+};
+use leaderboard::api::errors::{ApiError, ErrorCode};
+use log::warn;
+use parking_lot::Mutex;
+use serde_json::json;
+
+use crate::config::RateLimitConfig;
+
+/// Token bucket rate limiter. Each key can make up to `burst` requests at
+/// once, then gets one more every `interval`.
+pub struct RateLimiter<K> {
+    burst: u32,
+    interval: Duration,
+    buckets: Mutex<Buckets<K>>,
+}
+
+struct Buckets<K> {
+    buckets: HashMap<K, Bucket>,
+    last_sweep: Instant,
+}
+
+struct Bucket {
+    tokens: f32,
+    last: Instant,
+}
+
+/// Limits the number of requests each IP address can make to any route.
+pub struct RateLimit {
+    limiter: RateLimiter<IpAddr>,
+}
+
+impl<K: Hash + Eq> RateLimiter<K> {
+    /// Allows `requests` requests every minute, all of which can be made at
+    /// once.
+    pub fn per_minute(requests: u32) -> Self {
+        Self {
+            burst: requests,
+            interval: Duration::from_secs(60) / requests.max(1),
+            buckets: Mutex::new(Buckets {
+                buckets: HashMap::new(),
+                last_sweep: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes a token from the key's bucket. If there are none left, the time
+    /// until the next one will be available is returned instead.
+    pub fn check(&self, key: K) -> Result<(), Duration> {
+        let now = Instant::now();
+        let refill = |bucket: &Bucket| {
+            let new = now.duration_since(bucket.last).as_secs_f32() / self.interval.as_secs_f32();
+            (bucket.tokens + new).min(self.burst as f32)
+        };
+
+        let mut this = self.buckets.lock();
+
+        // Buckets that have refilled completely are the same as new ones, so
+        // they are dropped to keep memory use from growing forever. Any bucket
+        // that wasn't used since the last sweep will be full by now.
+        if now.duration_since(this.last_sweep) > self.interval * self.burst {
+            this.last_sweep = now;
+            this.buckets
+                .retain(|_, bucket| refill(bucket) < self.burst as f32);
+        }
+
+        let bucket = this.buckets.entry(key).or_insert(Bucket {
+            tokens: self.burst as f32,
+            last: now,
+        });
+        bucket.tokens = refill(bucket);
+        bucket.last = now;
+
+        if bucket.tokens < 1.0 {
+            return Err(self.interval.mul_f32(1.0 - bucket.tokens));
+        }
+
+        bucket.tokens -= 1.0;
+        Ok(())
+    }
+}
+
+impl RateLimit {
+    pub fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            limiter: RateLimiter::per_minute(config.ip_requests),
+        }
+    }
+}
+
+impl Middleware for RateLimit {
+    fn pre(&self, req: &mut Request) -> MiddleResult {
+        let ip = req.real_ip();
+        match self.limiter.check(ip) {
+            Ok(()) => MiddleResult::Continue,
+            Err(retry) => {
+                warn!("Rate limited {ip}");
+                MiddleResult::Send(too_many_requests(retry))
+            }
+        }
+    }
+}
+
+/// A 429 response telling the client how many seconds to wait before trying
+/// again.
+pub fn too_many_requests(retry: Duration) -> Response {
+    Response::new()
+        .status(Status::TooManyRequests)
+        .header("Retry-After", retry_after(retry))
+        .text(json!(ApiError::new(
+            ErrorCode::RateLimited,
+            "Too many requests, try again later"
+        )))
+        .content(Content::JSON)
+}
+
+/// Value of the `Retry-After` header, in whole seconds.
+pub fn retry_after(retry: Duration) -> String {
+    retry.as_secs().max(1).to_string()
+}
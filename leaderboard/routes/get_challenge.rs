@@ -0,0 +1,38 @@
+use afire::{Content, Server, extensions::RouteShorthands};
+use serde_json::json;
+
+use leaderboard::api::{challenge::GetChallengeResponse, results::Ranking};
+
+use crate::{app::App, routes::or_reject};
+
+const SCOREBOARD_COUNT: usize = 10;
+
+pub fn attach(server: &mut Server<App>) {
+    // Responds with null when no challenge is running
+    server.get("/api/challenge", |ctx| {
+        let app = ctx.app();
+        let response = match app.challenge() {
+            Some(challenge) => {
+                let (scoreboard, total) = or_reject!(
+                    ctx,
+                    (app.db).top_results(
+                        challenge.level.id,
+                        Ranking::Score,
+                        SCOREBOARD_COUNT,
+                        None,
+                    )
+                );
+                Some(GetChallengeResponse {
+                    level: challenge.source.clone(),
+                    end: challenge.end,
+                    scoreboard,
+                    total,
+                })
+            }
+            None => None,
+        };
+
+        ctx.text(json!(response)).content(Content::JSON).send()?;
+        Ok(())
+    });
+}
@@ -0,0 +1,22 @@
+use afire::{Content, Server, extensions::RouteShorthands};
+use serde_json::json;
+
+use leaderboard::api::results::GetFrontierResponse;
+
+use crate::{
+    app::App,
+    routes::{level_param, or_reject},
+};
+
+pub fn attach(server: &mut Server<App>) {
+    server.get("/api/{level}/frontier", |ctx| {
+        let level_id = or_reject!(ctx, level_param(ctx));
+
+        let app = ctx.app();
+        let frontier = or_reject!(ctx, app.db.frontier(level_id));
+        ctx.text(json!(GetFrontierResponse { frontier }))
+            .content(Content::JSON)
+            .send()?;
+        Ok(())
+    });
+}
@@ -0,0 +1,19 @@
+use afire::{Content, Server, extensions::RouteShorthands};
+use serde_json::json;
+
+use crate::{app::App, routes::reject};
+
+pub fn attach(server: &mut Server<App>) {
+    // For uptime checks, fails if the database can't be queried
+    server.get("/health", |ctx| {
+        let app = ctx.app();
+        if let Err(err) = app.db.health_check() {
+            reject!(ctx, err.context("Database error"));
+        }
+
+        ctx.text(json!({ "status": "ok" }))
+            .content(Content::JSON)
+            .send()?;
+        Ok(())
+    });
+}
@@ -0,0 +1,18 @@
+use afire::{Content, Server, extensions::RouteShorthands};
+use leaderboard::api::info::{API_VERSION, GetInfoResponse};
+use serde_json::json;
+
+use crate::app::App;
+
+pub fn attach(server: &mut Server<App>) {
+    // Lets clients check that they can talk to this server before using it
+    server.get("/api/info", |ctx| {
+        let info = GetInfoResponse {
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            api_version: API_VERSION,
+        };
+
+        ctx.text(json!(info)).content(Content::JSON).send()?;
+        Ok(())
+    });
+}
@@ -0,0 +1,26 @@
+use afire::{Content, Server, extensions::RouteShorthands};
+use serde_json::json;
+
+use leaderboard::api::levels::{GetLevelsResponse, LevelInfo};
+
+use crate::app::App;
+
+pub fn attach(server: &mut Server<App>) {
+    server.get("/api/levels", |ctx| {
+        let app = ctx.app();
+        let levels = (app.levels())
+            .map(|(level, challenge)| LevelInfo {
+                id: level.id,
+                name: level.name.clone(),
+                description: level.description.clone(),
+                size: level.size.map(|x| [x.x, x.y]),
+                challenge,
+            })
+            .collect();
+
+        ctx.text(json!(GetLevelsResponse { levels }))
+            .content(Content::JSON)
+            .send()?;
+        Ok(())
+    });
+}
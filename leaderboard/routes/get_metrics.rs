@@ -0,0 +1,36 @@
+use afire::{Content, HeaderName, Server, extensions::RouteShorthands};
+use leaderboard::api::{errors::ErrorCode, hmac};
+
+use crate::{
+    app::App,
+    routes::{or_reject, reject},
+};
+
+pub fn attach(server: &mut Server<App>) {
+    // Prometheus text format, see `metrics.rs` for what is collected
+    server.get("/metrics", |ctx| {
+        let app = ctx.app();
+        if let Some(token) = &app.config.server.metrics_token {
+            // The token is checked in constant time by comparing HMACs of
+            // both, so its length and contents can't be guessed from timing
+            let bearer = (ctx.req.headers.get(HeaderName::Authorization))
+                .and_then(|x| x.strip_prefix("Bearer "));
+            let expected = hmac::hash(token.as_bytes(), token.as_bytes());
+            if bearer
+                .is_none_or(|x| hmac::verify(token.as_bytes(), x.as_bytes(), &expected).is_err())
+            {
+                reject!(
+                    ctx,
+                    ErrorCode::InvalidAuthorization,
+                    "Invalid metrics token"
+                );
+            }
+        }
+
+        let metrics = or_reject!(ctx, app.metrics.render(&app.db));
+        ctx.text(metrics)
+            .content(Content::Custom("text/plain; version=0.0.4"))
+            .send()?;
+        Ok(())
+    });
+}
@@ -0,0 +1,25 @@
+use afire::{Content, Server, extensions::RouteShorthands};
+use common::user::UserId;
+use leaderboard::api::{accounts::GetProfileResponse, errors::ErrorCode};
+use serde_json::json;
+
+use crate::{app::App, routes::or_reject};
+
+pub fn attach(server: &mut Server<App>) {
+    server.get("/api/users/{user}", |ctx| {
+        let user = or_reject!(
+            ctx,
+            ctx.param_idx(0).parse::<UserId>(),
+            ErrorCode::BadRequest,
+            "Invalid user id"
+        );
+
+        let app = ctx.app();
+        let account = or_reject!(ctx, app.db.user_account(&user));
+        let response = GetProfileResponse {
+            name: account.and_then(|x| x.name),
+        };
+        ctx.text(json!(response)).content(Content::JSON).send()?;
+        Ok(())
+    });
+}
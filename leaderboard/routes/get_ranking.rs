@@ -0,0 +1,45 @@
+use afire::{Content, Server, extensions::RouteShorthands};
+use serde_json::json;
+
+use leaderboard::api::{
+    errors::ErrorCode,
+    results::{GetRankingResponse, Ranking},
+};
+
+use crate::{
+    app::App,
+    routes::{group_query, level_param, or_reject},
+};
+
+const DEFAULT_COUNT: usize = 10;
+const MAX_COUNT: usize = 100;
+
+pub fn attach(server: &mut Server<App>) {
+    server.get("/api/{level}/top/{ranking}", |ctx| {
+        let level_id = or_reject!(ctx, level_param(ctx));
+        let ranking = or_reject!(
+            ctx,
+            ctx.param_idx(1).parse::<Ranking>(),
+            ErrorCode::BadRequest,
+            "Invalid ranking"
+        );
+        let count = match ctx.req.query.get("count") {
+            Some(count) => {
+                let count = count.parse::<usize>();
+                or_reject!(ctx, count, ErrorCode::BadRequest, "Invalid count").min(MAX_COUNT)
+            }
+            None => DEFAULT_COUNT,
+        };
+        let group = or_reject!(ctx, group_query(&ctx.req));
+
+        let app = ctx.app();
+        let (results, total) = or_reject!(
+            ctx,
+            (app.db).top_results(level_id, ranking, count, group.as_deref())
+        );
+        ctx.text(json!(GetRankingResponse { results, total }))
+            .content(Content::JSON)
+            .send()?;
+        Ok(())
+    });
+}
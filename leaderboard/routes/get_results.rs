@@ -1,22 +1,21 @@
 use afire::{Content, Server, extensions::RouteShorthands};
 use serde_json::json;
-use uuid::Uuid;
 
-use leaderboard::api::results::GetResultsResponse;
-
-use crate::app::App;
+use crate::{
+    app::App,
+    routes::{group_query, level_param, or_reject},
+};
 
 pub fn attach(server: &mut Server<App>) {
     server.get("/api/{level}/results", |ctx| {
-        let level_id = ctx.param_idx(0).parse::<Uuid>()?;
+        let level_id = or_reject!(ctx, level_param(ctx));
+        let group = or_reject!(ctx, group_query(&ctx.req));
 
         let app = ctx.app();
 
         // TODO: Check if level exists?
-        let (cost, latency) = app.db.get_histogram(level_id)?;
-        ctx.text(json!(GetResultsResponse { cost, latency }))
-            .content(Content::JSON)
-            .send()?;
+        let histograms = or_reject!(ctx, app.db.get_histogram(level_id, group.as_deref()));
+        ctx.text(json!(histograms)).content(Content::JSON).send()?;
         Ok(())
     });
 }
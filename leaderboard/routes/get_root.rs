@@ -1,17 +1,29 @@
-use afire::{
-    Content, Server,
-    extensions::{RedirectResponse, RouteShorthands},
-};
+use afire::{Content, Server, extensions::RouteShorthands};
 
 use crate::app::App;
 
-const STEAM_PAGE: &str = "https://store.steampowered.com/app/3385920/Beam_Time";
+// The web frontend is small enough to be built into the server, so it's
+// always in sync with the api it uses
+const INDEX: &str = include_str!("../web/index.html");
+const SCRIPT: &str = include_str!("../web/app.js");
+const STYLE: &str = include_str!("../web/style.css");
 
 pub fn attach(server: &mut Server<App>) {
     server.get("/", |ctx| {
-        ctx.text(STEAM_PAGE)
-            .content(Content::TXT)
-            .redirect(STEAM_PAGE)
+        ctx.text(INDEX).content(Content::HTML).send()?;
+        Ok(())
+    });
+
+    server.get("/app.js", |ctx| {
+        ctx.text(SCRIPT)
+            .content(Content::Custom("text/javascript; charset=utf-8"))
+            .send()?;
+        Ok(())
+    });
+
+    server.get("/style.css", |ctx| {
+        ctx.text(STYLE)
+            .content(Content::Custom("text/css; charset=utf-8"))
             .send()?;
         Ok(())
     });
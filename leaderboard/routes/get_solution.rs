@@ -0,0 +1,55 @@
+use afire::{Content, Server, extensions::RouteShorthands};
+use anyhow::Context;
+use bincode::Options;
+use common::consts::BINCODE_OPTIONS;
+use leaderboard::api::{errors::ErrorCode, results::GetSolution};
+
+use crate::{
+    app::App,
+    routes::{level_param, or_reject, reject},
+};
+
+pub fn attach(server: &mut Server<App>) {
+    // Uses POST so the request can be signed like uploads are. Responds with
+    // the bincode encoded board.
+    server.post("/api/{level}/solution", |ctx| {
+        let level_id = or_reject!(ctx, level_param(ctx));
+
+        let app = ctx.app();
+        let body = or_reject!(
+            ctx,
+            BINCODE_OPTIONS.deserialize::<GetSolution>(&ctx.req.body),
+            ErrorCode::BadRequest,
+            "Invalid request body"
+        );
+        or_reject!(ctx, app.authorize(&body.user, &ctx.req));
+
+        // Don't spoil levels for people that haven't solved them yet
+        if !or_reject!(ctx, app.db.has_solved(level_id, &body.user)) {
+            reject!(
+                ctx,
+                ErrorCode::Forbidden,
+                "You must solve this level before viewing other solutions"
+            );
+        }
+
+        let solution = or_reject!(
+            ctx,
+            (app.db).top_solution(level_id, body.ranking, body.index as usize)
+        );
+        let Some(solution) = solution else {
+            reject!(ctx, ErrorCode::NotFound, "No solution at that rank");
+        };
+
+        let solution = or_reject!(
+            ctx,
+            BINCODE_OPTIONS
+                .serialize(&solution)
+                .context("Error serializing solution")
+        );
+        ctx.bytes(solution)
+            .content(Content::Custom("application/octet-stream"))
+            .send()?;
+        Ok(())
+    });
+}
@@ -0,0 +1,57 @@
+use afire::{Content, Server, extensions::RouteShorthands};
+use serde_json::json;
+
+use leaderboard::api::{
+    errors::ErrorCode,
+    results::{GetSolutionTilesResponse, Ranking, SolutionTile},
+};
+
+use crate::{
+    app::App,
+    routes::{level_param, or_reject, reject},
+};
+
+pub fn attach(server: &mut Server<App>) {
+    // Used by the web frontend, which can't prove that its user has solved the
+    // level, so it's only available if the server allows it
+    server.get("/api/{level}/top/{ranking}/{index}", |ctx| {
+        let level_id = or_reject!(ctx, level_param(ctx));
+        let ranking = or_reject!(
+            ctx,
+            ctx.param_idx(1).parse::<Ranking>(),
+            ErrorCode::BadRequest,
+            "Invalid ranking"
+        );
+        let index = or_reject!(
+            ctx,
+            ctx.param_idx(2).parse::<usize>(),
+            ErrorCode::BadRequest,
+            "Invalid index"
+        );
+
+        let app = ctx.app();
+        if !app.config.server.public_solutions {
+            reject!(
+                ctx,
+                ErrorCode::Forbidden,
+                "Solutions aren't public on this server"
+            );
+        }
+
+        let Some(solution) = or_reject!(ctx, app.db.top_solution(level_id, ranking, index)) else {
+            reject!(ctx, ErrorCode::NotFound, "No solution at that rank");
+        };
+        let tiles = (solution.iter())
+            .map(|(pos, tile)| SolutionTile {
+                x: pos.x,
+                y: pos.y,
+                tile,
+            })
+            .collect();
+
+        ctx.text(json!(GetSolutionTilesResponse { tiles }))
+            .content(Content::JSON)
+            .send()?;
+        Ok(())
+    });
+}
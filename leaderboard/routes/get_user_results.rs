@@ -0,0 +1,29 @@
+use afire::{Content, Server, extensions::RouteShorthands};
+use common::user::UserId;
+use serde_json::json;
+
+use leaderboard::api::errors::ErrorCode;
+
+use crate::{
+    app::App,
+    routes::{group_query, level_param, or_reject},
+};
+
+pub fn attach(server: &mut Server<App>) {
+    // Responds with null if the user hasn't submitted a solution to the level
+    server.get("/api/{level}/results/{user}", |ctx| {
+        let level_id = or_reject!(ctx, level_param(ctx));
+        let user = or_reject!(
+            ctx,
+            ctx.param_idx(1).parse::<UserId>(),
+            ErrorCode::BadRequest,
+            "Invalid user id"
+        );
+        let group = or_reject!(ctx, group_query(&ctx.req));
+
+        let app = ctx.app();
+        let results = or_reject!(ctx, app.db.user_results(level_id, &user, group.as_deref()));
+        ctx.text(json!(results)).content(Content::JSON).send()?;
+        Ok(())
+    });
+}
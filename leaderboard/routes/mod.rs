@@ -1,13 +1,133 @@
-use afire::Server;
+use afire::{Context, Request, Server, Status};
+use leaderboard::api::{
+    errors::{ApiError, ErrorCode},
+    groups::normalize_code,
+};
+use uuid::Uuid;
 
 use crate::app::App;
 
+mod get_challenge;
+mod get_frontier;
+mod get_health;
+mod get_info;
+mod get_levels;
+mod get_metrics;
+mod get_profile;
+mod get_ranking;
 mod get_results;
 mod get_root;
+mod get_solution;
+mod get_top_solution;
+mod get_user_results;
+mod post_account;
+mod post_account_link;
+mod post_group;
+mod post_key;
+mod post_link_code;
+mod put_account_name;
 mod put_results;
 
 pub fn attach(server: &mut Server<App>) {
     get_results::attach(server);
+    get_user_results::attach(server);
+    get_ranking::attach(server);
+    get_top_solution::attach(server);
+    get_frontier::attach(server);
+    get_solution::attach(server);
+    get_challenge::attach(server);
     put_results::attach(server);
+    post_key::attach(server);
+    post_group::attach(server);
+    post_account::attach(server);
+    put_account_name::attach(server);
+    post_link_code::attach(server);
+    post_account_link::attach(server);
+    get_profile::attach(server);
+    get_info::attach(server);
+    get_health::attach(server);
+    get_metrics::attach(server);
+    get_levels::attach(server);
     get_root::attach(server);
 }
+
+/// Responds with an [`ApiError`](leaderboard::api::errors::ApiError) as JSON,
+/// using the status for its code, and returns from the route. Takes either an
+/// error or a code and message. Internal errors are also logged in full, as
+/// they point to a problem with the server, while clients only get a generic
+/// message.
+macro_rules! reject {
+    ($ctx:expr, $code:expr, $message:expr) => {
+        $crate::routes::reject!(
+            $ctx,
+            leaderboard::api::errors::ApiError::new($code, $message)
+        )
+    };
+    ($ctx:expr, $err:expr) => {{
+        let source = $err;
+        let detail = format!("{source:#}");
+        let err = leaderboard::api::errors::ApiError::from(source);
+        if err.code == leaderboard::api::errors::ErrorCode::Internal {
+            log::error!("Error handling request to {}: {detail}", $ctx.req.path);
+        }
+        $ctx.status($crate::routes::status(err.code))
+            .text(serde_json::json!(err))
+            .content(afire::Content::JSON)
+            .send()?;
+        return Ok(());
+    }};
+}
+
+/// Unwraps a result, rejecting the request if it failed. Takes either a result
+/// whose error converts into an `ApiError`, or any result along with the code
+/// and message to reject it with.
+macro_rules! or_reject {
+    ($ctx:expr, $result:expr) => {
+        match $result {
+            Ok(x) => x,
+            Err(err) => $crate::routes::reject!($ctx, err),
+        }
+    };
+    ($ctx:expr, $result:expr, $code:expr, $message:expr) => {
+        match $result {
+            Ok(x) => x,
+            Err(_) => $crate::routes::reject!($ctx, $code, $message),
+        }
+    };
+}
+
+pub(crate) use {or_reject, reject};
+
+/// The HTTP status requests rejected with an error code respond with.
+pub fn status(code: ErrorCode) -> Status {
+    match code {
+        ErrorCode::InvalidAuthorization
+        | ErrorCode::InvalidSignature
+        | ErrorCode::KeyRequired
+        | ErrorCode::InvalidTicket => Status::Unauthorized,
+        ErrorCode::Banned | ErrorCode::Forbidden | ErrorCode::NameLocked => Status::Forbidden,
+        ErrorCode::UnknownLevel | ErrorCode::NotFound => Status::NotFound,
+        ErrorCode::KeyConflict => Status::Conflict,
+        ErrorCode::TooManyTiles => Status::PayloadTooLarge,
+        ErrorCode::RateLimited => Status::TooManyRequests,
+        ErrorCode::Internal => Status::InternalServerError,
+        _ => Status::BadRequest,
+    }
+}
+
+/// The level id from the first path parameter, which every route under
+/// `/api/{level}` has.
+pub fn level_param<S>(ctx: &Context<S>) -> Result<Uuid, ApiError> {
+    (ctx.param_idx(0).parse()).map_err(|_| ApiError::new(ErrorCode::BadRequest, "Invalid level id"))
+}
+
+/// The group code from a request's `group` query parameter, for routes that
+/// can be limited to the members of a group. None if there isn't one.
+pub fn group_query(req: &Request) -> Result<Option<String>, ApiError> {
+    (req.query.get("group"))
+        .map(|code| {
+            normalize_code(code)
+                .ok_or_else(|| ApiError::new(ErrorCode::BadRequest, "Invalid group code"))
+        })
+        .transpose()
+}
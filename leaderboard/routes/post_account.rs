@@ -0,0 +1,53 @@
+use afire::{Content, Server, extensions::RouteShorthands};
+use bincode::Options;
+use common::consts::BINCODE_OPTIONS;
+use leaderboard::api::{
+    accounts::{AccountResponse, PostAccount},
+    errors::ErrorCode,
+};
+use serde_json::json;
+
+use crate::{
+    app::App,
+    routes::{or_reject, reject},
+};
+
+pub fn attach(server: &mut Server<App>) {
+    // Responds with the user's account, creating it if needed
+    server.post("/api/accounts", |ctx| {
+        let app = ctx.app();
+        let body = or_reject!(
+            ctx,
+            BINCODE_OPTIONS.deserialize::<PostAccount>(&ctx.req.body),
+            ErrorCode::BadRequest,
+            "Invalid request body"
+        );
+
+        // Anyone with the HMAC key could get the token of a user without one
+        if or_reject!(ctx, app.db.user_keys(&body.user)).is_empty() {
+            reject!(
+                ctx,
+                ErrorCode::KeyRequired,
+                "A key must be registered before using accounts"
+            );
+        }
+
+        or_reject!(ctx, app.authorize(&body.user, &ctx.req));
+
+        if or_reject!(ctx, app.db.is_banned(&body.user)) {
+            reject!(
+                ctx,
+                ErrorCode::Banned,
+                "You have been banned from the leaderboard"
+            );
+        }
+
+        let account = or_reject!(ctx, app.db.get_or_create_account(&body.user));
+        let response = AccountResponse {
+            token: account.token,
+            name: account.name,
+        };
+        ctx.text(json!(response)).content(Content::JSON).send()?;
+        Ok(())
+    });
+}
@@ -0,0 +1,49 @@
+use afire::{Content, Server, extensions::RouteShorthands};
+use bincode::Options;
+use common::consts::BINCODE_OPTIONS;
+use leaderboard::api::{
+    accounts::{AccountResponse, PostLink},
+    errors::ErrorCode,
+};
+use log::info;
+use serde_json::json;
+
+use crate::{
+    app::App,
+    routes::{or_reject, reject},
+};
+
+pub fn attach(server: &mut Server<App>) {
+    server.post("/api/accounts/link", |ctx| {
+        let app = ctx.app();
+        let body = or_reject!(
+            ctx,
+            BINCODE_OPTIONS.deserialize::<PostLink>(&ctx.req.body),
+            ErrorCode::BadRequest,
+            "Invalid request body"
+        );
+
+        // Like creating accounts, linking gives out the account's token
+        if or_reject!(ctx, app.db.user_keys(&body.user)).is_empty() {
+            reject!(
+                ctx,
+                ErrorCode::KeyRequired,
+                "A key must be registered before using accounts"
+            );
+        }
+
+        or_reject!(ctx, app.authorize(&body.user, &ctx.req));
+
+        let Some(account) = or_reject!(ctx, app.db.redeem_link_code(&body.code, &body.user)) else {
+            reject!(ctx, ErrorCode::NotFound, "Invalid or expired link code");
+        };
+        info!("Linked {} to account {}", body.user, account.id);
+
+        let response = AccountResponse {
+            token: account.token,
+            name: account.name,
+        };
+        ctx.text(json!(response)).content(Content::JSON).send()?;
+        Ok(())
+    });
+}
@@ -0,0 +1,37 @@
+use afire::{Server, extensions::RouteShorthands};
+use bincode::Options;
+use common::consts::BINCODE_OPTIONS;
+use leaderboard::api::{
+    errors::ErrorCode,
+    groups::{PostGroup, normalize_code},
+};
+
+use crate::{
+    app::App,
+    routes::{or_reject, reject},
+};
+
+pub fn attach(server: &mut Server<App>) {
+    server.post("/api/groups", |ctx| {
+        let app = ctx.app();
+        let body = or_reject!(
+            ctx,
+            BINCODE_OPTIONS.deserialize::<PostGroup>(&ctx.req.body),
+            ErrorCode::BadRequest,
+            "Invalid request body"
+        );
+        or_reject!(ctx, app.authorize(&body.user, &ctx.req));
+
+        let code = match &body.code {
+            Some(code) => match normalize_code(code) {
+                Some(code) => Some(code),
+                None => reject!(ctx, ErrorCode::BadRequest, "Invalid group code"),
+            },
+            None => None,
+        };
+        or_reject!(ctx, app.db.set_group(&body.user, code.as_deref()));
+
+        ctx.text("Group updated").send()?;
+        Ok(())
+    });
+}
@@ -0,0 +1,78 @@
+use afire::{HeaderName, Server, extensions::RouteShorthands};
+use bincode::Options;
+use common::{consts::BINCODE_OPTIONS, user::UserId};
+use leaderboard::api::{errors::ErrorCode, hmac::verify, signing::PostKey};
+
+use crate::{
+    app::App,
+    routes::{or_reject, reject},
+    steam,
+};
+
+pub fn attach(server: &mut Server<App>) {
+    // Registering a key still needs the HMAC key, as the user has nothing
+    // else to sign the request with yet
+    server.post("/api/keys", |ctx| {
+        let app = ctx.app();
+        let hash = (ctx.req.headers.get(HeaderName::Authorization))
+            .and_then(|x| hex::decode(x.as_bytes()).ok());
+        if hash.is_none_or(|hash| verify(app.hmac_key(), &ctx.req.body, &hash).is_err()) {
+            reject!(
+                ctx,
+                ErrorCode::InvalidAuthorization,
+                "Invalid authorization"
+            );
+        }
+        let body = or_reject!(
+            ctx,
+            BINCODE_OPTIONS.deserialize::<PostKey>(&ctx.req.body),
+            ErrorCode::BadRequest,
+            "Invalid request body"
+        );
+
+        if or_reject!(ctx, app.db.is_banned(&body.user)) {
+            reject!(
+                ctx,
+                ErrorCode::Banned,
+                "You have been banned from uploading solutions"
+            );
+        }
+
+        // With a Steam auth ticket, nobody else can register keys for a user
+        let mut verified = false;
+        if let (Some(config), UserId::Steam(id)) = (&app.config.steam, body.user) {
+            let Some(ticket) = ctx.req.headers.get("Steam-Ticket") else {
+                reject!(
+                    ctx,
+                    ErrorCode::InvalidTicket,
+                    "A Steam auth ticket is needed to register a key"
+                );
+            };
+
+            let owner = or_reject!(ctx, steam::authenticate(config, ticket));
+            if owner != Some(id) {
+                reject!(
+                    ctx,
+                    ErrorCode::InvalidTicket,
+                    "Steam didn't accept the auth ticket for this user"
+                );
+            }
+            verified = true;
+        }
+
+        let registered = or_reject!(
+            ctx,
+            (app.db).register_key(&body.user, &body.public_key, verified)
+        );
+        if !registered {
+            reject!(
+                ctx,
+                ErrorCode::KeyConflict,
+                "A different key is already registered for this user"
+            );
+        }
+
+        ctx.text("Key registered").send()?;
+        Ok(())
+    });
+}
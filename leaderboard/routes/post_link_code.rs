@@ -0,0 +1,34 @@
+use afire::{Content, Server, extensions::RouteShorthands};
+use bincode::Options;
+use common::consts::BINCODE_OPTIONS;
+use leaderboard::api::{
+    accounts::{LinkCodeResponse, PostLinkCode},
+    errors::ErrorCode,
+};
+use serde_json::json;
+
+use crate::{
+    app::App,
+    routes::{or_reject, reject},
+};
+
+pub fn attach(server: &mut Server<App>) {
+    server.post("/api/accounts/link-code", |ctx| {
+        let app = ctx.app();
+        let body = or_reject!(
+            ctx,
+            BINCODE_OPTIONS.deserialize::<PostLinkCode>(&ctx.req.body),
+            ErrorCode::BadRequest,
+            "Invalid request body"
+        );
+        let Some(account) = or_reject!(ctx, app.db.token_account(&body.token)) else {
+            reject!(ctx, ErrorCode::Forbidden, "Invalid account token");
+        };
+
+        let (code, expires) = or_reject!(ctx, app.db.create_link_code(account.id));
+        ctx.text(json!(LinkCodeResponse { code, expires }))
+            .content(Content::JSON)
+            .send()?;
+        Ok(())
+    });
+}
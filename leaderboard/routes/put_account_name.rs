@@ -0,0 +1,53 @@
+use afire::{Content, Server, extensions::RouteShorthands};
+use bincode::Options;
+use common::consts::BINCODE_OPTIONS;
+use leaderboard::api::{
+    accounts::{AccountResponse, PutName},
+    errors::ErrorCode,
+};
+use log::info;
+use serde_json::json;
+
+use crate::{
+    app::App,
+    routes::{or_reject, reject},
+};
+
+pub fn attach(server: &mut Server<App>) {
+    server.put("/api/accounts/name", |ctx| {
+        let app = ctx.app();
+        let body = or_reject!(
+            ctx,
+            BINCODE_OPTIONS.deserialize::<PutName>(&ctx.req.body),
+            ErrorCode::BadRequest,
+            "Invalid request body"
+        );
+        let Some(account) = or_reject!(ctx, app.db.token_account(&body.token)) else {
+            reject!(ctx, ErrorCode::Forbidden, "Invalid account token");
+        };
+
+        if account.name_locked {
+            reject!(
+                ctx,
+                ErrorCode::NameLocked,
+                "Your name has been locked by a moderator"
+            );
+        }
+
+        let name = match body.name.as_deref() {
+            Some(name) => Some(or_reject!(ctx, app.moderate_name(name))),
+            None => None,
+        };
+
+        // Logged so moderators can look over new names
+        or_reject!(ctx, app.db.set_name(account.id, name));
+        info!("Account {} changed their name to {name:?}", account.id);
+
+        let response = AccountResponse {
+            token: account.token,
+            name: name.map(|x| x.to_owned()),
+        };
+        ctx.text(json!(response)).content(Content::JSON).send()?;
+        Ok(())
+    });
+}
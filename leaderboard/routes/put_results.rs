@@ -1,108 +1,127 @@
 use std::{
     borrow::Cow,
-    collections::HashSet,
-    net::{IpAddr, Ipv4Addr},
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use afire::{
-    HeaderName, Server,
+    Server,
     extensions::{RealIp, RouteShorthands},
-    route::RouteContext,
 };
 use beam_logic::{
-    level::default::DEFAULT_LEVELS,
-    misc::price,
+    misc::{area, price},
     simulation::{level_state::LevelResult, runtime::testing::TestingSimulationState},
     tile::TILE_VERSION,
 };
 use bincode::Options;
 use common::consts::BINCODE_OPTIONS;
-use leaderboard::api::{hmac::verify, results::PutResults};
+use leaderboard::api::{errors::ErrorCode, results::PutResults};
 use serde_json::json;
-use uuid::Uuid;
 
-use crate::{app::App, database::results::Results};
+use crate::{
+    app::App,
+    database::results::Results,
+    middleware::rate_limit::retry_after,
+    routes::{level_param, or_reject, reject},
+};
 
 pub fn attach(server: &mut Server<App>) {
     server.put("/api/{level}/results", |ctx| {
-        let level_id = ctx.param_idx(0).parse::<Uuid>()?;
-
-        let hash = ctx
-            .req
-            .headers
-            .get(HeaderName::Authorization)
-            .context("Authorization not provided")?;
-
-        let hash = hex::decode(hash.as_bytes())?;
-        verify(&ctx.req.body, &hash).context("Invalid authorization")?;
+        let level_id = or_reject!(ctx, level_param(ctx));
 
         let app = ctx.app();
-        let body = BINCODE_OPTIONS.deserialize::<PutResults>(&ctx.req.body)?;
+        let body = or_reject!(
+            ctx,
+            BINCODE_OPTIONS.deserialize::<PutResults>(&ctx.req.body),
+            ErrorCode::BadRequest,
+            "Invalid request body"
+        );
+        or_reject!(ctx, app.authorize(&body.user, &ctx.req));
         if body.version != TILE_VERSION {
-            Err("Unsupported tile version!")?;
+            reject!(
+                ctx,
+                ErrorCode::UnsupportedTileVersion,
+                "Unsupported tile version, update your game to upload solutions"
+            );
         }
 
-        let level = DEFAULT_LEVELS
-            .iter()
-            .find(|x| x.id == level_id)
-            .context("Level not found")?;
-
-        // Verify that uploaded board is valid for the level. All permanent
-        // tiles should still be there unchanged and there should not be any
-        // emitters or detectors with IDs that were not defined in the level.
-        let mut ids = HashSet::new();
-        for (pos, tile) in body.board.iter() {
-            if let Some(id) = tile.id()
-                && !ids.insert(id)
-            {
-                Err("Extra dynamic tiles were added, solution rejected")?;
-            }
+        if let Err(retry) = app.upload_limit.check(body.user) {
+            ctx.header("Retry-After", retry_after(retry));
+            reject!(
+                ctx,
+                ErrorCode::RateLimited,
+                "Too many uploads, try again later"
+            );
+        }
 
-            if level.permanent.contains(&pos) && !level.tiles.get(pos).soft_eq(&tile) {
-                Err("Permanent tiles were modified, solution rejected")?;
-            }
+        if or_reject!(ctx, app.db.is_banned(&body.user)) {
+            reject!(
+                ctx,
+                ErrorCode::Banned,
+                "You have been banned from uploading solutions"
+            );
+        }
 
-            if level.out_of_bounds(pos) {
-                Err("Tiles placed outside the level area, solution rejected")?;
-            }
+        if body.board.tiles.len() > app.config.simulation.max_tiles {
+            reject!(
+                ctx,
+                ErrorCode::TooManyTiles,
+                "Board has too many tiles, solution rejected"
+            );
         }
 
-        let results = TestingSimulationState::new(
+        let level = or_reject!(ctx, app.upload_level(level_id));
+        or_reject!(ctx, level.validate(&body.board));
+        // Validating makes sure the area fits
+        let area = area(&body.board, level).unwrap_or(u32::MAX);
+
+        let timeout = Duration::from_millis(app.config.simulation.timeout_ms);
+        let start = Instant::now();
+        let mut simulation = TestingSimulationState::new(
             &body.board,
             Cow::Borrowed(level),
             app.config.simulation.max_ticks,
-        )
-        .run();
-
-        ctx.text(json!(results)).send()?;
-
+        );
+        let results = simulation.run_for(timeout);
+        (app.metrics).record_simulation(simulation.ticks(), start.elapsed());
+        let Some(results) = results else {
+            reject!(
+                ctx,
+                ErrorCode::OutOfTime,
+                "Simulation took too long, solution rejected"
+            );
+        };
+
+        // Saved before responding, so the client can retry if it fails
         if let LevelResult::Success { latency } = results {
-            let (cost, _count) = price(&body.board, level);
+            let (cost, tiles) = price(&body.board, level);
             let timestamp = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap()
                 .as_secs();
 
-            let ip_address = match ctx.req.real_ip() {
-                IpAddr::V4(ipv4_addr) => ipv4_addr,
-                IpAddr::V6(_) => Ipv4Addr::UNSPECIFIED,
-            };
-
-            app.db.insert_result(Results {
-                user_id: body.user,
-                ip_address,
-                timestamp,
-
-                level_id: level_id.into(),
-                cost,
-                latency,
-
-                solution: body.board,
-            })?;
-            app.db.update_histograms(level_id)?;
+            let change = or_reject!(
+                ctx,
+                app.db.insert_result(Results {
+                    user_id: body.user,
+                    ip_address: ctx.req.real_ip(),
+                    timestamp,
+
+                    level_id: level_id.into(),
+                    cost,
+                    latency,
+                    tiles: tiles as u32,
+                    area,
+
+                    solution: body.board,
+                })
+            );
+
+            if let Some(change) = change {
+                app.histograms.update(change);
+            }
         }
 
+        ctx.text(json!(results)).send()?;
         Ok(())
     });
 }
@@ -0,0 +1,72 @@
+//! Checks Steam auth tickets, which prove that a key is being registered by
+//! the Steam user it's for.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use leaderboard::api::signing::STEAM_TICKET_IDENTITY;
+use serde::Deserialize;
+use ureq::Agent;
+
+use crate::config::SteamConfig;
+
+const AUTHENTICATE_URL: &str =
+    "https://partner.steam-api.com/ISteamUserAuth/AuthenticateUserTicket/v1/";
+
+/// Checking a ticket holds up the thread handling the request, so Steam only
+/// gets a few seconds to respond before it counts as unreachable.
+const TIMEOUT: Duration = Duration::from_secs(4);
+
+#[derive(Deserialize)]
+struct AuthenticateResponse {
+    response: AuthenticateResult,
+}
+
+#[derive(Deserialize)]
+struct AuthenticateResult {
+    /// Missing if the ticket was rejected, in which case there's an error
+    /// instead.
+    params: Option<AuthenticateParams>,
+}
+
+#[derive(Deserialize)]
+struct AuthenticateParams {
+    result: String,
+    steamid: String,
+}
+
+/// Returns the id of the Steam user a hex encoded auth ticket belongs to, or
+/// None if Steam doesn't accept the ticket. Errors if Steam can't be reached.
+pub fn authenticate(config: &SteamConfig, ticket: &str) -> Result<Option<u64>> {
+    let agent = Agent::new_with_config(
+        Agent::config_builder()
+            .timeout_global(Some(TIMEOUT))
+            .http_status_as_error(false)
+            .build(),
+    );
+
+    let mut response = agent
+        .get(AUTHENTICATE_URL)
+        .query("key", &config.web_api_key)
+        .query("appid", config.app_id.to_string())
+        .query("ticket", ticket)
+        .query("identity", STEAM_TICKET_IDENTITY)
+        .call()
+        .context("Error reaching Steam")?;
+
+    let status = response.status();
+    if status.is_server_error() {
+        bail!("Steam responded with {status}");
+    } else if !status.is_success() {
+        return Ok(None);
+    }
+
+    let response = (response.body_mut())
+        .read_json::<AuthenticateResponse>()
+        .context("Error deserializing Steam response")?;
+    Ok(response
+        .response
+        .params
+        .filter(|x| x.result == "OK")
+        .and_then(|x| x.steamid.parse().ok()))
+}
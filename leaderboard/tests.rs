@@ -0,0 +1,145 @@
+//! Tests that send requests to a local instance of the server.
+
+use std::{
+    env, fs,
+    net::{TcpListener, TcpStream},
+    process, thread,
+    time::Duration,
+};
+
+use beam_logic::{
+    level::default::DEFAULT_LEVELS,
+    text,
+    tile::{TILE_VERSION, Tile},
+};
+use bincode::Options;
+use common::{
+    consts::{API_HMAC_KEY, BINCODE_OPTIONS},
+    map::Map,
+    user::UserId,
+};
+use leaderboard::api::{
+    errors::{ApiError, ErrorCode},
+    hmac,
+    results::PutResultsRef,
+};
+use ureq::{Agent, Body, http::Response};
+
+use crate::{app::App, server};
+
+/// Starts a server with a new database and the given limits, returning its
+/// address once it's accepting connections.
+fn start(name: &str, ip_requests: u32, user_uploads: u32, max_tiles: usize) -> String {
+    let dir = env::temp_dir().join(format!("leaderboard-{name}-{}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    // Grab a free port from the OS
+    let port = (TcpListener::bind("127.0.0.1:0").unwrap())
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let config = format!(
+        r#"
+        [server]
+        host = "127.0.0.1"
+        port = {port}
+        threads = 2
+        database_path = '{database}'
+
+        [simulation]
+        max_ticks = 500
+        max_tiles = {max_tiles}
+        timeout_ms = 2000
+
+        [rate_limit]
+        ip_requests = {ip_requests}
+        user_uploads = {user_uploads}
+
+        [histograms]
+        debounce_ms = 10
+        max_delay_ms = 100
+        bins = 12
+        log_scale = false
+        "#,
+        database = dir.join("data.db").display()
+    );
+    let config_path = dir.join("config.toml");
+    fs::write(&config_path, config).unwrap();
+
+    let app = App::new(config_path.to_str().unwrap(), false).unwrap();
+    thread::spawn(move || server(app).run());
+
+    let address = format!("127.0.0.1:{port}");
+    for _ in 0..100 {
+        if TcpStream::connect(&address).is_ok() {
+            return format!("http://{address}");
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    panic!("Server didn't start");
+}
+
+fn agent() -> Agent {
+    Agent::new_with_config(Agent::config_builder().http_status_as_error(false).build())
+}
+
+/// Uploads a board for the first campaign level, authorized with the HMAC key.
+fn upload(agent: &Agent, server: &str, user: &UserId, board: &Map<Tile>) -> Response<Body> {
+    let level = DEFAULT_LEVELS[0].id;
+    let body = BINCODE_OPTIONS
+        .serialize(&PutResultsRef {
+            user,
+            board,
+            version: TILE_VERSION,
+        })
+        .unwrap();
+
+    agent
+        .put(format!("{server}/api/{level}/results"))
+        .header(
+            "Authorization",
+            hex::encode(hmac::hash(API_HMAC_KEY, &body)),
+        )
+        .send(&body)
+        .unwrap()
+}
+
+fn error(mut response: Response<Body>) -> ErrorCode {
+    response.body_mut().read_json::<ApiError>().unwrap().code
+}
+
+#[test]
+fn ip_rate_limit() {
+    let server = start("ip_rate_limit", 3, 10, 2048);
+    let agent = agent();
+
+    for _ in 0..3 {
+        let response = agent.get(format!("{server}/health")).call().unwrap();
+        assert_eq!(response.status(), 200);
+    }
+
+    let response = agent.get(format!("{server}/health")).call().unwrap();
+    assert_eq!(response.status(), 429);
+    assert!(response.headers().contains_key("Retry-After"));
+    assert_eq!(error(response), ErrorCode::RateLimited);
+}
+
+#[test]
+fn board_size_and_upload_limits() {
+    let server = start("upload_limits", 100, 1, 4);
+    let agent = agent();
+    let user = UserId::Hardware(1);
+
+    let board = text::decode("origin 0 0\n## ## ## ## ##").unwrap();
+
+    let response = upload(&agent, &server, &user, &board);
+    assert_eq!(response.status(), 413);
+    assert_eq!(error(response), ErrorCode::TooManyTiles);
+
+    // Only one upload is allowed per minute
+    let response = upload(&agent, &server, &user, &board);
+    assert_eq!(response.status(), 429);
+    assert_eq!(error(response), ErrorCode::RateLimited);
+}
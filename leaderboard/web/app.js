@@ -0,0 +1,252 @@
+const HISTOGRAMS = ["cost", "latency", "tiles", "area"];
+const TOP_COUNT = 25;
+const TILE_SIZE = 24;
+
+const COLORS = {
+  background: "#2a2a3c",
+  grid: "#3a3a50",
+  tile: "#e0def4",
+  accent: "#e06c75",
+  wall: "#6e6a86",
+};
+
+// Clockwise angle of each direction, starting from up
+const ROTATION = { Up: 0, Right: 0.5, Down: 1, Left: 1.5 };
+
+let selected = null;
+
+async function getJson(path) {
+  const response = await fetch(path);
+  if (!response.ok) {
+    // Errors have a code and a message, except ones from outside the routes
+    const text = await response.text();
+    let message = text;
+    try {
+      message = JSON.parse(text).message ?? text;
+    } catch {}
+    throw new Error(message);
+  }
+  return response.json();
+}
+
+async function loadLevels() {
+  const { levels } = await getJson("/api/levels");
+  const list = document.getElementById("levels");
+
+  for (const level of levels) {
+    const item = document.createElement("li");
+    item.textContent = level.name;
+    item.classList.toggle("challenge", level.challenge);
+    item.addEventListener("click", () => {
+      list.querySelector(".selected")?.classList.remove("selected");
+      item.classList.add("selected");
+      selectLevel(level);
+    });
+    list.appendChild(item);
+  }
+}
+
+async function selectLevel(level) {
+  selected = level;
+  document.getElementById("level").hidden = false;
+  document.getElementById("solution").hidden = true;
+  document.getElementById("level-name").textContent = level.name;
+  document.getElementById("level-description").textContent = level.description;
+
+  const results = await getJson(`/api/${level.id}/results`);
+  const container = document.getElementById("histograms");
+  container.replaceChildren(
+    ...HISTOGRAMS.map((name) => drawHistogram(name, results[name])),
+  );
+
+  await loadRanking();
+}
+
+// The value at the start of a bin, undoing the scaling that the server used
+// when sorting values into bins.
+function binStart(histogram, bin) {
+  const t = bin / histogram.bins.length;
+  if (!histogram.log_scale) return Math.round(t * histogram.max);
+  return Math.round(Math.expm1(t * Math.log1p(histogram.max)));
+}
+
+function drawHistogram(name, histogram) {
+  const element = document.createElement("div");
+  element.className = "histogram";
+
+  const canvas = document.createElement("canvas");
+  canvas.width = 300;
+  canvas.height = 150;
+  const ctx = canvas.getContext("2d");
+
+  const peak = Math.max(1, ...histogram.bins);
+  const width = canvas.width / histogram.bins.length;
+  ctx.fillStyle = COLORS.accent;
+  histogram.bins.forEach((count, i) => {
+    const height = (count / peak) * (canvas.height - 4);
+    ctx.fillRect(i * width + 1, canvas.height - height, width - 2, height);
+  });
+
+  const label = document.createElement("p");
+  const scale = histogram.log_scale ? ", log scale" : "";
+  label.textContent = `${name}: 0 to ${histogram.max}${scale}`;
+
+  canvas.addEventListener("mousemove", (event) => {
+    const bin = Math.floor(event.offsetX / width);
+    const start = binStart(histogram, bin);
+    const end = binStart(histogram, bin + 1);
+    canvas.title = `${start} to ${end}: ${histogram.bins[bin]} solutions`;
+  });
+
+  element.append(label, canvas);
+  return element;
+}
+
+async function loadRanking() {
+  const ranking = document.getElementById("ranking").value;
+  const level = selected;
+  const { results, total } = await getJson(
+    `/api/${level.id}/top/${ranking}?count=${TOP_COUNT}`,
+  );
+  if (level !== selected) return;
+
+  document.getElementById("total").textContent = `${total} solutions`;
+  const body = document.getElementById("ranking-body");
+  body.replaceChildren(
+    ...results.map((result, index) => {
+      const row = document.createElement("tr");
+      for (const value of [result.rank, result.cost, result.latency]) {
+        const cell = document.createElement("td");
+        cell.textContent = value;
+        row.appendChild(cell);
+      }
+
+      const view = document.createElement("button");
+      view.textContent = "View";
+      view.addEventListener("click", () =>
+        loadSolution(level, ranking, index, result),
+      );
+      const cell = document.createElement("td");
+      cell.appendChild(view);
+      row.appendChild(cell);
+      return row;
+    }),
+  );
+}
+
+async function loadSolution(level, ranking, index, result) {
+  const container = document.getElementById("solution");
+  const title = document.getElementById("solution-title");
+  const canvas = document.getElementById("board");
+  container.hidden = false;
+
+  let tiles;
+  try {
+    ({ tiles } = await getJson(`/api/${level.id}/top/${ranking}/${index}`));
+  } catch (error) {
+    title.textContent = error.message;
+    canvas.hidden = true;
+    return;
+  }
+
+  title.textContent = `Rank ${result.rank} by ${ranking}, ${result.cost} cost, ${result.latency} latency`;
+  canvas.hidden = false;
+  drawBoard(canvas, tiles);
+}
+
+function drawBoard(canvas, tiles) {
+  const xs = tiles.map((x) => x.x);
+  const ys = tiles.map((x) => x.y);
+  const [minX, maxX] = [Math.min(0, ...xs), Math.max(0, ...xs)];
+  const [minY, maxY] = [Math.min(0, ...ys), Math.max(0, ...ys)];
+
+  canvas.width = (maxX - minX + 1) * TILE_SIZE;
+  canvas.height = (maxY - minY + 1) * TILE_SIZE;
+  const ctx = canvas.getContext("2d");
+
+  ctx.fillStyle = COLORS.background;
+  ctx.fillRect(0, 0, canvas.width, canvas.height);
+  ctx.strokeStyle = COLORS.grid;
+  for (let x = 0; x <= canvas.width; x += TILE_SIZE)
+    line(ctx, x, 0, x, canvas.height);
+  for (let y = 0; y <= canvas.height; y += TILE_SIZE)
+    line(ctx, 0, y, canvas.width, y);
+
+  // Positions grow upwards in game, but downwards on the canvas
+  for (const { x, y, tile } of tiles) {
+    ctx.save();
+    ctx.translate((x - minX + 0.5) * TILE_SIZE, (maxY - y + 0.5) * TILE_SIZE);
+    drawTile(ctx, tile);
+    ctx.restore();
+  }
+}
+
+// Tiles are drawn from the center, spanning from -1 to 1 before scaling.
+function drawTile(ctx, tile) {
+  const [kind, data] =
+    typeof tile === "string" ? [tile, {}] : Object.entries(tile)[0];
+  const half = TILE_SIZE / 2 - 3;
+
+  if (typeof data.rotation === "string")
+    ctx.rotate(ROTATION[data.rotation] * Math.PI);
+
+  ctx.strokeStyle = ctx.fillStyle = COLORS.tile;
+  ctx.lineWidth = 2;
+
+  switch (kind) {
+    case "Wall":
+      ctx.fillStyle = COLORS.wall;
+      ctx.fillRect(-half, -half, half * 2, half * 2);
+      break;
+    case "Mirror":
+    case "Splitter":
+      ctx.setLineDash(kind === "Splitter" ? [3, 3] : []);
+      if (data.rotation) line(ctx, -half, -half, half, half);
+      else line(ctx, -half, half, half, -half);
+      ctx.setLineDash([]);
+      break;
+    case "Emitter":
+      ctx.fillStyle = data.active ? COLORS.accent : COLORS.wall;
+      triangle(ctx, half);
+      break;
+    case "Galvo":
+      ctx.beginPath();
+      ctx.arc(0, 0, half * 0.6, 0, Math.PI * 2);
+      ctx.stroke();
+      triangle(ctx, half * 0.5);
+      break;
+    case "Detector":
+      ctx.strokeRect(-half, -half, half * 2, half * 2);
+      ctx.beginPath();
+      ctx.arc(0, 0, half * 0.4, 0, Math.PI * 2);
+      ctx.fill();
+      break;
+    default:
+      // Delays and chips are drawn as their first letter
+      ctx.strokeRect(-half, -half, half * 2, half * 2);
+      ctx.font = `${half * 1.4}px sans-serif`;
+      ctx.textAlign = "center";
+      ctx.textBaseline = "middle";
+      ctx.fillText(kind[0], 0, 0);
+  }
+}
+
+// An arrow pointing up, which becomes the tile's direction once rotated.
+function triangle(ctx, size) {
+  ctx.beginPath();
+  ctx.moveTo(0, -size);
+  ctx.lineTo(size * 0.8, size);
+  ctx.lineTo(-size * 0.8, size);
+  ctx.closePath();
+  ctx.fill();
+}
+
+function line(ctx, x1, y1, x2, y2) {
+  ctx.beginPath();
+  ctx.moveTo(x1, y1);
+  ctx.lineTo(x2, y2);
+  ctx.stroke();
+}
+
+document.getElementById("ranking").addEventListener("change", loadRanking);
+loadLevels();